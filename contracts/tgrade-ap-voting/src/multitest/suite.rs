@@ -349,7 +349,7 @@ impl Suite {
         let amount = self
             .app
             .wrap()
-            .query_balance(&Addr::unchecked(owner), denom)?
+            .query_balance(Addr::unchecked(owner), denom)?
             .amount;
         Ok(amount.into())
     }
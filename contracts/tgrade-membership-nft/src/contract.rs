@@ -0,0 +1,215 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+use cw2::set_contract_version;
+use cw721_base::Cw721Contract;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, Metadata, MintMsg, QueryMsg};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:tgrade-membership-nft";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// cw721-base storage and queries, with `Metadata` as the token extension
+pub type MembershipNft<'a> = Cw721Contract<'a, Metadata, Empty, Empty, Empty>;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    MembershipNft::default().instantiate(deps.branch(), env, info, msg)?;
+    // Overrides the cw721-base version
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Mint(msg) => execute_mint(deps, env, info, msg),
+        ExecuteMsg::UpdateMetadata {
+            token_id,
+            extension,
+        } => execute_update_metadata(deps, info, token_id, extension),
+        ExecuteMsg::Burn { token_id } => execute_burn(deps, info, token_id),
+    }
+}
+
+fn ensure_minter(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let minter = MembershipNft::default().minter.load(deps.storage)?;
+    if info.sender != minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn execute_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: MintMsg<Metadata>,
+) -> Result<Response, ContractError> {
+    Ok(MembershipNft::default().mint(deps, env, info, msg)?)
+}
+
+fn execute_update_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    extension: Metadata,
+) -> Result<Response, ContractError> {
+    ensure_minter(deps.as_ref(), &info)?;
+
+    MembershipNft::default()
+        .tokens
+        .update(deps.storage, &token_id, |token| match token {
+            Some(mut token) => {
+                token.extension = extension.clone();
+                Ok(token)
+            }
+            None => Err(StdError::not_found("token")),
+        })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_metadata")
+        .add_attribute("token_id", token_id)
+        .add_attribute("status", extension.status))
+}
+
+fn execute_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    ensure_minter(deps.as_ref(), &info)?;
+
+    let tract = MembershipNft::default();
+    let token = tract.tokens.load(deps.storage, &token_id)?;
+    tract.tokens.remove(deps.storage, &token_id)?;
+    tract.decrement_tokens(deps.storage)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("owner", token.owner)
+        .add_attribute("token_id", token_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    MembershipNft::default().query(deps, env, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps};
+    use cw721::{NftInfoResponse, NumTokensResponse};
+
+    use super::*;
+
+    const MINTER: &str = "circle";
+    const MEMBER: &str = "member";
+
+    fn metadata(status: &str) -> Metadata {
+        Metadata {
+            status: status.to_owned(),
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            name: "Circle membership".to_owned(),
+            symbol: "CIRCLE".to_owned(),
+            minter: MINTER.to_owned(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Mint(MintMsg {
+            token_id: MEMBER.to_owned(),
+            owner: MEMBER.to_owned(),
+            token_uri: None,
+            extension: metadata("Voting"),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn status(deps: Deps) -> String {
+        let msg = QueryMsg::NftInfo {
+            token_id: MEMBER.to_owned(),
+        };
+        let info: NftInfoResponse<Metadata> =
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        info.extension.status
+    }
+
+    #[test]
+    fn only_minter_can_mint() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Mint(MintMsg {
+            token_id: "other".to_owned(),
+            owner: "other".to_owned(),
+            token_uri: None,
+            extension: metadata("Voting"),
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info(MEMBER, &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Cw721(cw721_base::ContractError::Unauthorized {})
+        );
+        assert_eq!(status(deps.as_ref()), "Voting");
+    }
+
+    #[test]
+    fn minter_updates_metadata() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::UpdateMetadata {
+            token_id: MEMBER.to_owned(),
+            extension: metadata("Leaving"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MEMBER, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg).unwrap();
+        assert_eq!(status(deps.as_ref()), "Leaving");
+    }
+
+    #[test]
+    fn only_minter_can_burn() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Burn {
+            token_id: MEMBER.to_owned(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MEMBER, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+        let tokens: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(tokens.count, 0);
+    }
+}
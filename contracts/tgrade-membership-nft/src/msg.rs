@@ -0,0 +1,28 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Empty;
+
+pub use cw721_base::{InstantiateMsg, MintMsg};
+
+/// Extension of every membership token
+#[cw_serde]
+pub struct Metadata {
+    /// Status of the member in the trusted circle, as displayed by it (ie. "Voting")
+    pub status: String,
+}
+
+/// Tokens cannot be transferred, sent or approved, so all messages are reserved to the minter
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Mints a new membership token
+    Mint(MintMsg<Metadata>),
+    /// Replaces the metadata of an existing token
+    UpdateMetadata {
+        token_id: String,
+        extension: Metadata,
+    },
+    /// Burns a token, whoever holds it
+    Burn { token_id: String },
+}
+
+/// All the cw721 queries. Approvals and operators are always empty
+pub type QueryMsg = cw721_base::QueryMsg<Empty>;
@@ -10,7 +10,7 @@ use tg_voting_contract::ContractError as VotingError;
 
 #[test]
 fn only_voters_can_propose() {
-    let members = vec!["owner", "voter1", "voter2", "voter3"];
+    let members = ["owner", "voter1", "voter2", "voter3"];
 
     let rules = RulesBuilder::new()
         .with_threshold(Decimal::percent(51))
@@ -77,7 +77,7 @@ fn only_voters_can_propose() {
 
 #[test]
 fn grant_engagement_reward() {
-    let members = vec!["owner", "voter1", "voter2", "voter3"];
+    let members = ["owner", "voter1", "voter2", "voter3"];
 
     let rules = RulesBuilder::new()
         .with_threshold(Decimal::percent(50))
@@ -150,7 +150,7 @@ fn grant_engagement_reward() {
 
 #[test]
 fn execute_group_can_change() {
-    let members = vec!["owner", "voter1", "voter2", "voter3"];
+    let members = ["owner", "voter1", "voter2", "voter3"];
 
     let rules = RulesBuilder::new()
         .with_threshold(Decimal::percent(51))
@@ -232,7 +232,7 @@ fn execute_group_can_change() {
 
 #[test]
 fn close_proposal() {
-    let members = vec!["owner", "voter1"];
+    let members = ["owner", "voter1"];
 
     let rules = RulesBuilder::new()
         .with_threshold(Decimal::percent(51))
@@ -279,7 +279,7 @@ mod voting {
 
     #[test]
     fn casting_votes() {
-        let members = vec!["owner", "voter1", "voter2", "voter3", "voter4"];
+        let members = ["owner", "voter1", "voter2", "voter3", "voter4"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(51))
@@ -362,7 +362,7 @@ mod voting {
 
     #[test]
     fn expired_proposals_cannot_be_voted() {
-        let members = vec!["owner", "voter1"];
+        let members = ["owner", "voter1"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(51))
@@ -392,7 +392,7 @@ mod voting {
 
     #[test]
     fn veto_doesnt_affect_tally() {
-        let members = vec!["owner", "voter1", "voter2", "voter3"];
+        let members = ["owner", "voter1", "voter2", "voter3"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(51))
@@ -431,7 +431,7 @@ mod voting {
 
     #[test]
     fn query_individual_votes() {
-        let members = vec!["owner", "voter1", "voter2"];
+        let members = ["owner", "voter1", "voter2"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(51))
@@ -483,7 +483,7 @@ mod voting {
 
     #[test]
     fn executing_slashing_proposals_works() {
-        let members = vec!["owner", "voter1", "voter2"];
+        let members = ["owner", "voter1", "voter2"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(50))
@@ -528,7 +528,7 @@ mod voting {
 
     #[test]
     fn executing_punishment_proposals_works() {
-        let members = vec!["owner", "voter1", "voter2"];
+        let members = ["owner", "voter1", "voter2"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(50))
@@ -589,7 +589,7 @@ mod voting {
 
     #[test]
     fn unjail_through_proposal() {
-        let members = vec!["owner", "voter1", "voter2"];
+        let members = ["owner", "voter1", "voter2"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(50))
@@ -644,7 +644,7 @@ mod voting {
 
     #[test]
     fn update_valset() {
-        let members = vec!["owner", "voter1", "voter2"];
+        let members = ["owner", "voter1", "voter2"];
 
         let rules = RulesBuilder::new()
             .with_threshold(Decimal::percent(50))
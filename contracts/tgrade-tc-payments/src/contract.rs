@@ -151,7 +151,7 @@ pub fn execute_distribute_rewards<Q: CustomQuery>(
         .add_attribute("action", "distribute_rewards")
         .add_attribute("sender", sender.as_str())
         .add_attribute("denom", &denom)
-        .add_attribute("amount", &funds_to_distribute.to_string())
+        .add_attribute("amount", funds_to_distribute.to_string())
         .add_submessage(distribute_msg);
 
     Ok(resp)
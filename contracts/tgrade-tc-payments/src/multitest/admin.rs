@@ -49,7 +49,7 @@ fn admin_can_disable_admin() {
 
 #[test]
 fn non_admin_cannot_change_admin() {
-    let members = vec!["member1", "member2", "member3", "member4"];
+    let members = ["member1", "member2", "member3", "member4"];
 
     let mut suite = SuiteBuilder::new()
         // .with_operators(&members)
@@ -2,8 +2,8 @@ use super::suite::SuiteBuilder;
 
 #[test]
 fn simple_distribution() {
-    let oc = vec!["oc1", "oc2"];
-    let ap = vec!["ap1"];
+    let oc = ["oc1", "oc2"];
+    let ap = ["ap1"];
     let mut suite = SuiteBuilder::new()
         .with_epoch_reward(10_000u128)
         .with_distribute_ratio(80)
@@ -62,8 +62,8 @@ fn simple_distribution() {
 
 #[test]
 fn more_users_distribution() {
-    let oc = vec!["oc1", "oc2"];
-    let ap = vec!["ap1", "ap2", "ap3", "ap4"];
+    let oc = ["oc1", "oc2"];
+    let ap = ["ap1", "ap2", "ap3", "ap4"];
     let mut suite = SuiteBuilder::new()
         .with_epoch_reward(2000u128)
         .with_distribute_ratio(50)
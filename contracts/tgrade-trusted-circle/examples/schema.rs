@@ -5,8 +5,12 @@ use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, s
 
 pub use tg4::{MemberListResponse, MemberResponse, TotalPointsResponse};
 pub use tgrade_trusted_circle::msg::{
-    EscrowListResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, ProposalListResponse,
-    ProposalResponse, QueryMsg, TrustedCircleResponse, VoteListResponse, VoteResponse,
+    CommitteeListResponse, CommitteeResponse, ContractPermissionsResponse, DelegatedResponse,
+    DraftListResponse, DraftResponse, EscrowClaimsResponse, EscrowHistoryResponse,
+    EscrowListResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, MembersAtHeightResponse,
+    PendingWorkResponse, ProposalListResponse, ProposalResponse, ProposalTallyResponse, QueryMsg,
+    RewardWeightsResponse, SpendListResponse, StateSnapshot, TreasuryResponse,
+    TrustedCircleResponse, VoteListResponse, VoteResponse, VotingKeyResponse,
 };
 pub use tgrade_trusted_circle::state::ProposalContent;
 
@@ -23,11 +27,27 @@ fn main() {
     export_schema(&schema_for!(TrustedCircleResponse), &out_dir);
     export_schema_with_title(&schema_for!(EscrowResponse), &out_dir, "EscrowResponse");
     export_schema(&schema_for!(MemberListResponse), &out_dir);
+    export_schema(&schema_for!(MembersAtHeightResponse), &out_dir);
     export_schema(&schema_for!(MemberResponse), &out_dir);
     export_schema(&schema_for!(TotalPointsResponse), &out_dir);
     export_schema(&schema_for!(ProposalResponse), &out_dir);
     export_schema(&schema_for!(ProposalListResponse), &out_dir);
+    export_schema(&schema_for!(ProposalTallyResponse), &out_dir);
     export_schema(&schema_for!(VoteResponse), &out_dir);
     export_schema(&schema_for!(VoteListResponse), &out_dir);
     export_schema(&schema_for!(EscrowListResponse), &out_dir);
+    export_schema(&schema_for!(EscrowHistoryResponse), &out_dir);
+    export_schema(&schema_for!(EscrowClaimsResponse), &out_dir);
+    export_schema(&schema_for!(PendingWorkResponse), &out_dir);
+    export_schema(&schema_for!(DelegatedResponse), &out_dir);
+    export_schema(&schema_for!(RewardWeightsResponse), &out_dir);
+    export_schema(&schema_for!(TreasuryResponse), &out_dir);
+    export_schema(&schema_for!(ContractPermissionsResponse), &out_dir);
+    export_schema(&schema_for!(DraftResponse), &out_dir);
+    export_schema(&schema_for!(DraftListResponse), &out_dir);
+    export_schema(&schema_for!(CommitteeResponse), &out_dir);
+    export_schema(&schema_for!(CommitteeListResponse), &out_dir);
+    export_schema(&schema_for!(SpendListResponse), &out_dir);
+    export_schema(&schema_for!(VotingKeyResponse), &out_dir);
+    export_schema(&schema_for!(StateSnapshot), &out_dir);
 }
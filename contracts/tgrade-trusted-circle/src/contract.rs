@@ -1,33 +1,62 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, to_vec, Addr, BankMsg, Binary, BlockInfo, ContractResult, CustomQuery, Deps,
-    DepsMut, Empty, Env, Event, MessageInfo, Order, QuerierWrapper, QueryRequest, StdError,
-    StdResult, SystemError, SystemResult, Uint128, WasmQuery,
+    coin, coins, to_binary, to_vec, Addr, BankMsg, Binary, BlockInfo, Coin, ContractResult,
+    CustomQuery, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, QuerierWrapper,
+    QueryRequest, Reply, StdError, StdResult, Storage, SystemError, SystemResult, Timestamp,
+    Uint128, WasmQuery,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
-use cw_utils::{ensure_from_older_version, maybe_addr, Expiration};
+use cw_utils::{ensure_from_older_version, maybe_addr, Expiration, PaymentError};
+use sha2::{Digest, Sha256};
 use tg3::{Status, Vote};
 use tg4::{
     member_key, Member, MemberInfo, MemberListResponse, MemberResponse, TotalPointsResponse,
 };
-use tg_bindings::{TgradeMsg, TgradeQuery};
-use tg_utils::{members, TOTAL};
+use tg_bindings::{
+    request_privileges, Privilege, PrivilegeChangeMsg, TgradeMsg, TgradeQuery, TgradeSudoMsg,
+};
+use tg_utils::members;
 use tg_voting_contract::ballots::ballots;
 
 use crate::error::ContractError;
-use crate::migration::migrate_proposals;
+use crate::membership::{
+    instantiate_membership_nft, membership_nft_instantiated, membership_token_msgs,
+    MEMBERSHIP_NFT_REPLY_ID,
+};
+use crate::migration::{
+    migrate_escrow_totals, migrate_proposals, migrate_proposals_by_expiry, migrate_reward_total,
+    migrate_total_snapshot, migrate_voting_key_owners,
+};
 use crate::msg::{
-    Escrow, EscrowListResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, ProposalListResponse,
-    ProposalResponse, QueryMsg, RewardsResponse, RulesResponse, TrustedCircleResponse, VoteInfo,
-    VoteListResponse, VoteResponse,
+    CommitteeListResponse, CommitteeResponse, ContractPermissionsResponse, DelegatedResponse,
+    DraftListResponse, DraftResponse, Escrow, EscrowClaim, EscrowClaimsResponse,
+    EscrowHistoryEntry, EscrowHistoryResponse, EscrowListResponse, EscrowResponse, ExecuteMsg,
+    InstantiateMsg, MembersAtHeightResponse, PendingWorkResponse, ProposalListResponse,
+    ProposalResponse, ProposalTallyResponse, QueryMsg, RewardWeightsResponse, RewardsResponse,
+    RulesResponse, SignedVote, SnapshotEntry, SnapshotHeader, SnapshotKey, SpendInfo,
+    SpendListResponse, StateSnapshot, TreasuryResponse, TrustedCircleResponse, VoteCommitment,
+    VoteInfo, VoteListResponse, VoteResponse, VoteSignDoc, VotingKeyResponse, SNAPSHOT_VERSION,
+};
+use crate::poll;
+use crate::stake::{
+    bond_escrow, burn_escrow, claim_escrow, claim_unbonded, release_escrow, take_unbonded_slash,
+    unbond_escrow,
 };
 use crate::state::MemberStatus::NonVoting;
 use crate::state::{
-    batches, create_batch, create_proposal, Batch, EscrowStatus, MemberStatus, Proposal,
-    ProposalContent, Punishment, TrustedCircle, TrustedCircleAdjustments, Votes, VotingRules,
-    DISTRIBUTION, ESCROWS, PROPOSALS, PROPOSAL_BY_EXPIRY, TRUSTED_CIRCLE,
+    add_to_batch, batches, create_batch, create_proposal, drafts, ensure_not_importing,
+    ensure_not_paused, escrow_deposited, escrow_released, escrow_total, payment_deadline,
+    record_escrow, reserved_funds, spends, track_membership, treasury_balance, votes_needed, Batch,
+    Committee, ContractPermissions, Draft, EscrowAction, EscrowChange, EscrowStatus, Import,
+    ImportTotals, MemberStatus, Proposal, ProposalContent, ProposalKind, Punishment, SecretBallot,
+    Spend, TrustedCircle, TrustedCircleAdjustments, UnrevealedVotes, UrgentRules, VoteCommit,
+    Votes, VotingRules, CLOSED_PROPOSALS_CURSOR, COMMITTEES, CONTRACT_PERMISSIONS, DISTRIBUTION,
+    DRAFT_COUNT, ESCROWS, ESCROW_CHANGE, ESCROW_CLAIMS, ESCROW_LEDGER, EXPIRED_BATCHES_CURSOR,
+    IMPORT, MEMBERSHIP_NFT, PAUSED_UNTIL, POLL_BALLOTS, PROPOSALS, PROPOSAL_BY_EXPIRY,
+    PROPOSAL_COUNT, REWARD_TOTAL, REWARD_WEIGHTS, TOTAL, TREASURY, TRUSTED_CIRCLE,
+    URGENT_PROPOSALS, VOTE_COMMITS, VOTE_NONCES, VOTING_KEYS, VOTING_KEY_OWNERS,
 };
 
 // version info for migration info
@@ -66,58 +95,152 @@ pub fn instantiate(
             .map(|addr| deps.api.addr_validate(&addr))
             .transpose()?,
         edit_trusted_circle_disabled: msg.edit_trusted_circle_disabled,
+        payment_deadline: msg.payment_deadline,
+        remove_expired_pending: msg.remove_expired_pending,
+        urgent_rules: msg.urgent_rules,
+        secret_rules: msg.secret_rules,
+        guardian: msg
+            .guardian
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
+        draft_period: msg.draft_period,
+        stake_contract: msg
+            .stake_contract
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
     };
     trusted_circle.validate()?;
 
-    // Store sender as initial member, and define its points / state
-    // based on init_funds
+    // Add metadata for identification / indexing
+    let contract_data_ev = Event::new(METADATA)
+        .add_attribute("contract_kind", CONTRACT_NAME)
+        .add_attribute("name", msg.name);
+    let vesting_period = msg.reward_vesting_period.unwrap_or_default() as u64 * 86_400;
+    let membership_nft = msg
+        .membership_nft
+        .map(|init| instantiate_membership_nft(&env, init))
+        .transpose()?;
+
+    // Members are imported afterwards, see `execute_import_state`
+    if let Some(importer) = msg.importer {
+        cw_utils::nonpayable(&info)?;
+        if !msg.initial_members.is_empty() || !msg.initial_voters.is_empty() {
+            return Err(ContractError::InvalidSnapshot(
+                "Initial members are not allowed when importing the state".to_owned(),
+            ));
+        }
+        if trusted_circle.stake_contract.is_some() {
+            return Err(ContractError::InvalidSnapshot(
+                "Escrow cannot be bonded when importing the state".to_owned(),
+            ));
+        }
+        TRUSTED_CIRCLE.save(deps.storage, &trusted_circle)?;
+        DISTRIBUTION.init(deps.branch(), msg.reward_denom, vesting_period)?;
+        REWARD_WEIGHTS.save(deps.storage, &msg.reward_weights.unwrap_or_default())?;
+        TOTAL.save(deps.storage, &0, env.block.height)?;
+        let import = Import {
+            importer: deps.api.addr_validate(&importer)?,
+            expected: None,
+            imported: ImportTotals::default(),
+        };
+        IMPORT.save(deps.storage, &import)?;
+        return Ok(Response::default()
+            .add_submessages(membership_nft)
+            .add_event(contract_data_ev));
+    }
+
+    // Store sender and initial voters as voting members. Initial voters pay exactly the
+    // escrow, and so does the sender along with them. Alone, the sender is left with the
+    // rest of init_funds
+    let mut voters = vec![(info.sender.clone(), Uint128::zero())];
+    for voter in &msg.initial_voters {
+        let voter = ensure_not_denied(deps.as_ref(), &trusted_circle, voter)?;
+        if voters.iter().any(|(addr, _)| *addr == voter) {
+            return Err(ContractError::DuplicateInitialVoter(voter.into_string()));
+        }
+        voters.push((voter, trusted_circle.escrow_amount));
+    }
     let amount = cw_utils::must_pay(&info, &msg.denom)?;
-    if amount < trusted_circle.get_escrow() {
+    let voters_escrow =
+        trusted_circle.escrow_amount * Uint128::from(msg.initial_voters.len() as u64);
+    let sender_escrow = amount
+        .checked_sub(voters_escrow)
+        .map_err(|_| ContractError::InsufficientFunds(amount))?;
+    if sender_escrow < trusted_circle.get_escrow() {
         return Err(ContractError::InsufficientFunds(amount));
     }
+    if !msg.initial_voters.is_empty() && sender_escrow > trusted_circle.escrow_amount {
+        return Err(ContractError::ExcessFunds(amount));
+    }
+    voters[0].1 = sender_escrow;
 
     // Create the TRUSTED_CIRCLE
     TRUSTED_CIRCLE.save(deps.storage, &trusted_circle)?;
 
-    // Put sender funds in escrow
-    let escrow = EscrowStatus {
-        paid: amount,
-        status: MemberStatus::Voting {},
-    };
-    ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+    DISTRIBUTION.init(deps.branch(), msg.reward_denom, vesting_period)?;
+    REWARD_WEIGHTS.save(deps.storage, &msg.reward_weights.unwrap_or_default())?;
 
-    members().save(
+    // Put voters funds in escrow
+    let mut promote_evs = vec![];
+    for (voter, amount) in &voters {
+        let escrow = EscrowStatus {
+            paid: *amount,
+            status: MemberStatus::Voting {},
+        };
+        ESCROWS.save(deps.storage, voter, &escrow)?;
+        escrow_deposited(deps.storage, &msg.denom, *amount)?;
+        record_escrow(
+            deps.storage,
+            &env.block,
+            voter,
+            EscrowAction::Deposit { amount: *amount },
+            Some(&escrow),
+        )?;
+
+        members().save(
+            deps.storage,
+            voter,
+            &MemberInfo::new(VOTING_POINTS),
+            env.block.height,
+        )?;
+        update_reward_points(deps.branch(), &env.block, voter, None, Some(escrow.status))?;
+        promote_evs.push(Event::new(PROMOTE_TYPE).add_attribute(MEMBER_KEY, voter));
+    }
+    TOTAL.save(
         deps.storage,
-        &info.sender,
-        &MemberInfo::new(VOTING_POINTS),
+        &(VOTING_POINTS * voters.len() as u64),
         env.block.height,
     )?;
-    TOTAL.save(deps.storage, &VOTING_POINTS)?;
-    let promote_ev = Event::new(PROMOTE_TYPE).add_attribute(MEMBER_KEY, info.sender);
-
-    DISTRIBUTION.init(deps.branch(), msg.reward_denom)?;
 
     // add all members
     let add_evs = add_remove_non_voting_members(
         deps,
         &trusted_circle,
-        env.block.height,
+        &env.block,
         msg.initial_members,
         vec![],
     )?;
-    // Add metadata for identification / indexing
-    let contract_data_ev = Event::new(METADATA)
-        .add_attribute("contract_kind", CONTRACT_NAME)
-        .add_attribute("name", msg.name);
     Ok(Response::default()
+        .add_messages(bond_escrow(&trusted_circle, amount)?)
+        .add_submessages(membership_nft)
         .add_event(contract_data_ev)
         .add_events(add_evs)
-        .add_event(promote_ev))
+        .add_events(promote_evs))
 }
 
 // And declare a custom Error variant for the ones where you will want to make use of it
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut<TgradeQuery>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let res = execute_msg(deps.branch(), env, info, msg)?;
+    Ok(res.add_messages(membership_token_msgs(deps.storage)?))
+}
+
+fn execute_msg(
     deps: DepsMut<TgradeQuery>,
     env: Env,
     info: MessageInfo,
@@ -125,30 +248,100 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     use ExecuteMsg::*;
 
+    ensure_contract_permitted(deps.storage, &info.sender, &msg)?;
+    if !matches!(msg, ImportState { .. } | FinishImport {}) {
+        ensure_not_importing(deps.storage)?;
+    }
+
     match msg {
         DepositEscrow {} => execute_deposit_escrow(deps, env, info),
         ReturnEscrow {} => execute_return_escrow(deps, env, info),
+        ClaimEscrow {} => execute_claim_escrow(deps, env, info),
         Propose {
             title,
             description,
             proposal,
-        } => execute_propose(deps, env, info, title, description, proposal),
+            urgent,
+            secret,
+        } => execute_propose(
+            deps,
+            env,
+            info,
+            title,
+            description,
+            proposal,
+            urgent,
+            secret,
+        ),
+        ProposeToCommittee {
+            committee,
+            title,
+            description,
+            proposal,
+        } => execute_propose_to_committee(deps, env, info, committee, title, description, proposal),
+        CreateDraft {
+            title,
+            description,
+            proposal,
+            urgent,
+            secret,
+        } => execute_create_draft(
+            deps,
+            env,
+            info,
+            title,
+            description,
+            proposal,
+            urgent,
+            secret,
+        ),
+        AmendDraft {
+            draft_id,
+            title,
+            description,
+            proposal,
+        } => execute_amend_draft(deps, env, info, draft_id, title, description, proposal),
+        CosponsorDraft { draft_id } => execute_cosponsor_draft(deps, env, info, draft_id),
+        PublishDraft { draft_id } => execute_publish_draft(deps, env, info, draft_id),
         Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
         Execute { proposal_id } => execute_execute(deps, env, info, proposal_id),
         Close { proposal_id } => execute_close(deps, env, info, proposal_id),
         LeaveTrustedCircle {} => execute_leave_trusted_circle(deps, env, info),
         CheckPending {} => execute_check_pending(deps, env, info),
+        Pause { hours } => execute_pause(deps, env, info, hours),
 
         DistributeRewards {} => execute_distribute_funds(deps, env, info),
-        WithdrawRewards {} => execute_withdraw_funds(deps, info),
+        WithdrawRewards { owner, receiver } => {
+            execute_withdraw_funds(deps, env, info, owner, receiver)
+        }
+        DelegateWithdrawal { delegate } => execute_delegate_withdrawal(deps, info, delegate),
+        UndelegateWithdrawal {} => execute_undelegate_withdrawal(deps, info),
+        FundTreasury {} => execute_fund_treasury(deps, info),
+        ClaimSpend { spend_id } => execute_claim_spend(deps, env, info, spend_id),
+        VotePoll {
+            proposal_id,
+            choices,
+        } => execute_vote_poll(deps, env, info, proposal_id, choices),
+        CommitVote { proposal_id, hash } => execute_commit_vote(deps, env, info, proposal_id, hash),
+        RevealVote {
+            proposal_id,
+            vote,
+            salt,
+        } => execute_reveal_vote(deps, env, info, proposal_id, vote, salt),
+        RegisterVotingKey { pubkey } => execute_register_voting_key(deps, info, pubkey),
+        SubmitSignedVotes { votes } => execute_submit_signed_votes(deps, env, info, votes),
+        ImportState { snapshot } => execute_import_state(deps, env, info, snapshot),
+        FinishImport {} => execute_finish_import(deps, env, info),
     }
 }
 
 pub fn execute_deposit_escrow<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+    mut deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
+    ensure_not_paused(deps.storage, &env.block)?;
+
     // They must be a member and an allowed status to pay in
     let mut escrow = ESCROWS
         .may_load(deps.storage, &info.sender)?
@@ -159,8 +352,12 @@ pub fn execute_deposit_escrow<Q: CustomQuery>(
     // update the amount
     let amount = cw_utils::must_pay(&info, &trusted_circle.denom)?;
     escrow.paid += amount;
+    escrow_deposited(deps.storage, &trusted_circle.denom, amount)?;
+
+    let deposit = EscrowAction::Deposit { amount };
 
     let mut res = Response::new()
+        .add_messages(bond_escrow(&trusted_circle, amount)?)
         .add_attribute("action", "deposit_escrow")
         .add_attribute("sender", &info.sender)
         .add_attribute("amount", amount.to_string());
@@ -168,11 +365,32 @@ pub fn execute_deposit_escrow<Q: CustomQuery>(
     // check to see if we update the pending status
     match escrow.status {
         MemberStatus::Pending { proposal_id: batch } => {
+            // unpaid pending members cannot pay in anymore after the payment deadline
+            if let Some(deadline) = payment_deadline(deps.storage, &trusted_circle, batch)? {
+                if env.block.time.seconds() >= deadline {
+                    return Err(ContractError::PaymentDeadlinePassed(deadline));
+                }
+            }
             let required_escrow = trusted_circle.get_escrow();
             if escrow.paid >= required_escrow {
                 // If we paid enough, we can move into Paid, Pending Voter
+                let old_status = escrow.status;
                 escrow.status = MemberStatus::PendingPaid { proposal_id: batch };
                 ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+                update_reward_points(
+                    deps.branch(),
+                    &env.block,
+                    &info.sender,
+                    Some(old_status),
+                    Some(escrow.status),
+                )?;
+                record_escrow(
+                    deps.storage,
+                    &env.block,
+                    &info.sender,
+                    deposit,
+                    Some(&escrow),
+                )?;
                 // Now check if this batch is ready...
                 if let Some(event) = update_batch_after_escrow_paid(deps, env, batch, &info.sender)?
                 {
@@ -181,11 +399,25 @@ pub fn execute_deposit_escrow<Q: CustomQuery>(
             } else {
                 // Otherwise, just update the paid value until later
                 ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+                record_escrow(
+                    deps.storage,
+                    &env.block,
+                    &info.sender,
+                    deposit,
+                    Some(&escrow),
+                )?;
             }
             Ok(res)
         }
         MemberStatus::PendingPaid { .. } | MemberStatus::Voting {} => {
             ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+            record_escrow(
+                deps.storage,
+                &env.block,
+                &info.sender,
+                deposit,
+                Some(&escrow),
+            )?;
             Ok(res)
         }
         _ => Err(ContractError::InvalidStatus(escrow.status)),
@@ -214,13 +446,11 @@ fn update_batch_after_escrow_paid<Q: CustomQuery>(
         (true, true) => {
             batches().save(deps.storage, proposal_id, &batch)?;
             // just promote this one, everyone else has been promoted
-            if convert_to_voter_if_paid(deps.branch(), paid_escrow, height)? {
+            if convert_to_voter_if_paid(deps.branch(), paid_escrow, &env.block)? {
                 // update the total with the new points
-                TOTAL.update::<_, StdError>(deps.storage, |old| Ok(old + VOTING_POINTS))?;
-                DISTRIBUTION.apply_points_correction(
-                    deps.branch(),
-                    &[(paid_escrow, VOTING_POINTS as i128)],
-                )?;
+                TOTAL.update::<_, StdError>(deps.storage, height, |old| {
+                    Ok(old.unwrap_or_default() + VOTING_POINTS)
+                })?;
                 let evt = Event::new(PROMOTE_TYPE)
                     .add_attribute(PROPOSAL_KEY, proposal_id.to_string())
                     .add_attribute(MEMBER_KEY, paid_escrow);
@@ -230,8 +460,26 @@ fn update_batch_after_escrow_paid<Q: CustomQuery>(
             }
         }
         (true, false) => {
-            let evt =
-                convert_all_paid_members_to_voters(deps.branch(), proposal_id, &mut batch, height)?;
+            // If promoting the batch was already split over several calls, this member may have
+            // been checked (and skipped) before, so we promote them first
+            batches().save(deps.storage, proposal_id, &batch)?;
+            let paid_promoted = convert_to_voter_if_paid(deps.branch(), paid_escrow, &env.block)?;
+            if paid_promoted {
+                TOTAL.update::<_, StdError>(deps.storage, height, |old| {
+                    Ok(old.unwrap_or_default() + VOTING_POINTS)
+                })?;
+            }
+            let mut limit = PENDING_WORK_LIMIT;
+            let mut evt = convert_all_paid_members_to_voters(
+                deps.branch(),
+                proposal_id,
+                &mut batch,
+                &env.block,
+                &mut limit,
+            )?;
+            if paid_promoted {
+                evt = evt.add_attribute(MEMBER_KEY, paid_escrow);
+            }
             Ok(Some(evt))
         }
         // not ready yet
@@ -255,38 +503,67 @@ const PROPOSAL_KEY: &str = "proposal";
 const MEMBER_KEY: &str = "member";
 const CONTRACT_ADDR_KEY: &str = "contract_addr";
 const REMOVE_VOTING_TYPE: &str = "remove_voting";
+const EXPIRE_PENDING_TYPE: &str = "pending_expired";
+const CLOSE_TYPE: &str = "proposals_closed";
+const PENDING_WORK_TYPE: &str = "pending_work";
+const SIGNED_VOTE_TYPE: &str = "signed_vote";
+
+/// How many members are checked at most by a single `CheckPending` (also done on every new
+/// proposal, and when the last member of a batch pays in). This keeps the gas used bounded,
+/// whatever is left is resumed by the following calls
+pub(crate) const PENDING_WORK_LIMIT: usize = 100;
+
+/// How many members (or batches) are counted at most by the `PendingWork` query, so it stays
+/// bounded as well
+pub(crate) const PENDING_WORK_QUERY_LIMIT: usize = 10 * PENDING_WORK_LIMIT;
 
 /// Call when the batch is ready to become voters (all paid or expiration hit).
-/// This checks all members if they have paid up, and if so makes them full voters.
+/// This checks up to `limit` members if they have paid up, and if so makes them full voters.
 /// As well as making members voter, it will update and save the batch and the
-/// total vote count.
+/// total vote count. `limit` is decreased by the number of members checked.
+/// The batch is only marked as promoted once all its members were checked.
 fn convert_all_paid_members_to_voters<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     batch_id: u64,
     batch: &mut Batch,
-    height: u64,
+    block: &BlockInfo,
+    limit: &mut usize,
 ) -> StdResult<Event> {
     let mut evt = Event::new(PROMOTE_TYPE).add_attribute(PROPOSAL_KEY, batch_id.to_string());
 
-    // try to promote them all
+    // try to promote them all, resuming from where the last call stopped
+    let start = batch.next_member as usize;
+    let end = batch.members.len().min(start + *limit);
+    *limit -= end - start;
     let mut added = 0;
-    let mut diff = vec![];
-    for waiting in batch.members.iter() {
-        if convert_to_voter_if_paid(deps.branch(), waiting, height)? {
-            diff.push((waiting, VOTING_POINTS as i128));
+    for waiting in batch.members[start..end].iter() {
+        if convert_to_voter_if_paid(deps.branch(), waiting, block)? {
             evt = evt.add_attribute(MEMBER_KEY, waiting);
             added += VOTING_POINTS;
         }
     }
-    DISTRIBUTION.apply_points_correction(deps.branch(), &diff)?;
 
-    // make this a promoted and save
-    batch.batch_promoted = true;
+    if end == batch.members.len() {
+        // make this a promoted and save
+        batch.batch_promoted = true;
+        batch.next_member = 0;
+        batch.promoting_since = None;
+    } else {
+        batch.next_member = end as u32;
+        // An early promotion (everybody paid) can be left unfinished before the grace period
+        // ends. Make sure the following checks pick it up
+        if batch.grace_ends_at > block.time.seconds() && batch.promoting_since.is_none() {
+            batch.promoting_since = Some(block.time.seconds());
+        }
+        evt = evt.add_attribute("remaining", (batch.members.len() - end).to_string());
+    }
     batches().save(deps.storage, batch_id, batch)?;
 
     // update the total with the new points
     if added > 0 {
-        TOTAL.update::<_, StdError>(deps.storage, |old| Ok(old + added))?;
+        TOTAL.update::<_, StdError>(deps.storage, block.height, |old| {
+            Ok(old.unwrap_or_default() + added)
+        })?;
     }
 
     Ok(evt)
@@ -298,7 +575,7 @@ fn convert_all_paid_members_to_voters<Q: CustomQuery>(
 fn convert_to_voter_if_paid<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     to_promote: &Addr,
-    height: u64,
+    block: &BlockInfo,
 ) -> StdResult<bool> {
     let mut escrow = ESCROWS.load(deps.storage, to_promote)?;
     // if this one was not yet paid up, do nothing
@@ -307,27 +584,86 @@ fn convert_to_voter_if_paid<Q: CustomQuery>(
     }
 
     // update status
+    let old_status = escrow.status;
     escrow.status = MemberStatus::Voting {};
     ESCROWS.save(deps.storage, to_promote, &escrow)?;
-    DISTRIBUTION.apply_points_correction(deps.branch(), &[(to_promote, VOTING_POINTS as i128)])?;
+    record_escrow(
+        deps.storage,
+        block,
+        to_promote,
+        EscrowAction::Promote {},
+        Some(&escrow),
+    )?;
+    update_reward_points(
+        deps.branch(),
+        block,
+        to_promote,
+        Some(old_status),
+        Some(escrow.status),
+    )?;
 
     // update voting points
     members().save(
         deps.storage,
         to_promote,
         &MemberInfo::new(VOTING_POINTS),
-        height,
+        block.height,
     )?;
 
     Ok(true)
 }
 
-pub fn execute_return_escrow<Q: CustomQuery>(
+/// Updates the reward points of `addr`, after its status changed from `old` to `new` (`None` if
+/// not a member).
+fn update_reward_points<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    block: &BlockInfo,
+    addr: &Addr,
+    old: Option<MemberStatus>,
+    new: Option<MemberStatus>,
+) -> StdResult<()> {
+    let weights = REWARD_WEIGHTS.may_load(deps.storage)?.unwrap_or_default();
+    let points = |status: Option<MemberStatus>| status.map_or(0, |s| weights.points(&s));
+    let (old, new) = (points(old), points(new));
+    if old == new {
+        return Ok(());
+    }
+
+    let total = REWARD_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+    let total = (total + new)
+        .checked_sub(old)
+        .ok_or_else(|| StdError::generic_err("Reward total underflow"))?;
+    REWARD_TOTAL.save(deps.storage, &total)?;
+    DISTRIBUTION.apply_points_correction(deps, &[(addr, new as i128 - old as i128)], block.time)
+}
+
+/// Reward points of a member with given status
+fn reward_points(storage: &dyn Storage, status: &MemberStatus) -> StdResult<u64> {
+    let weights = REWARD_WEIGHTS.may_load(storage)?.unwrap_or_default();
+    Ok(weights.points(status))
+}
+
+/// Distributes slashed escrow of `punished` as rewards, without a share for `punished`
+fn distribute_slashed_escrow<Q: CustomQuery>(
     deps: DepsMut<Q>,
+    punished: &Addr,
+    amount: u128,
+) -> StdResult<Coin> {
+    let points = match ESCROWS.may_load(deps.storage, punished)? {
+        Some(escrow) => reward_points(deps.storage, &escrow.status)?,
+        None => 0,
+    };
+    let total = REWARD_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+    DISTRIBUTION.distribute_funds(deps, amount, total.into(), &[(punished, points.into())])
+}
+
+pub fn execute_return_escrow<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
     cw_utils::nonpayable(&info)?;
+    ensure_not_paused(deps.storage, &env.block)?;
 
     let mut escrow = ESCROWS
         .may_load(deps.storage, &info.sender)?
@@ -362,9 +698,12 @@ pub fn execute_return_escrow<Q: CustomQuery>(
 
     // Update remaining escrow
     escrow.paid = escrow.paid.checked_sub(refund)?;
+    escrow_released(deps.storage, &trusted_circle.denom, refund)?;
+    let action = EscrowAction::Return { amount: refund };
     if escrow.paid.is_zero() {
         // clearing out leaving member
         ESCROWS.remove(deps.storage, &info.sender);
+        record_escrow(deps.storage, &env.block, &info.sender, action, None)?;
         members().remove(deps.storage, &info.sender, env.block.height)?;
         res = res.add_event(
             Event::new(REMOVE_VOTING_TYPE).add_attribute(MEMBER_KEY, info.sender.clone()),
@@ -372,134 +711,664 @@ pub fn execute_return_escrow<Q: CustomQuery>(
     } else {
         // removing excess from voting member
         ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+        record_escrow(
+            deps.storage,
+            &env.block,
+            &info.sender,
+            action,
+            Some(&escrow),
+        )?;
     }
 
     // Refund tokens
     if !refund.is_zero() {
-        res = res.add_message(BankMsg::Send {
-            to_address: info.sender.into(),
-            amount: vec![coin(refund.u128(), trusted_circle.denom)],
-        });
+        res = res.add_message(release_escrow(
+            deps.branch(),
+            &env,
+            &trusted_circle,
+            &info.sender,
+            refund,
+        )?);
     }
     Ok(res)
 }
 
+pub fn execute_claim_escrow<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    ensure_not_paused(deps.storage, &env.block)?;
+
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let (amount, msgs) = claim_escrow(deps, &env, &trusted_circle, &info.sender)?;
+    Ok(Response::new()
+        .add_attribute("action", "claim_escrow")
+        .add_attribute("sender", info.sender)
+        .add_attribute("amount", amount)
+        .add_messages(msgs))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn execute_propose<Q: CustomQuery>(
-    mut deps: DepsMut<Q>,
-    mut env: Env,
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    proposal: ProposalContent,
+    urgent: bool,
+    secret: bool,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let (id, pending) = open_proposal(
+        deps,
+        env,
+        &info.sender,
+        title,
+        description,
+        proposal,
+        urgent,
+        secret,
+        vec![],
+        None,
+    )?;
+
+    let res = Response::new()
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("action", "propose")
+        .add_attribute("sender", info.sender)
+        .add_attribute("urgent", urgent.to_string())
+        .add_attribute("secret", secret.to_string())
+        .add_submessages(pending.messages)
+        .add_events(pending.events);
+
+    Ok(res)
+}
+
+pub fn execute_propose_to_committee<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
     info: MessageInfo,
+    committee: String,
     title: String,
     description: String,
     proposal: ProposalContent,
 ) -> Result<Response, ContractError> {
     cw_utils::nonpayable(&info)?;
 
+    let (id, pending) = open_proposal(
+        deps,
+        env,
+        &info.sender,
+        title,
+        description,
+        proposal,
+        false,
+        false,
+        vec![],
+        Some(committee.clone()),
+    )?;
+
+    let res = Response::new()
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("action", "propose_to_committee")
+        .add_attribute("sender", info.sender)
+        .add_attribute("committee", committee)
+        .add_submessages(pending.messages)
+        .add_events(pending.events);
+
+    Ok(res)
+}
+
+/// Opens a proposal of `proposer` for voting, snapshotting the total points and rules. Returns
+/// its id, and the response of the pending work triggered. Proposals of a `committee` are voted
+/// by its members, with its rules
+#[allow(clippy::too_many_arguments)]
+fn open_proposal<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    mut env: Env,
+    proposer: &Addr,
+    title: String,
+    description: String,
+    proposal: ProposalContent,
+    urgent: bool,
+    secret: bool,
+    cosponsors: Vec<Addr>,
+    committee: Option<String>,
+) -> Result<(u64, Response), ContractError> {
     // trigger check_pending (we should get this cheaper)
     // Note, we check this at the end of last block, so they will actually be included in the voters
     // of this proposal (which uses a snapshot)
     // Also as this contract actually may be called on 0-height block, it has to be checked
     // (probably can be removed in some migration after genesis).
-    let events = if env.block.height > 0 {
+    let pending = if env.block.height > 0 {
         // As its only altering height for a while there is no point on cloning whole env just for
         // one call. Height is restored literally 2 lines below.
         env.block.height -= 1;
-        let events = check_pending(deps.branch(), &env)?;
+        let pending = check_pending(deps.branch(), &env, PENDING_WORK_LIMIT)?;
         env.block.height += 1;
-        events
+        pending
     } else {
-        Vec::new()
+        Response::new()
     };
 
-    // only voting members  can create a proposal
-    let vote_power = members()
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or_default()
-        .points;
-    if vote_power == 0 {
-        return Err(ContractError::Unauthorized(
-            "Member doesn't have a voting power".to_owned(),
-        ));
-    }
+    // only voting members (and permitted contracts) can create a proposal
+    let vote_power = match &committee {
+        Some(name) => {
+            ensure_committee_power(deps.storage, name, &proposal)?;
+            committee_member_power(deps.storage, name, proposer)?
+        }
+        None => proposer_power(deps.storage, proposer, &proposal)?,
+    };
 
+    if !proposal.allowed_when_paused() {
+        ensure_not_paused(deps.storage, &env.block)?;
+    }
     // validate the proposal's content
     validate_proposal(deps.as_ref(), env.clone(), &proposal)?;
 
     // create a proposal
     let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let is_poll = matches!(proposal, ProposalContent::Poll { .. });
+    let secret_rules = match (secret, &trusted_circle.secret_rules) {
+        (false, _) => None,
+        (true, Some(secret_rules)) if !urgent && !is_poll => Some(secret_rules.clone()),
+        (true, _) => return Err(ContractError::SecretNotAllowed {}),
+    };
+    if !urgent && matches!(proposal, ProposalContent::Pause { .. }) {
+        return Err(ContractError::PauseNotUrgent {});
+    }
+    let (mut rules, voting_period) = if urgent {
+        let urgent_rules = match trusted_circle.urgent_rules {
+            Some(urgent_rules) if proposal.can_be_urgent() => urgent_rules,
+            _ => return Err(ContractError::UrgentNotAllowed {}),
+        };
+        record_urgent_proposal(deps.storage, &env.block, proposer, &urgent_rules)?;
+        (
+            urgent_rules.voting_rules(),
+            urgent_rules.voting_period_secs(),
+        )
+    } else if let Some(name) = &committee {
+        let rules = COMMITTEES.load(deps.storage, name)?.rules;
+        let voting_period = rules.voting_period_secs();
+        (rules, voting_period)
+    } else {
+        let voting_period = trusted_circle.rules.voting_period_secs();
+        (trusted_circle.rules, voting_period)
+    };
+    let commit_ends = env.block.time.plus_seconds(voting_period);
+    let (expires, secret, votes) = match secret_rules {
+        // Secret proposals are only decided once votes are revealed, and the proposer commits
+        // its vote like everyone else
+        Some(secret_rules) => {
+            rules.allow_end_early = false;
+            let secret = SecretBallot {
+                commit_ends: Expiration::AtTime(commit_ends),
+                unrevealed: secret_rules.unrevealed,
+            };
+            let expires = commit_ends.plus_seconds(secret_rules.reveal_period_secs());
+            (expires, Some(secret), Votes::yes(0))
+        }
+        // Polls are only decided once they expire, and the proposer chooses like everyone else
+        None if is_poll => {
+            rules.allow_end_early = false;
+            (commit_ends, None, Votes::yes(0))
+        }
+        None => (commit_ends, None, Votes::yes(vote_power)),
+    };
     let mut prop = Proposal {
         title,
         description,
         start_height: env.block.height,
-        expires: Expiration::AtTime(
-            env.block
-                .time
-                .plus_seconds(trusted_circle.rules.voting_period_secs()),
-        ),
+        expires: Expiration::AtTime(expires),
         proposal,
         status: Status::Open,
-        votes: Votes::yes(vote_power),
-        total_points: TOTAL.load(deps.storage)?,
-        rules: trusted_circle.rules,
+        votes,
+        total_points: match &committee {
+            Some(name) => committee_points(deps.storage, name)?,
+            None => TOTAL.load(deps.storage)?,
+        },
+        rules,
+        urgent,
+        urgent_period: urgent.then_some(voting_period),
+        secret,
+        cosponsors,
+        committee,
     };
     prop.update_status(&env.block);
     let id = create_proposal(deps.storage, &prop)?;
 
     // add the first yes vote from voter
-    ballots().create_ballot(deps.storage, &info.sender, id, vote_power, Vote::Yes)?;
+    if prop.secret.is_none() && !is_poll && vote_power > 0 {
+        ballots().create_ballot(deps.storage, proposer, id, vote_power, Vote::Yes)?;
+    }
 
-    let res = Response::new()
-        .add_attribute("proposal_id", id.to_string())
-        .add_attribute("action", "propose")
-        .add_attribute("sender", info.sender)
-        .add_events(events);
+    Ok((id, pending))
+}
 
-    Ok(res)
+/// Voting points of `member`, failing if it has none
+fn voting_power(storage: &dyn Storage, member: &Addr) -> Result<u64, ContractError> {
+    let points = members()
+        .may_load(storage, member)?
+        .unwrap_or_default()
+        .points;
+    if points == 0 {
+        return Err(ContractError::Unauthorized(
+            "Member doesn't have a voting power".to_owned(),
+        ));
+    }
+    Ok(points)
 }
 
-pub fn validate_proposal<Q: CustomQuery>(
-    deps: Deps<Q>,
-    env: Env,
+/// Voting points of `proposer`, which may be zero for contracts permitted to propose `proposal`
+fn proposer_power(
+    storage: &dyn Storage,
+    proposer: &Addr,
     proposal: &ProposalContent,
-) -> Result<(), ContractError> {
-    match proposal {
-        ProposalContent::EditTrustedCircle(trusted_circle_adjustments) => {
-            let mut trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
-
-            if trusted_circle.edit_trusted_circle_disabled {
-                return Err(ContractError::FrozenRules);
-            }
-
-            trusted_circle.apply_adjustments(
-                env,
-                u64::MAX, // Dummy proposal id
-                trusted_circle_adjustments.clone(),
-            )?;
-            trusted_circle.validate()
-        }
-        ProposalContent::AddRemoveNonVotingMembers { add, remove } => {
-            if add.is_empty() && remove.is_empty() {
-                return Err(ContractError::NoMembers {});
-            }
-            validate_addresses_with_deny_list(deps, add)
-        }
-        ProposalContent::AddVotingMembers { voters } => {
-            if voters.is_empty() {
-                return Err(ContractError::NoMembers {});
-            }
-            validate_addresses_with_deny_list(deps, voters)
-        }
-        ProposalContent::PunishMembers(punishments) => {
-            if punishments.is_empty() {
-                return Err(ContractError::NoPunishments {});
-            }
-            punishments.iter().try_for_each(|p| p.validate(&deps))
-        }
-        ProposalContent::WhitelistContract(addr) | ProposalContent::RemoveContract(addr) => {
-            validate_contract_address(&deps, addr)
-        }
+) -> Result<u64, ContractError> {
+    match CONTRACT_PERMISSIONS.may_load(storage, proposer)? {
+        Some(permissions) if permissions.can_propose(proposal) => Ok(0),
+        _ => voting_power(storage, proposer),
     }
 }
 
+fn load_committee(storage: &dyn Storage, name: &str) -> Result<Committee, ContractError> {
+    COMMITTEES
+        .may_load(storage, name)?
+        .ok_or_else(|| ContractError::CommitteeNotFound(name.to_owned()))
+}
+
+/// Ensures the committee still exists, and decides this kind of proposal
+fn ensure_committee_power(
+    storage: &dyn Storage,
+    name: &str,
+    proposal: &ProposalContent,
+) -> Result<(), ContractError> {
+    if !load_committee(storage, name)?.can_propose(proposal) {
+        return Err(ContractError::Unauthorized(format!(
+            "Committee {} cannot decide {:?} proposals",
+            name,
+            proposal.kind()
+        )));
+    }
+    Ok(())
+}
+
+/// Voting points of `member` in the committee, failing if it has none
+fn committee_member_power(
+    storage: &dyn Storage,
+    name: &str,
+    member: &Addr,
+) -> Result<u64, ContractError> {
+    if !load_committee(storage, name)?.is_member(member) {
+        return Err(ContractError::Unauthorized(format!(
+            "Not a member of committee {}",
+            name
+        )));
+    }
+    voting_power(storage, member)
+}
+
+/// Voting points of the committee members, who may have stopped voting since it was created
+fn committee_points(storage: &dyn Storage, name: &str) -> Result<u64, ContractError> {
+    let mut points = 0;
+    for member in load_committee(storage, name)?.members {
+        points += members()
+            .may_load(storage, &member)?
+            .unwrap_or_default()
+            .points;
+    }
+    Ok(points)
+}
+
+/// Whitelisted contracts may only execute what they were granted, besides handling their own
+/// rewards. Which proposals they may create is checked with the proposal
+fn ensure_contract_permitted(
+    storage: &dyn Storage,
+    sender: &Addr,
+    msg: &ExecuteMsg,
+) -> Result<(), ContractError> {
+    let permissions = match CONTRACT_PERMISSIONS.may_load(storage, sender)? {
+        Some(permissions) => permissions,
+        None => return Ok(()),
+    };
+    let permitted = match msg {
+        ExecuteMsg::DistributeRewards {} => permissions.distribute_rewards,
+        ExecuteMsg::CheckPending {} => permissions.check_pending,
+        ExecuteMsg::Propose { .. }
+        | ExecuteMsg::CreateDraft { .. }
+        | ExecuteMsg::AmendDraft { .. }
+        | ExecuteMsg::PublishDraft { .. } => !permissions.propose.is_empty(),
+        ExecuteMsg::WithdrawRewards { .. }
+        | ExecuteMsg::DelegateWithdrawal { .. }
+        | ExecuteMsg::UndelegateWithdrawal {}
+        | ExecuteMsg::FundTreasury {} => true,
+        _ => false,
+    };
+    if !permitted {
+        return Err(ContractError::Unauthorized(
+            "Contract is not permitted to execute this message".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_draft<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    proposal: ProposalContent,
+    urgent: bool,
+    secret: bool,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    proposer_power(deps.storage, &info.sender, &proposal)?;
+    validate_proposal(deps.as_ref(), env.clone(), &proposal)?;
+
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let draft = Draft {
+        author: info.sender,
+        title,
+        description,
+        proposal,
+        urgent,
+        secret,
+        cosponsors: vec![],
+        expires: env
+            .block
+            .time
+            .plus_seconds(trusted_circle.draft_period_secs()),
+    };
+    let id = DRAFT_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    DRAFT_COUNT.save(deps.storage, &id)?;
+    drafts().save(deps.storage, id, &draft)?;
+
+    let res = Response::new()
+        .add_attribute("action", "create_draft")
+        .add_attribute("draft_id", id.to_string())
+        .add_attribute("sender", draft.author)
+        .add_attribute("expires", draft.expires.to_string());
+    Ok(res)
+}
+
+/// Loads a draft which did not expire yet
+fn load_open_draft(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    draft_id: u64,
+) -> Result<Draft, ContractError> {
+    let draft = drafts()
+        .may_load(storage, draft_id)?
+        .ok_or(ContractError::DraftNotFound(draft_id))?;
+    if block.time >= draft.expires {
+        return Err(ContractError::DraftExpired(draft_id));
+    }
+    Ok(draft)
+}
+
+pub fn execute_amend_draft<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    draft_id: u64,
+    title: Option<String>,
+    description: Option<String>,
+    proposal: Option<ProposalContent>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut draft = load_open_draft(deps.storage, &env.block, draft_id)?;
+    if draft.author != info.sender {
+        return Err(ContractError::Unauthorized(
+            "Only the author can amend the draft".to_owned(),
+        ));
+    }
+    if let Some(proposal) = proposal {
+        proposer_power(deps.storage, &info.sender, &proposal)?;
+        validate_proposal(deps.as_ref(), env, &proposal)?;
+        draft.proposal = proposal;
+    }
+    if let Some(title) = title {
+        draft.title = title;
+    }
+    if let Some(description) = description {
+        draft.description = description;
+    }
+    // Cosponsors have to sponsor the amended draft again
+    draft.cosponsors = vec![];
+    drafts().save(deps.storage, draft_id, &draft)?;
+
+    let res = Response::new()
+        .add_attribute("action", "amend_draft")
+        .add_attribute("draft_id", draft_id.to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_cosponsor_draft<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    draft_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    voting_power(deps.storage, &info.sender)?;
+
+    let mut draft = load_open_draft(deps.storage, &env.block, draft_id)?;
+    if draft.author == info.sender || draft.cosponsors.contains(&info.sender) {
+        return Err(ContractError::AlreadyCosponsored {});
+    }
+    draft.cosponsors.push(info.sender.clone());
+    drafts().save(deps.storage, draft_id, &draft)?;
+
+    let res = Response::new()
+        .add_attribute("action", "cosponsor_draft")
+        .add_attribute("draft_id", draft_id.to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_publish_draft<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    draft_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let draft = load_open_draft(deps.storage, &env.block, draft_id)?;
+    if draft.author != info.sender {
+        return Err(ContractError::Unauthorized(
+            "Only the author can publish the draft".to_owned(),
+        ));
+    }
+    drafts().remove(deps.storage, draft_id)?;
+
+    let (urgent, secret) = (draft.urgent, draft.secret);
+    let (id, pending) = open_proposal(
+        deps,
+        env,
+        &info.sender,
+        draft.title,
+        draft.description,
+        draft.proposal,
+        urgent,
+        secret,
+        draft.cosponsors,
+        None,
+    )?;
+
+    let res = Response::new()
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("action", "publish_draft")
+        .add_attribute("draft_id", draft_id.to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("urgent", urgent.to_string())
+        .add_attribute("secret", secret.to_string())
+        .add_submessages(pending.messages)
+        .add_events(pending.events);
+    Ok(res)
+}
+
+/// Records an urgent proposal of `proposer`, failing if it already created the maximum number of
+/// urgent proposals within the limit period
+fn record_urgent_proposal(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    proposer: &Addr,
+    urgent_rules: &UrgentRules,
+) -> Result<(), ContractError> {
+    let now = block.time.seconds();
+    let since = now.saturating_sub(urgent_rules.limit_period_secs());
+    let mut recent = URGENT_PROPOSALS
+        .may_load(storage, proposer)?
+        .unwrap_or_default();
+    recent.retain(|created| *created > since);
+    if recent.len() >= urgent_rules.max_proposals as usize {
+        return Err(ContractError::UrgentLimitReached(
+            urgent_rules.max_proposals,
+        ));
+    }
+
+    recent.push(now);
+    URGENT_PROPOSALS.save(storage, proposer, &recent)?;
+    Ok(())
+}
+
+pub fn validate_proposal<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    proposal: &ProposalContent,
+) -> Result<(), ContractError> {
+    match proposal {
+        ProposalContent::EditTrustedCircle(trusted_circle_adjustments) => {
+            let mut trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+
+            if trusted_circle.edit_trusted_circle_disabled {
+                return Err(ContractError::FrozenRules);
+            }
+
+            if let Some(guardian) = &trusted_circle_adjustments.guardian {
+                deps.api.addr_validate(guardian)?;
+            }
+            trusted_circle.apply_adjustments(
+                env,
+                u64::MAX, // Dummy proposal id
+                trusted_circle_adjustments.clone(),
+            )?;
+            trusted_circle.validate()
+        }
+        ProposalContent::AddRemoveNonVotingMembers { add, remove } => {
+            if add.is_empty() && remove.is_empty() {
+                return Err(ContractError::NoMembers {});
+            }
+            validate_addresses_with_deny_list(deps, add)
+        }
+        ProposalContent::AddVotingMembers { voters } => {
+            if voters.is_empty() {
+                return Err(ContractError::NoMembers {});
+            }
+            validate_addresses_with_deny_list(deps, voters)
+        }
+        ProposalContent::PunishMembers(punishments) => {
+            if punishments.is_empty() {
+                return Err(ContractError::NoPunishments {});
+            }
+            punishments.iter().try_for_each(|p| p.validate(&deps))
+        }
+        ProposalContent::WhitelistContract(addr) | ProposalContent::RemoveContract(addr) => {
+            validate_contract_address(&deps, addr)
+        }
+        ProposalContent::TreasurySpend {
+            recipient, amount, ..
+        } => {
+            deps.api.addr_validate(recipient)?;
+            if amount.amount.is_zero() {
+                return Err(ContractError::ZeroTreasurySpend {});
+            }
+            let available = treasury_balance(deps.storage, &amount.denom)?.available;
+            if available < amount.amount {
+                return Err(ContractError::TreasuryTooLow {
+                    available: coin(available.u128(), &amount.denom),
+                    requested: amount.clone(),
+                });
+            }
+            Ok(())
+        }
+        ProposalContent::Poll {
+            options,
+            max_choices,
+            ..
+        } => poll::validate_poll(options, *max_choices),
+        ProposalContent::Pause { hours } => validate_pause_period(*hours),
+        ProposalContent::Unpause {} => Ok(()),
+        ProposalContent::SetContractPermissions { contract, .. } => {
+            validate_contract_address(&deps, contract)?;
+            ensure_whitelisted(deps.storage, &Addr::unchecked(contract))
+        }
+        ProposalContent::CreateCommittee {
+            name,
+            members,
+            powers,
+            rules,
+        } => validate_committee(deps, name, members, powers, rules),
+        ProposalContent::DissolveCommittee { name } => {
+            load_committee(deps.storage, name).map(|_| ())
+        }
+    }
+}
+
+/// Committees are made of voting members, and decide at least one kind of proposal
+fn validate_committee<Q: CustomQuery>(
+    deps: Deps<Q>,
+    name: &str,
+    members: &[String],
+    powers: &[ProposalKind],
+    rules: &VotingRules,
+) -> Result<(), ContractError> {
+    if name.is_empty() {
+        return Err(ContractError::InvalidCommittee("Missing name".to_owned()));
+    }
+    if members.is_empty() {
+        return Err(ContractError::NoMembers {});
+    }
+    for (i, member) in members.iter().enumerate() {
+        if members[..i].contains(member) {
+            return Err(ContractError::InvalidCommittee(format!(
+                "{} is listed twice",
+                member
+            )));
+        }
+        let addr = deps.api.addr_validate(member)?;
+        let voting = matches!(
+            ESCROWS.may_load(deps.storage, &addr)?,
+            Some(escrow) if escrow.status.is_voting()
+        );
+        if !voting {
+            return Err(ContractError::InvalidCommittee(format!(
+                "{} is not a voting member",
+                member
+            )));
+        }
+    }
+    if powers.is_empty() {
+        return Err(ContractError::InvalidCommittee("Missing powers".to_owned()));
+    }
+    if let Some(kind) = powers.iter().find(|kind| !kind.can_be_delegated()) {
+        return Err(ContractError::InvalidCommittee(format!(
+            "{:?} cannot be delegated",
+            kind
+        )));
+    }
+    rules.validate()
+}
+
 pub fn validate_human_addresses<Q: CustomQuery>(
     deps: &Deps<Q>,
     addrs: &[String],
@@ -547,20 +1416,69 @@ pub fn execute_vote<Q: CustomQuery>(
 ) -> Result<Response, ContractError> {
     cw_utils::nonpayable(&info)?;
 
-    // ensure proposal exists and can be voted on
-    let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
+    let status = cast_vote(deps.storage, &env.block, &info.sender, proposal_id, vote)?;
+
+    let res = Response::new()
+        .add_attribute("action", "vote")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("status", format!("{:?}", status));
+    Ok(res)
+}
+
+/// Records the ballot of `voter` and updates the proposal tally. Returns the new proposal status
+fn cast_vote(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    voter: &Addr,
+    proposal_id: u64,
+    vote: Vote,
+) -> Result<Status, ContractError> {
+    let mut prop = load_votable_proposal(storage, block, proposal_id)?;
+    if prop.secret.is_some() {
+        return Err(ContractError::SecretProposal {});
+    }
+    if let ProposalContent::Poll { .. } = prop.proposal {
+        return Err(ContractError::PollBallotRequired {});
+    }
+    let vote_power = vote_power(storage, &prop, voter)?;
+
+    ballots().create_ballot(storage, voter, proposal_id, vote_power, vote)?;
+
+    // update vote tally
+    prop.votes.add_vote(vote, vote_power);
+    prop.update_status(block);
+    PROPOSALS.save(storage, proposal_id, &prop)?;
+
+    Ok(prop.status)
+}
+
+/// Loads the proposal, ensuring it exists and can be voted on
+fn load_votable_proposal(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    proposal_id: u64,
+) -> Result<Proposal, ContractError> {
+    let prop = PROPOSALS.load(storage, proposal_id)?;
     if prop.status != Status::Open && prop.status != Status::Passed {
         return Err(ContractError::NotOpen {});
     }
     // Looking at Expiration: if the block time == expiration time, this counts as expired
-    if prop.expires.is_expired(&env.block) {
+    if prop.expires.is_expired(block) {
         return Err(ContractError::Expired {});
     }
+    Ok(prop)
+}
 
+/// Returns the voting power of `voter` on the proposal, failing if it cannot vote
+fn vote_power(storage: &dyn Storage, prop: &Proposal, voter: &Addr) -> Result<u64, ContractError> {
+    if let Some(name) = &prop.committee {
+        committee_member_power(storage, name, voter)?;
+    }
     // only members of the multisig can vote
     // use a snapshot of "start of proposal"
     let vote_power = members()
-        .may_load_at_height(deps.storage, &info.sender, prop.start_height)?
+        .may_load_at_height(storage, voter, prop.start_height)?
         .unwrap_or_default()
         .points;
     if vote_power == 0 {
@@ -570,26 +1488,263 @@ pub fn execute_vote<Q: CustomQuery>(
     }
 
     // ensure the voter is not currently leaving the trusted_circle (must be currently a voter)
-    let escrow = ESCROWS.load(deps.storage, &info.sender)?;
+    let escrow = ESCROWS.load(storage, voter)?;
     if !escrow.status.is_voting() {
         return Err(ContractError::InvalidStatus(escrow.status));
     }
+    Ok(vote_power)
+}
 
-    ballots().create_ballot(deps.storage, &info.sender, proposal_id, vote_power, vote)?;
+pub fn execute_vote_poll<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    choices: Vec<u32>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut prop = load_votable_proposal(deps.storage, &env.block, proposal_id)?;
+    match &prop.proposal {
+        ProposalContent::Poll {
+            options,
+            max_choices,
+            ..
+        } => poll::validate_choices(options.len(), *max_choices, &choices)?,
+        _ => return Err(ContractError::NotPoll {}),
+    }
+    let vote_power = vote_power(deps.storage, &prop, &info.sender)?;
+
+    // Poll ballots count as yes votes toward the quorum
+    ballots().create_ballot(
+        deps.storage,
+        &info.sender,
+        proposal_id,
+        vote_power,
+        Vote::Yes,
+    )?;
+    POLL_BALLOTS.save(deps.storage, (proposal_id, &info.sender), &choices)?;
+
+    prop.votes.add_vote(Vote::Yes, vote_power);
+    prop.update_status(&env.block);
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    let res = Response::new()
+        .add_attribute("action", "vote_poll")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("status", format!("{:?}", prop.status));
+    Ok(res)
+}
+
+pub fn execute_commit_vote<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    hash: Binary,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut prop = load_votable_proposal(deps.storage, &env.block, proposal_id)?;
+    let secret = prop
+        .secret
+        .clone()
+        .ok_or(ContractError::NotSecretProposal {})?;
+    if secret.commit_ends.is_expired(&env.block) {
+        return Err(ContractError::CommitPeriodEnded {});
+    }
+    if hash.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+    let points = vote_power(deps.storage, &prop, &info.sender)?;
+    if VOTE_COMMITS.has(deps.storage, (proposal_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    VOTE_COMMITS.save(
+        deps.storage,
+        (proposal_id, &info.sender),
+        &VoteCommit { hash, points },
+    )?;
+
+    // Commits count as abstain until revealed, if so configured
+    if secret.unrevealed == UnrevealedVotes::Abstain {
+        prop.votes.add_vote(Vote::Abstain, points);
+        PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "commit_vote")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string());
+    Ok(res)
+}
+
+pub fn execute_reveal_vote<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: Vote,
+    salt: Binary,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut prop = load_votable_proposal(deps.storage, &env.block, proposal_id)?;
+    let secret = prop
+        .secret
+        .clone()
+        .ok_or(ContractError::NotSecretProposal {})?;
+    if !secret.commit_ends.is_expired(&env.block) {
+        return Err(ContractError::RevealNotStarted {});
+    }
+    let commit = VOTE_COMMITS
+        .may_load(deps.storage, (proposal_id, &info.sender))?
+        .ok_or(ContractError::NoCommitment(proposal_id))?;
+    let commitment = VoteCommitment {
+        voter: info.sender.to_string(),
+        proposal_id,
+        vote,
+        salt,
+    };
+    if Sha256::digest(to_vec(&commitment)?).as_slice() != commit.hash.as_slice() {
+        return Err(ContractError::InvalidReveal {});
+    }
+    VOTE_COMMITS.remove(deps.storage, (proposal_id, &info.sender));
+
+    ballots().create_ballot(deps.storage, &info.sender, proposal_id, commit.points, vote)?;
 
     // update vote tally
-    prop.votes.add_vote(vote, vote_power);
+    if secret.unrevealed == UnrevealedVotes::Abstain {
+        prop.votes.abstain -= commit.points;
+    }
+    prop.votes.add_vote(vote, commit.points);
     prop.update_status(&env.block);
     PROPOSALS.save(deps.storage, proposal_id, &prop)?;
 
     let res = Response::new()
-        .add_attribute("action", "vote")
+        .add_attribute("action", "reveal_vote")
         .add_attribute("sender", info.sender)
         .add_attribute("proposal_id", proposal_id.to_string())
         .add_attribute("status", format!("{:?}", prop.status));
     Ok(res)
 }
 
+pub fn execute_register_voting_key<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    if !ESCROWS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized(
+            "Only members can register a voting key".to_owned(),
+        ));
+    }
+    match (pubkey.len(), pubkey.first()) {
+        (33, Some(2 | 3)) | (65, Some(4)) => (),
+        _ => return Err(ContractError::InvalidVotingKey {}),
+    }
+    match VOTING_KEY_OWNERS.may_load(deps.storage, &pubkey)? {
+        Some(owner) if owner != info.sender => return Err(ContractError::VotingKeyTaken {}),
+        _ => (),
+    }
+    if let Some(old) = VOTING_KEYS.may_load(deps.storage, &info.sender)? {
+        // Keys shared before they had to be unique stay with their first member
+        if VOTING_KEY_OWNERS.may_load(deps.storage, &old)?.as_ref() == Some(&info.sender) {
+            VOTING_KEY_OWNERS.remove(deps.storage, &old);
+        }
+    }
+    VOTING_KEYS.save(deps.storage, &info.sender, &pubkey)?;
+    VOTING_KEY_OWNERS.save(deps.storage, &pubkey, &info.sender)?;
+
+    let res = Response::new()
+        .add_attribute("action", "register_voting_key")
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_submit_signed_votes<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    votes: Vec<SignedVote>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    if votes.is_empty() {
+        return Err(ContractError::NoSignedVotes {});
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "submit_signed_votes")
+        .add_attribute("sender", info.sender)
+        .add_attribute("votes", votes.len().to_string());
+    for signed in votes {
+        let voter = deps.api.addr_validate(&signed.voter)?;
+        verify_signed_vote(deps.as_ref(), &env, &voter, &signed)?;
+        VOTE_NONCES.save(deps.storage, &voter, &(signed.nonce + 1))?;
+
+        let status = cast_vote(
+            deps.storage,
+            &env.block,
+            &voter,
+            signed.proposal_id,
+            signed.vote,
+        )?;
+        res = res.add_event(
+            Event::new(SIGNED_VOTE_TYPE)
+                .add_attribute(MEMBER_KEY, voter)
+                .add_attribute(PROPOSAL_KEY, signed.proposal_id.to_string())
+                .add_attribute("status", format!("{:?}", status)),
+        );
+    }
+
+    Ok(res)
+}
+
+/// Checks that `signed` is signed with the registered key of `voter`, and uses its next nonce
+fn verify_signed_vote<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
+    voter: &Addr,
+    signed: &SignedVote,
+) -> Result<(), ContractError> {
+    let pubkey = VOTING_KEYS
+        .may_load(deps.storage, voter)?
+        .ok_or_else(|| ContractError::NoVotingKey(voter.to_string()))?;
+    let expected = VOTE_NONCES
+        .may_load(deps.storage, voter)?
+        .unwrap_or_default();
+    if signed.nonce != expected {
+        return Err(ContractError::InvalidVoteNonce {
+            voter: voter.to_string(),
+            nonce: signed.nonce,
+            expected,
+        });
+    }
+
+    let doc = VoteSignDoc {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        voter: voter.to_string(),
+        proposal_id: signed.proposal_id,
+        vote: signed.vote,
+        nonce: signed.nonce,
+    };
+    let hash = Sha256::digest(to_vec(&doc)?);
+    // Malformed signatures are just as invalid as the wrong ones
+    let valid = deps
+        .api
+        .secp256k1_verify(&hash, &signed.signature, &pubkey)
+        .unwrap_or(false);
+    if !valid {
+        return Err(ContractError::InvalidVoteSignature(voter.to_string()));
+    }
+    Ok(())
+}
+
 pub fn execute_execute<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     env: Env,
@@ -601,6 +1756,11 @@ pub fn execute_execute<Q: CustomQuery>(
     // anyone can trigger this if the vote passed
     let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
 
+    // the committee may have been dissolved or overridden by the trusted circle since
+    if let Some(name) = &prop.committee {
+        ensure_committee_power(deps.storage, name, &prop.proposal)?;
+    }
+
     if let ProposalContent::EditTrustedCircle(..) = prop.proposal {
         let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
 
@@ -611,9 +1771,13 @@ pub fn execute_execute<Q: CustomQuery>(
 
     // we allow execution even after the proposal "expiration" as long as all vote come in before
     // that point. If it was approved on time, it can be executed any time.
-    if prop.status != Status::Passed {
+    // Polls have nothing to execute, they are concluded on expiry instead.
+    if prop.status != Status::Passed || prop.proposal.kind() == ProposalKind::Poll {
         return Err(ContractError::WrongExecuteStatus {});
     }
+    if !prop.proposal.allowed_when_paused() {
+        ensure_not_paused(deps.storage, &env.block)?;
+    }
 
     // set it to executed
     prop.status = Status::Executed;
@@ -638,10 +1802,7 @@ pub fn execute_close<Q: CustomQuery>(
     // anyone can trigger this if the vote passed
 
     let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
-    if [Status::Executed, Status::Rejected, Status::Passed]
-        .iter()
-        .any(|x| *x == prop.status)
-    {
+    if [Status::Executed, Status::Rejected, Status::Passed].contains(&prop.status) {
         return Err(ContractError::WrongCloseStatus {});
     }
     if !prop.expires.is_expired(&env.block) {
@@ -659,12 +1820,52 @@ pub fn execute_close<Q: CustomQuery>(
     Ok(res)
 }
 
+/// Closes the open proposals which expired without passing. Proposals which passed are marked as
+/// such, so they can be executed. At most `limit` proposals are checked per call.
+fn close_expired_proposals<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    limit: usize,
+) -> Result<Option<Event>, ContractError> {
+    let start = CLOSED_PROPOSALS_CURSOR
+        .may_load(deps.storage)?
+        .map(Bound::exclusive);
+    let end = Bound::inclusive((env.block.time.seconds(), u64::MAX));
+    let expired = PROPOSAL_BY_EXPIRY
+        .keys(deps.storage, start, Some(end), Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut closed = vec![];
+    for (expiry, proposal_id) in expired {
+        CLOSED_PROPOSALS_CURSOR.save(deps.storage, &(expiry, proposal_id))?;
+        let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
+        if prop.status != Status::Open {
+            continue;
+        }
+        prop.update_status(&env.block);
+        PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+        if prop.status == Status::Rejected {
+            closed.push(proposal_id);
+        }
+    }
+
+    if closed.is_empty() {
+        return Ok(None);
+    }
+    let evt = closed.into_iter().fold(Event::new(CLOSE_TYPE), |evt, id| {
+        evt.add_attribute(PROPOSAL_KEY, id.to_string())
+    });
+    Ok(Some(evt))
+}
+
 pub fn execute_leave_trusted_circle<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
     cw_utils::nonpayable(&info)?;
+    ensure_not_paused(deps.storage, &env.block)?;
 
     // FIXME: Special check if last member leaving (https://github.com/confio/tgrade-contracts/issues/521)
     let escrow = ESCROWS
@@ -681,13 +1882,29 @@ pub fn execute_leave_trusted_circle<Q: CustomQuery>(
 
 /// This is called for members who have never paid any escrow in
 fn leave_immediately<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+    mut deps: DepsMut<Q>,
     env: Env,
     leaver: Addr,
 ) -> Result<Response, ContractError> {
     // non-voting member... remove them and refund any escrow (a pending member who didn't pay it all in)
     members().remove(deps.storage, &leaver, env.block.height)?;
+    if let Some(escrow) = ESCROWS.may_load(deps.storage, &leaver)? {
+        update_reward_points(
+            deps.branch(),
+            &env.block,
+            &leaver,
+            Some(escrow.status),
+            None,
+        )?;
+    }
     ESCROWS.remove(deps.storage, &leaver);
+    record_escrow(
+        deps.storage,
+        &env.block,
+        &leaver,
+        EscrowAction::Leave {},
+        None,
+    )?;
 
     let res = Response::new()
         .add_attribute("action", "leave_trusted_circle")
@@ -705,12 +1922,11 @@ fn trigger_long_leave<Q: CustomQuery>(
     // if we are voting member, reduce vote to 0 (otherwise, it is already 0)
     if escrow.status == (MemberStatus::Voting {}) {
         members().save(deps.storage, &leaver, &MemberInfo::new(0), env.block.height)?;
-        TOTAL.update::<_, StdError>(deps.storage, |old| {
-            old.checked_sub(VOTING_POINTS)
+        TOTAL.update::<_, StdError>(deps.storage, env.block.height, |old| {
+            old.unwrap_or_default()
+                .checked_sub(VOTING_POINTS)
                 .ok_or_else(|| StdError::generic_err("Total underflow"))
         })?;
-        DISTRIBUTION
-            .apply_points_correction(deps.branch(), &[(&leaver, -(VOTING_POINTS as i128))])?;
         // now, we reduce total points of all open proposals that this member has not yet voted on
         adjust_open_proposals_for_leaver(deps.branch(), &env, &leaver)?;
     }
@@ -718,8 +1934,23 @@ fn trigger_long_leave<Q: CustomQuery>(
     // in all case, we become a leaving member and set the claim on our escrow
     let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
     let claim_at = env.block.time.seconds() + trusted_circle.rules.voting_period_secs() * 2;
+    let old_status = escrow.status;
     escrow.status = MemberStatus::Leaving { claim_at };
     ESCROWS.save(deps.storage, &leaver, &escrow)?;
+    update_reward_points(
+        deps.branch(),
+        &env.block,
+        &leaver,
+        Some(old_status),
+        Some(escrow.status),
+    )?;
+    record_escrow(
+        deps.storage,
+        &env.block,
+        &leaver,
+        EscrowAction::Leave {},
+        Some(&escrow),
+    )?;
 
     let res = Response::new()
         .add_attribute("action", "leave_trusted_circle")
@@ -736,9 +1967,9 @@ fn adjust_open_proposals_for_leaver<Q: CustomQuery>(
 ) -> Result<(), ContractError> {
     // find all open proposals that have not yet expired
     let now = env.block.time.seconds();
-    let start = Bound::exclusive(now);
+    let start = Bound::exclusive((now, u64::MAX));
     let open_prop_ids = PROPOSAL_BY_EXPIRY
-        .range(deps.storage, Some(start), None, Order::Ascending)
+        .keys(deps.storage, Some(start), None, Order::Ascending)
         .collect::<StdResult<Vec<_>>>()?;
 
     // check which ones we have not voted on and update them
@@ -747,9 +1978,17 @@ fn adjust_open_proposals_for_leaver<Q: CustomQuery>(
             .ballots
             .may_load(deps.storage, (prop_id, leaver))?
             .is_none()
+            && !VOTE_COMMITS.has(deps.storage, (prop_id, leaver))
         {
             let mut prop = PROPOSALS.load(deps.storage, prop_id)?;
-            if prop.status == (Status::Open {}) {
+            let voter = match &prop.committee {
+                Some(name) => matches!(
+                    COMMITTEES.may_load(deps.storage, name)?,
+                    Some(committee) if committee.is_member(leaver)
+                ),
+                None => true,
+            };
+            if prop.status == (Status::Open {}) && voter {
                 prop.total_points -= VOTING_POINTS;
                 PROPOSALS.save(deps.storage, prop_id, &prop)?;
             }
@@ -765,134 +2004,224 @@ pub fn execute_check_pending<Q: CustomQuery>(
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
     cw_utils::nonpayable(&info)?;
+    ensure_not_paused(deps.storage, &env.block)?;
 
-    let events = check_pending(deps, &env)?;
+    let pending = check_pending(deps, &env, PENDING_WORK_LIMIT)?;
     let res = Response::new()
         .add_attribute("action", "check_pending")
         .add_attribute("sender", &info.sender)
-        .add_events(events);
+        .add_submessages(pending.messages)
+        .add_events(pending.events);
     Ok(res)
 }
 
+/// Returns a response with the events of all the changes done, and the messages with the
+/// refunds for expired pending members (if any).
+/// At most `limit` members are checked, the rest is left for later calls. Progress is kept in
+/// storage, so every call resumes where the previous one stopped.
+/// Nothing is checked while the trusted circle is paused, as membership changes wait for the
+/// pause to end.
 fn check_pending<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     env: &Env,
-) -> Result<Vec<Event>, ContractError> {
-    // Check if there's a pending escrow, and update escrow_amount if grace period is expired
-    let mut evts = check_pending_escrow(deps.branch(), env)?;
+    limit: usize,
+) -> Result<Response, ContractError> {
+    if ensure_not_paused(deps.storage, &env.block).is_err() {
+        return Ok(Response::new());
+    }
+    let mut left = limit;
+    // Check if there's a pending escrow, and enforce it if grace period is expired
+    let mut evts = check_pending_escrow(deps.branch(), env, &mut left)?;
     // Then, check pending batches
-    evts.extend_from_slice(&check_pending_batches(deps, &env.block)?);
-    Ok(evts)
+    evts.extend_from_slice(&check_pending_batches(
+        deps.branch(),
+        &env.block,
+        &mut left,
+    )?);
+    // Finally, expire pending members who did not pay in time
+    let expired = check_expired_pending(deps, env, &mut left)?;
+    let mut res = Response::new()
+        .add_events(evts)
+        .add_submessages(expired.messages)
+        .add_events(expired.events);
+    if left < limit {
+        res = res.add_event(
+            Event::new(PENDING_WORK_TYPE)
+                .add_attribute("checked", (limit - left).to_string())
+                .add_attribute("limit_reached", (left == 0).to_string()),
+        );
+    }
+    Ok(res)
 }
 
 fn check_pending_escrow<Q: CustomQuery>(
-    mut deps: DepsMut<Q>,
+    deps: DepsMut<Q>,
     env: &Env,
+    limit: &mut usize,
 ) -> Result<Vec<Event>, ContractError> {
-    let mut trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
-    if let Some(pending_escrow) = trusted_circle.escrow_pending {
-        if env.block.time.seconds() >= pending_escrow.grace_ends_at {
-            // Demote all Voting without enough escrow to Pending (pending_escrow > escrow_amount)
-            // Promote all Pending with enough escrow to PendingPaid (pending_escrow < escrow_amount)
-            let evt = pending_escrow_demote_promote_members(
-                deps.branch(),
-                env,
-                pending_escrow.proposal_id,
-                trusted_circle.escrow_amount,
-                pending_escrow.amount,
-                env.block.height,
-            )?;
-
-            // Enforce new escrow from now on
-            trusted_circle.escrow_amount = pending_escrow.amount;
-            trusted_circle.escrow_pending = None;
-            TRUSTED_CIRCLE.save(deps.storage, &trusted_circle)?;
+    let mut change = ESCROW_CHANGE.may_load(deps.storage)?;
+    // A new pending escrow has to wait until the previous one is enforced on all members
+    if change.is_none() {
+        let mut trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+        if let Some(pending_escrow) = trusted_circle.escrow_pending {
+            if env.block.time.seconds() >= pending_escrow.grace_ends_at {
+                if pending_escrow.amount != trusted_circle.escrow_amount {
+                    change = Some(EscrowChange {
+                        proposal_id: pending_escrow.proposal_id,
+                        old_amount: trusted_circle.escrow_amount,
+                        new_amount: pending_escrow.amount,
+                        last_member: None,
+                    });
+                }
 
-            if let Some(evt) = evt {
-                return Ok(vec![evt]);
+                // Enforce new escrow from now on
+                trusted_circle.escrow_amount = pending_escrow.amount;
+                trusted_circle.escrow_pending = None;
+                TRUSTED_CIRCLE.save(deps.storage, &trusted_circle)?;
             }
         }
     }
-    Ok(vec![])
+
+    match change {
+        // Demote all Voting without enough escrow to Pending (pending_escrow > escrow_amount)
+        // Promote all Pending with enough escrow to PendingPaid (pending_escrow < escrow_amount)
+        Some(mut change) => {
+            Ok(
+                pending_escrow_demote_promote_members(deps, env, &mut change, limit)?
+                    .into_iter()
+                    .collect(),
+            )
+        }
+        None => Ok(vec![]),
+    }
 }
 
-/// If new_escrow_amount > escrow_amount:
-/// Iterates over all Voting, and demotes those with not enough escrow to Pending.
-/// Else if new_escrow_amount < escrow_amount:
-/// Iterates over all Pending, and promotes those with enough escrow to PendingPaid
+/// Checks up to `limit` members after the last one checked, and saves the progress.
+/// If new_amount > old_amount:
+/// Demotes Voting with not enough escrow to Pending.
+/// Else if new_amount < old_amount:
+/// Promotes Pending with enough escrow to PendingPaid
 fn pending_escrow_demote_promote_members<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     env: &Env,
-    proposal_id: u64,
-    escrow_amount: Uint128,
-    new_escrow_amount: Uint128,
-    height: u64,
+    change: &mut EscrowChange,
+    limit: &mut usize,
 ) -> Result<Option<Event>, ContractError> {
-    #[allow(clippy::comparison_chain)]
-    if new_escrow_amount > escrow_amount {
-        let demoted: Vec<_> = ESCROWS
-            .range(deps.storage, None, None, Order::Ascending)
-            .filter(|r| match r.as_ref() {
-                Err(_) => true,
-                Ok((_, es)) => es.status == MemberStatus::Voting {} && es.paid < new_escrow_amount,
-            })
-            .collect::<StdResult<_>>()?;
+    if *limit == 0 {
+        ESCROW_CHANGE.save(deps.storage, change)?;
+        return Ok(None);
+    }
+
+    let start = change.last_member.as_ref().map(Bound::exclusive);
+    let escrows = ESCROWS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(*limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    // We ran out of members before hitting the limit, so everybody was checked
+    let done = escrows.len() < *limit;
+    *limit -= escrows.len();
+    if let Some((last, _)) = escrows.last() {
+        change.last_member = Some(last.clone());
+    }
+    if done {
+        ESCROW_CHANGE.remove(deps.storage);
+    } else {
+        ESCROW_CHANGE.save(deps.storage, change)?;
+    }
+
+    let proposal_id = change.proposal_id;
+    let new_escrow_amount = change.new_amount;
+    let height = env.block.height;
+    if new_escrow_amount > change.old_amount {
         let mut evt = Event::new(DEMOTE_TYPE).add_attribute(PROPOSAL_KEY, proposal_id.to_string());
         let mut demoted_addrs = vec![];
-        for (addr, mut escrow_status) in demoted {
+        for (addr, mut escrow_status) in escrows {
+            if escrow_status.status != (MemberStatus::Voting {})
+                || escrow_status.paid >= new_escrow_amount
+            {
+                continue;
+            }
+            let old_status = escrow_status.status;
             escrow_status.status = MemberStatus::Pending { proposal_id };
             ESCROWS.save(deps.storage, &addr, &escrow_status)?;
+            record_escrow(
+                deps.storage,
+                &env.block,
+                &addr,
+                EscrowAction::Demote { proposal_id },
+                Some(&escrow_status),
+            )?;
+            update_reward_points(
+                deps.branch(),
+                &env.block,
+                &addr,
+                Some(old_status),
+                Some(escrow_status.status),
+            )?;
             // Remove voting points
             members().save(deps.storage, &addr, &MemberInfo::new(0), height)?;
             // And adjust TOTAL
-            TOTAL.update::<_, StdError>(deps.storage, |old| {
-                old.checked_sub(VOTING_POINTS)
+            TOTAL.update::<_, StdError>(deps.storage, height, |old| {
+                old.unwrap_or_default()
+                    .checked_sub(VOTING_POINTS)
                     .ok_or_else(|| StdError::generic_err("Total underflow"))
             })?;
-            DISTRIBUTION
-                .apply_points_correction(deps.branch(), &[(&addr, -(VOTING_POINTS as i128))])?;
             demoted_addrs.push(addr.clone());
             evt = evt.add_attribute(MEMBER_KEY, addr);
         }
-        // Create and store batch (so that promotion can work)!
+        if demoted_addrs.is_empty() {
+            return Ok(None);
+        }
+        // Create or extend the batch (so that promotion can work)!
         let grace_period = 0; // promote them as soon as they pay (this is like a "batch of one")
-        create_batch(deps.storage, env, proposal_id, grace_period, &demoted_addrs)?;
-        return Ok(Some(evt));
-    } else if new_escrow_amount < escrow_amount {
-        let promoted: Vec<_> = ESCROWS
-            .range(deps.storage, None, None, Order::Ascending)
-            .filter(|r| match r.as_ref() {
-                Err(_) => true,
-                Ok((_, es)) => match es.status {
-                    MemberStatus::Pending { .. } => es.paid >= new_escrow_amount,
-                    _ => false,
-                },
-            })
-            .collect::<StdResult<_>>()?;
+        add_to_batch(deps.storage, env, proposal_id, grace_period, &demoted_addrs)?;
+        Ok(Some(evt))
+    } else {
         let mut evt = Event::new(PROMOTE_TYPE).add_attribute(PROPOSAL_KEY, proposal_id.to_string());
-        for (addr, mut escrow_status) in promoted {
+        let mut promoted = false;
+        for (addr, mut escrow_status) in escrows {
             // Get _original_ proposal_id, i.e. don't reset proposal_id (So this member is still
             // promoted with its batch).
             let original_proposal_id = match escrow_status.status {
-                MemberStatus::Pending { proposal_id } => proposal_id,
-                _ => unreachable!(),
+                MemberStatus::Pending { proposal_id }
+                    if escrow_status.paid >= new_escrow_amount =>
+                {
+                    proposal_id
+                }
+                _ => continue,
             };
+            let old_status = escrow_status.status;
             escrow_status.status = MemberStatus::PendingPaid {
                 proposal_id: original_proposal_id,
             };
             ESCROWS.save(deps.storage, &addr, &escrow_status)?;
+            update_reward_points(
+                deps.branch(),
+                &env.block,
+                &addr,
+                Some(old_status),
+                Some(escrow_status.status),
+            )?;
+            record_escrow(
+                deps.storage,
+                &env.block,
+                &addr,
+                EscrowAction::EscrowLowered { proposal_id },
+                Some(&escrow_status),
+            )?;
             evt = evt
                 .add_attribute("original_proposal", original_proposal_id.to_string())
                 .add_attribute(MEMBER_KEY, addr);
+            promoted = true;
         }
-        return Ok(Some(evt));
+        Ok(promoted.then_some(evt))
     }
-    Ok(None)
 }
 
 fn check_pending_batches<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     block: &BlockInfo,
+    limit: &mut usize,
 ) -> StdResult<Vec<Event>> {
     let batch_map = batches();
 
@@ -904,19 +2233,146 @@ fn check_pending_batches<Q: CustomQuery>(
     let max_key = (now, u64::MAX);
     let bound = Bound::inclusive(max_key);
 
-    let ready = batch_map
+    // Batches are never empty, so we check at least one member of each
+    let ready = batch_map
+        .idx
+        .promotion_time
+        .sub_prefix(0u8)
+        .range(deps.storage, None, Some(bound), Order::Ascending)
+        .take(*limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut evts = vec![];
+    for (batch_id, mut batch) in ready {
+        if *limit == 0 {
+            break;
+        }
+        evts.push(convert_all_paid_members_to_voters(
+            deps.branch(),
+            batch_id,
+            &mut batch,
+            block,
+            limit,
+        )?);
+    }
+    Ok(evts)
+}
+
+/// Goes through the promoted batches whose payment deadline has passed, and reverts their still
+/// unpaid pending members to non-voting members (or removes them), refunding any partial escrow.
+/// At most `limit` members are checked, a batch left halfway is resumed by the next call.
+fn check_expired_pending<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: &Env,
+    limit: &mut usize,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let deadline = match trusted_circle.payment_deadline_secs() {
+        Some(deadline) => deadline,
+        None => return Ok(res),
+    };
+    let now = env.block.time.seconds();
+    if now < deadline {
+        return Ok(res);
+    }
+
+    // Batches are promoted when their grace period ends, so all batches we are interested in
+    // are already promoted (1), and ordered by their grace end
+    let start = EXPIRED_BATCHES_CURSOR
+        .may_load(deps.storage)?
+        .map(Bound::exclusive);
+    let end = Bound::inclusive((now - deadline, u64::MAX));
+    let expired = batches()
         .idx
         .promotion_time
-        .sub_prefix(0u8)
-        .range(deps.storage, None, Some(bound), Order::Ascending)
+        .sub_prefix(1u8)
+        .range(deps.storage, start, Some(end), Order::Ascending)
+        .take(*limit)
         .collect::<StdResult<Vec<_>>>()?;
 
-    ready
-        .into_iter()
-        .map(|(batch_id, mut batch)| {
-            convert_all_paid_members_to_voters(deps.branch(), batch_id, &mut batch, block.height)
-        })
-        .collect()
+    for (batch_id, mut batch) in expired {
+        if batch.waiting_escrow == 0 {
+            EXPIRED_BATCHES_CURSOR.save(deps.storage, &(batch.grace_ends_at, batch_id))?;
+            continue;
+        }
+        if *limit == 0 {
+            break;
+        }
+
+        let first = batch.next_member as usize;
+        let last = batch.members.len().min(first + *limit);
+        *limit -= last - first;
+
+        let mut evt =
+            Event::new(EXPIRE_PENDING_TYPE).add_attribute(PROPOSAL_KEY, batch_id.to_string());
+        let mut expired_members = 0;
+        for member in batch.members[first..last].iter() {
+            let mut escrow = match ESCROWS.may_load(deps.storage, member)? {
+                Some(escrow)
+                    if escrow.status
+                        == (MemberStatus::Pending {
+                            proposal_id: batch_id,
+                        }) =>
+                {
+                    escrow
+                }
+                _ => continue,
+            };
+
+            if !escrow.paid.is_zero() {
+                escrow_released(deps.storage, &trusted_circle.denom, escrow.paid)?;
+                res = res.add_message(release_escrow(
+                    deps.branch(),
+                    env,
+                    &trusted_circle,
+                    member,
+                    escrow.paid,
+                )?);
+            }
+
+            let action = EscrowAction::Expire {
+                refund: escrow.paid,
+            };
+            let old_status = escrow.status;
+            if trusted_circle.remove_expired_pending {
+                members().remove(deps.storage, member, env.block.height)?;
+                ESCROWS.remove(deps.storage, member);
+                record_escrow(deps.storage, &env.block, member, action, None)?;
+                update_reward_points(deps.branch(), &env.block, member, Some(old_status), None)?;
+            } else {
+                escrow = EscrowStatus::non_voting();
+                ESCROWS.save(deps.storage, member, &escrow)?;
+                record_escrow(deps.storage, &env.block, member, action, Some(&escrow))?;
+                update_reward_points(
+                    deps.branch(),
+                    &env.block,
+                    member,
+                    Some(old_status),
+                    Some(escrow.status),
+                )?;
+            }
+            evt = evt.add_attribute(MEMBER_KEY, member);
+            expired_members += 1;
+        }
+
+        batch.waiting_escrow = batch.waiting_escrow.saturating_sub(expired_members);
+        if last == batch.members.len() {
+            batch.next_member = 0;
+            EXPIRED_BATCHES_CURSOR.save(deps.storage, &(batch.grace_ends_at, batch_id))?;
+        } else {
+            batch.next_member = last as u32;
+        }
+        batches().save(deps.storage, batch_id, &batch)?;
+
+        if expired_members > 0 {
+            res = res.add_event(
+                evt.add_attribute("removed", trusted_circle.remove_expired_pending.to_string()),
+            );
+        }
+    }
+
+    Ok(res)
 }
 
 pub fn proposal_execute<Q: CustomQuery>(
@@ -942,7 +2398,141 @@ pub fn proposal_execute<Q: CustomQuery>(
             proposal_whitelist_contract_addr(deps, env, &addr)
         }
         ProposalContent::RemoveContract(addr) => proposal_remove_contract_addr(deps, env, &addr),
+        ProposalContent::TreasurySpend {
+            recipient,
+            amount,
+            vesting,
+        } => proposal_treasury_spend(deps, env, proposal_id, recipient, amount, vesting),
+        ProposalContent::Poll { .. } => Ok(Response::new().add_attribute("proposal", "poll")),
+        ProposalContent::Pause { hours } => proposal_pause(deps, env, hours),
+        ProposalContent::Unpause {} => proposal_unpause(deps),
+        ProposalContent::SetContractPermissions {
+            contract,
+            permissions,
+        } => proposal_set_contract_permissions(deps, contract, permissions),
+        ProposalContent::CreateCommittee {
+            name,
+            members,
+            powers,
+            rules,
+        } => proposal_create_committee(deps, name, members, powers, rules),
+        ProposalContent::DissolveCommittee { name } => proposal_dissolve_committee(deps, name),
+    }
+}
+
+pub fn proposal_create_committee<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    name: String,
+    members: Vec<String>,
+    powers: Vec<ProposalKind>,
+    rules: VotingRules,
+) -> Result<Response, ContractError> {
+    let members = members
+        .iter()
+        .map(|member| deps.api.addr_validate(member))
+        .collect::<StdResult<_>>()?;
+    let committee = Committee {
+        members,
+        powers,
+        rules,
+    };
+    COMMITTEES.save(deps.storage, &name, &committee)?;
+    Ok(Response::new()
+        .add_attribute("proposal", "create_committee")
+        .add_attribute("committee", name))
+}
+
+pub fn proposal_dissolve_committee<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    name: String,
+) -> Result<Response, ContractError> {
+    load_committee(deps.storage, &name)?;
+    COMMITTEES.remove(deps.storage, &name);
+    Ok(Response::new()
+        .add_attribute("proposal", "dissolve_committee")
+        .add_attribute("committee", name))
+}
+
+/// Whitelisted contracts are non-voting members
+fn ensure_whitelisted(storage: &dyn Storage, contract: &Addr) -> Result<(), ContractError> {
+    match members().may_load(storage, contract)? {
+        Some(member) if member.points == 0 => Ok(()),
+        _ => Err(ContractError::NotWhitelisted(contract.to_string())),
+    }
+}
+
+pub fn proposal_set_contract_permissions<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    contract: String,
+    permissions: ContractPermissions,
+) -> Result<Response, ContractError> {
+    // Validated with the proposal, but it may have been removed since
+    let contract = Addr::unchecked(contract);
+    ensure_whitelisted(deps.storage, &contract)?;
+    CONTRACT_PERMISSIONS.save(deps.storage, &contract, &permissions)?;
+
+    let res = Response::new()
+        .add_attribute("proposal", "set_contract_permissions")
+        .add_attribute("contract", contract);
+    Ok(res)
+}
+
+const MAX_PAUSE_HOURS: u32 = 30 * 24;
+
+fn validate_pause_period(hours: u32) -> Result<(), ContractError> {
+    if hours == 0 || hours > MAX_PAUSE_HOURS {
+        return Err(ContractError::InvalidPausePeriod(hours));
+    }
+    Ok(())
+}
+
+pub fn execute_pause<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    hours: u32,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    if trusted_circle.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized(
+            "Only the guardian can pause the trusted circle".to_owned(),
+        ));
+    }
+    validate_pause_period(hours)?;
+    // The guardian cannot extend its own pause, only a vote can
+    if let Err(ContractError::Paused { until }) = ensure_not_paused(deps.storage, &env.block) {
+        return Err(ContractError::AlreadyPaused { until });
     }
+
+    let until = env.block.time.plus_seconds(hours as u64 * 3600);
+    PAUSED_UNTIL.save(deps.storage, &until)?;
+
+    let res = Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("sender", info.sender)
+        .add_attribute("until", until.to_string());
+    Ok(res)
+}
+
+pub fn proposal_pause<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    hours: u32,
+) -> Result<Response, ContractError> {
+    let until = env.block.time.plus_seconds(hours as u64 * 3600);
+    PAUSED_UNTIL.save(deps.storage, &until)?;
+
+    let res = Response::new()
+        .add_attribute("proposal", "pause")
+        .add_attribute("until", until.to_string());
+    Ok(res)
+}
+
+pub fn proposal_unpause<Q: CustomQuery>(deps: DepsMut<Q>) -> Result<Response, ContractError> {
+    PAUSED_UNTIL.remove(deps.storage);
+    Ok(Response::new().add_attribute("proposal", "unpause"))
 }
 
 pub fn proposal_add_remove_non_voting_members<Q: CustomQuery>(
@@ -958,7 +2548,7 @@ pub fn proposal_add_remove_non_voting_members<Q: CustomQuery>(
 
     let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
     // make the local update
-    let ev = add_remove_non_voting_members(deps, &trusted_circle, env.block.height, add, remove)?;
+    let ev = add_remove_non_voting_members(deps, &trusted_circle, &env.block, add, remove)?;
     Ok(res.add_events(ev))
 }
 
@@ -981,7 +2571,7 @@ pub fn proposal_edit_trusted_circle<Q: CustomQuery>(
 }
 
 pub fn proposal_add_voting_members<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+    mut deps: DepsMut<Q>,
     env: Env,
     proposal_id: u64,
     to_add: Vec<String>,
@@ -1011,7 +2601,7 @@ pub fn proposal_add_voting_members<Q: CustomQuery>(
         evt = evt.add_attribute(MEMBER_KEY, &add);
         let old = ESCROWS.may_load(deps.storage, &add)?;
         // Only add the member if it does not already exist or is non-voting
-        let create = match old {
+        let create = match &old {
             Some(val) => matches!(val.status, MemberStatus::NonVoting {}),
             None => true,
         };
@@ -1019,6 +2609,20 @@ pub fn proposal_add_voting_members<Q: CustomQuery>(
             members().save(deps.storage, &add, &MemberInfo::new(0), height)?;
             // Create member entry in escrow (with no funds)
             ESCROWS.save(deps.storage, &add, &escrow)?;
+            update_reward_points(
+                deps.branch(),
+                &env.block,
+                &add,
+                old.map(|old| old.status),
+                Some(escrow.status),
+            )?;
+            record_escrow(
+                deps.storage,
+                &env.block,
+                &add,
+                EscrowAction::AddVoting { proposal_id },
+                Some(&escrow),
+            )?;
         }
     }
 
@@ -1040,7 +2644,7 @@ pub fn proposal_whitelist_contract_addr<Q: CustomQuery>(
         .add_attribute("proposal", "whitelist_contract_addr")
         .add_attribute("addr", addr);
 
-    let ev = whitelist_contract_addr(deps, env.block.height, addr)?;
+    let ev = whitelist_contract_addr(deps, &env.block, addr)?;
     Ok(res.add_events(ev))
 }
 
@@ -1053,7 +2657,7 @@ pub fn proposal_remove_contract_addr<Q: CustomQuery>(
         .add_attribute("proposal", "remove_contract_addr")
         .add_attribute("addr", addr);
 
-    let ev = remove_contract_addr(deps, env.block.height, addr)?;
+    let ev = remove_contract_addr(deps, &env.block, addr)?;
     Ok(res.add_events(ev))
 }
 
@@ -1077,9 +2681,9 @@ fn ensure_not_denied<Q: CustomQuery>(
 
 // This is a helper used both on instantiation as well as on passed proposals
 pub fn add_remove_non_voting_members<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+    mut deps: DepsMut<Q>,
     config: &TrustedCircle,
-    height: u64,
+    block: &BlockInfo,
     to_add: Vec<String>,
     to_remove: Vec<String>,
 ) -> Result<Vec<Event>, ContractError> {
@@ -1107,9 +2711,11 @@ pub fn add_remove_non_voting_members<Q: CustomQuery>(
         // If the member already exists, the update for that member is ignored
         if old.is_none() {
             // update member value
-            members().save(deps.storage, &add_addr, &MemberInfo::new(0), height)?;
+            members().save(deps.storage, &add_addr, &MemberInfo::new(0), block.height)?;
             // set status
-            ESCROWS.save(deps.storage, &add_addr, &EscrowStatus::non_voting())?;
+            let escrow = EscrowStatus::non_voting();
+            ESCROWS.save(deps.storage, &add_addr, &escrow)?;
+            update_reward_points(deps.branch(), block, &add_addr, None, Some(escrow.status))?;
         }
     }
 
@@ -1120,8 +2726,15 @@ pub fn add_remove_non_voting_members<Q: CustomQuery>(
         // Ignore non-members
         if let Some(escrow) = old {
             if matches!(escrow.status, MemberStatus::NonVoting {}) {
-                members().remove(deps.storage, &remove_addr, height)?;
+                members().remove(deps.storage, &remove_addr, block.height)?;
                 ESCROWS.remove(deps.storage, &remove_addr);
+                update_reward_points(
+                    deps.branch(),
+                    block,
+                    &remove_addr,
+                    Some(escrow.status),
+                    None,
+                )?;
             } else {
                 return Err(ContractError::VotingMember(remove));
             }
@@ -1155,6 +2768,11 @@ pub fn proposal_punish_members<Q: CustomQuery>(
                 kick_out,
                 ..
             } => (member, slashing_percentage, kick_out),
+            Punishment::RewardEscrow {
+                member,
+                slashing_percentage,
+                kick_out,
+            } => (member, slashing_percentage, kick_out),
         };
 
         let addr = Addr::unchecked(member);
@@ -1166,13 +2784,28 @@ pub fn proposal_punish_members<Q: CustomQuery>(
             ));
         }
 
+        // Punished members lose their rewards which didn't vest yet
+        let points = reward_points(deps.storage, &escrow_status.status)?;
+        if points > 0 {
+            let forfeited = DISTRIBUTION.forfeit_unvested(
+                deps.branch(),
+                &addr,
+                points.into(),
+                env.block.time,
+            )?;
+            if !forfeited.amount.is_zero() {
+                res = res.add_attribute("forfeited", forfeited.to_string());
+            }
+        }
+
         let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
-        let trusted_circle_denom = trusted_circle.clone().denom;
 
         // Distribution amount
         let escrow_slashed = (escrow_status.paid * slashing_percentage).u128();
         // Remaining escrow amount
         let mut escrow_remaining = escrow_status.paid.u128() - escrow_slashed;
+        // Amount to be distributed as rewards, once the member status is adjusted
+        let mut escrow_rewarded = 0;
 
         if escrow_slashed > 0 {
             // Distribute / burn
@@ -1184,47 +2817,98 @@ pub fn proposal_punish_members<Q: CustomQuery>(
                     let escrow_remainder = escrow_slashed % distribution_list.len() as u128;
                     for distr_addr in distribution_list {
                         // Generate Bank message with distribution payment
-                        res = res.add_message(BankMsg::Send {
-                            to_address: distr_addr.clone(),
-                            amount: vec![coin(escrow_each, trusted_circle_denom.clone())],
-                        });
+                        res = res.add_message(release_escrow(
+                            deps.branch(),
+                            &env,
+                            &trusted_circle,
+                            &Addr::unchecked(distr_addr),
+                            escrow_each.into(),
+                        )?);
                     }
                     // Keep remainder escrow in member account
                     escrow_remaining += escrow_remainder;
                 }
                 Punishment::BurnEscrow { .. } => {
-                    res = res.add_message(BankMsg::Burn {
-                        amount: vec![coin(escrow_slashed, trusted_circle_denom)],
-                    });
+                    res = res.add_message(burn_escrow(
+                        deps.branch(),
+                        &env,
+                        &trusted_circle,
+                        escrow_slashed.into(),
+                    )?);
+                }
+                Punishment::RewardEscrow { .. } => {
+                    let unbond = unbond_escrow(
+                        deps.branch(),
+                        &env,
+                        &trusted_circle,
+                        &addr,
+                        escrow_slashed.into(),
+                    )?;
+                    match unbond {
+                        // Distributed by `DistributeRewards` once it is unbonded
+                        Some(unbond) => res = res.add_message(unbond),
+                        // Funds stay in the contract
+                        None => escrow_rewarded = escrow_slashed,
+                    }
                 }
             }
         }
 
         // Adjust remaining escrow / status
+        let released = escrow_status.paid.u128() - escrow_remaining;
+        escrow_released(deps.storage, &trusted_circle.denom, released.into())?;
         escrow_status.paid = escrow_remaining.into();
+        if escrow_slashed > 0 {
+            let slash = EscrowAction::Slash {
+                proposal_id,
+                amount: Uint128::new(escrow_slashed),
+            };
+            record_escrow(deps.storage, &env.block, &addr, slash, Some(&escrow_status))?;
+        }
         let required_escrow = trusted_circle.get_escrow();
         if kick_out {
             let attrs =
-                trigger_long_leave(deps.branch(), env.clone(), addr, escrow_status)?.attributes;
+                trigger_long_leave(deps.branch(), env.clone(), addr.clone(), escrow_status)?
+                    .attributes;
             res.attributes.extend_from_slice(&attrs);
         } else if escrow_status.paid < required_escrow {
             // If it's a voting member, reduce vote to 0 (otherwise, it is already 0)
             if escrow_status.status == (MemberStatus::Voting {}) {
                 members().save(deps.storage, &addr, &MemberInfo::new(0), env.block.height)?;
-                TOTAL.update::<_, StdError>(deps.storage, |old| {
-                    old.checked_sub(VOTING_POINTS)
+                TOTAL.update::<_, StdError>(deps.storage, env.block.height, |old| {
+                    old.unwrap_or_default()
+                        .checked_sub(VOTING_POINTS)
                         .ok_or_else(|| StdError::generic_err("Total underflow"))
                 })?;
-                DISTRIBUTION
-                    .apply_points_correction(deps.branch(), &[(&addr, -(VOTING_POINTS as i128))])?;
             }
+            let old_status = escrow_status.status;
             escrow_status.status = MemberStatus::Pending { proposal_id };
             ESCROWS.save(deps.storage, &addr, &escrow_status)?;
-            demoted_addrs.push(addr);
+            update_reward_points(
+                deps.branch(),
+                &env.block,
+                &addr,
+                Some(old_status),
+                Some(escrow_status.status),
+            )?;
+            record_escrow(
+                deps.storage,
+                &env.block,
+                &addr,
+                EscrowAction::Demote { proposal_id },
+                Some(&escrow_status),
+            )?;
+            demoted_addrs.push(addr.clone());
         } else {
             // Just update remaining escrow
             ESCROWS.save(deps.storage, &addr, &escrow_status)?;
         };
+
+        if escrow_rewarded > 0 {
+            // The punished member doesn't get a share of its own slashed escrow
+            let rewarded = distribute_slashed_escrow(deps.branch(), &addr, escrow_rewarded)?;
+            res = res.add_attribute("rewarded", rewarded.to_string());
+        }
     }
 
     if !demoted_addrs.is_empty() {
@@ -1249,26 +2933,33 @@ pub fn proposal_punish_members<Q: CustomQuery>(
 
 pub fn whitelist_contract_addr<Q: CustomQuery>(
     deps: DepsMut<Q>,
-    height: u64,
+    block: &BlockInfo,
     addr: &str,
 ) -> Result<Vec<Event>, ContractError> {
     let ev = Event::new(WHITELIST_TYPE).add_attribute(CONTRACT_ADDR_KEY, addr);
     let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
 
-    add_remove_non_voting_members(deps, &trusted_circle, height, vec![addr.into()], vec![])?;
+    // Whitelisted without any permission, unless whitelisted already
+    let contract = Addr::unchecked(addr);
+    if !CONTRACT_PERMISSIONS.has(deps.storage, &contract) {
+        CONTRACT_PERMISSIONS.save(deps.storage, &contract, &ContractPermissions::default())?;
+    }
+    add_remove_non_voting_members(deps, &trusted_circle, block, vec![addr.into()], vec![])?;
 
     Ok(vec![ev])
 }
 
 pub fn remove_contract_addr<Q: CustomQuery>(
     deps: DepsMut<Q>,
-    height: u64,
+    block: &BlockInfo,
     addr: &str,
 ) -> Result<Vec<Event>, ContractError> {
     let ev = Event::new(REMOVE_TYPE).add_attribute(CONTRACT_ADDR_KEY, addr);
     let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
 
-    add_remove_non_voting_members(deps, &trusted_circle, height, vec![], vec![addr.into()])?;
+    CONTRACT_PERMISSIONS.remove(deps.storage, &Addr::unchecked(addr));
+
+    add_remove_non_voting_members(deps, &trusted_circle, block, vec![], vec![addr.into()])?;
 
     Ok(vec![ev])
 }
@@ -1297,6 +2988,264 @@ pub fn is_contract<Q: CustomQuery>(querier: &QuerierWrapper<Q>, addr: &Addr) ->
     }
 }
 
+/// How many members / proposals are checked at most on every end block.
+/// This keeps the gas used by the end blocker bounded, the rest are handled in the following blocks
+const END_BLOCK_LIMIT: usize = 10;
+
+/// Loads the import in progress, if `sender` is its importer
+fn load_import(storage: &dyn Storage, sender: &Addr) -> Result<Import, ContractError> {
+    let import = IMPORT
+        .may_load(storage)?
+        .ok_or(ContractError::NotImporting {})?;
+    if import.importer != *sender {
+        return Err(ContractError::Unauthorized(
+            "Only the importer can import the state".to_owned(),
+        ));
+    }
+    Ok(import)
+}
+
+pub fn execute_import_state<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    snapshot: StateSnapshot,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    let mut import = load_import(deps.storage, &info.sender)?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(ContractError::InvalidSnapshot(format!(
+            "Unsupported version {}",
+            snapshot.version
+        )));
+    }
+
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    match (snapshot.header, &import.expected) {
+        (Some(header), None) => {
+            if header.denom != trusted_circle.denom
+                || header.escrow_amount != trusted_circle.escrow_amount
+            {
+                return Err(ContractError::InvalidSnapshot(
+                    "Escrow doesn't match the exported trusted circle".to_owned(),
+                ));
+            }
+            REWARD_WEIGHTS.save(deps.storage, &header.reward_weights)?;
+            DISTRIBUTION.import(deps.storage, &header.distribution)?;
+            PROPOSAL_COUNT.save(deps.storage, &header.proposal_count)?;
+            import.expected = Some(header.totals);
+        }
+        (None, Some(_)) => {}
+        (Some(_), Some(_)) => {
+            return Err(ContractError::InvalidSnapshot(
+                "Header already imported".to_owned(),
+            ))
+        }
+        (None, None) => {
+            return Err(ContractError::InvalidSnapshot(
+                "The first page has to include the header".to_owned(),
+            ))
+        }
+    }
+
+    let weights = REWARD_WEIGHTS.load(deps.storage)?;
+    let proposal_count = PROPOSAL_COUNT.load(deps.storage)?;
+    let entries = snapshot.entries.len();
+    for entry in snapshot.entries {
+        match entry {
+            SnapshotEntry::Member {
+                addr,
+                escrow,
+                points,
+            } => {
+                let addr = deps.api.addr_validate(&addr)?;
+                if ESCROWS.has(deps.storage, &addr) {
+                    return Err(ContractError::InvalidSnapshot(format!(
+                        "Member {} imported twice",
+                        addr
+                    )));
+                }
+                let voting = matches!(escrow.status, MemberStatus::Voting {});
+                if voting != (points.unwrap_or_default() > 0) {
+                    return Err(ContractError::InvalidSnapshot(format!(
+                        "Voting points of {} don't match its status",
+                        addr
+                    )));
+                }
+
+                ESCROWS.save(deps.storage, &addr, &escrow)?;
+                escrow_deposited(deps.storage, &trusted_circle.denom, escrow.paid)?;
+                track_membership(deps.storage, &addr)?;
+                if let Some(points) = points {
+                    members().save(
+                        deps.storage,
+                        &addr,
+                        &MemberInfo::new(points),
+                        env.block.height,
+                    )?;
+                    import.imported.points += points;
+                }
+                import.imported.reward_points += weights.points(&escrow.status);
+                import.imported.escrow += escrow.paid;
+            }
+            SnapshotEntry::Adjustment { addr, adjustment } => {
+                let addr = deps.api.addr_validate(&addr)?;
+                DISTRIBUTION.import_adjustment(deps.storage, &addr, &adjustment)?;
+            }
+            SnapshotEntry::Batch { id, batch } => {
+                // Members are exported first. Those of batches still to be promoted are loaded
+                // on promotion, and cannot be pending in another batch
+                if !batch.batch_promoted {
+                    for member in &batch.members {
+                        let err = match ESCROWS.may_load(deps.storage, member)?.map(|e| e.status) {
+                            None => "is not imported",
+                            Some(MemberStatus::Pending { proposal_id })
+                            | Some(MemberStatus::PendingPaid { proposal_id })
+                                if proposal_id != id =>
+                            {
+                                "is pending in another batch"
+                            }
+                            _ => continue,
+                        };
+                        return Err(ContractError::InvalidSnapshot(format!(
+                            "Member {} of batch {} {}",
+                            member, id, err
+                        )));
+                    }
+                }
+                batches().save(deps.storage, id, &batch)?;
+            }
+            SnapshotEntry::Proposal { id, mut proposal } => {
+                if id > proposal_count {
+                    return Err(ContractError::InvalidSnapshot(format!(
+                        "Proposal {} is past the proposal count",
+                        id
+                    )));
+                }
+                // Ballots are not exported, so they could be cast again
+                proposal.update_status(&env.block);
+                if proposal.status == Status::Open {
+                    return Err(ContractError::InvalidSnapshot(format!(
+                        "Proposal {} is still open for voting",
+                        id
+                    )));
+                }
+                // Passed proposals were decided by the members of the exported trusted circle,
+                // so they are not executed here
+                if proposal.status == Status::Passed {
+                    proposal.status = Status::Rejected;
+                }
+                PROPOSALS.save(deps.storage, id, &proposal)?;
+            }
+        }
+    }
+    IMPORT.save(deps.storage, &import)?;
+
+    let res = Response::new()
+        .add_attribute("action", "import_state")
+        .add_attribute("sender", info.sender)
+        .add_attribute("entries", entries.to_string());
+    Ok(res)
+}
+
+pub fn execute_finish_import<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    let import = load_import(deps.storage, &info.sender)?;
+    let expected = import
+        .expected
+        .ok_or_else(|| ContractError::InvalidSnapshot("Header not imported".to_owned()))?;
+    if import.imported != expected {
+        return Err(ContractError::InvalidSnapshot(format!(
+            "Imported totals {:?} don't match the exported {:?}",
+            import.imported, expected
+        )));
+    }
+
+    // Escrow and rewards not withdrawn yet have to be transferred to the contract beforehand
+    let denom = TRUSTED_CIRCLE.load(deps.storage)?.denom;
+    let reward_denom = DISTRIBUTION.denom(deps.storage)?;
+    let withdrawable = DISTRIBUTION.withdrawable_total(deps.storage)?;
+    let mut needed = vec![(denom, expected.escrow)];
+    match needed.iter_mut().find(|(denom, _)| *denom == reward_denom) {
+        Some((_, amount)) => *amount += withdrawable,
+        None => needed.push((reward_denom, withdrawable)),
+    }
+    for (denom, amount) in needed {
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &denom)?
+            .amount;
+        if balance < amount {
+            return Err(ContractError::InvalidSnapshot(format!(
+                "Contract holds {}{}, {}{} needed",
+                balance, denom, amount, denom
+            )));
+        }
+    }
+
+    TOTAL.save(deps.storage, &expected.points, env.block.height)?;
+    REWARD_TOTAL.save(deps.storage, &expected.reward_points)?;
+    IMPORT.remove(deps.storage);
+
+    let res = Response::new()
+        .add_attribute("action", "finish_import")
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(
+    deps: DepsMut<TgradeQuery>,
+    env: Env,
+    msg: TgradeSudoMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        TgradeSudoMsg::PrivilegeChange(PrivilegeChangeMsg::Promoted {}) => privilege_promote(deps),
+        TgradeSudoMsg::EndBlock {} => end_block(deps, env),
+        _ => Err(ContractError::UnknownSudoMsg {}),
+    }
+}
+
+fn privilege_promote<Q: CustomQuery>(_deps: DepsMut<Q>) -> Result<Response, ContractError> {
+    let msgs = request_privileges(&[Privilege::EndBlocker]);
+    Ok(Response::new().add_submessages(msgs))
+}
+
+/// Does the housekeeping otherwise triggered by `CheckPending` and `Close`, so it doesn't depend
+/// on someone calling them
+fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
+    // Nothing to do until the import is finished
+    if IMPORT.may_load(deps.storage)?.is_some() {
+        return Ok(Response::new());
+    }
+    let pending = check_pending(deps.branch(), &env, END_BLOCK_LIMIT)?;
+    let mut res = Response::new()
+        .add_submessages(pending.messages)
+        .add_events(pending.events);
+    if let Some(evt) = close_expired_proposals(deps.branch(), &env, END_BLOCK_LIMIT)? {
+        res = res.add_event(evt);
+    }
+    Ok(res.add_messages(membership_token_msgs(deps.storage)?))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut<TgradeQuery>, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        MEMBERSHIP_NFT_REPLY_ID => {
+            let msgs = membership_nft_instantiated(deps.storage, msg)?;
+            let nft = MEMBERSHIP_NFT.load(deps.storage)?;
+            Ok(Response::new()
+                .add_attribute("membership_nft", nft)
+                .add_messages(msgs))
+        }
+        id => Err(ContractError::UnknownReplyId(id)),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     use QueryMsg::*;
@@ -1312,9 +3261,24 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Bina
             to_binary(&list_non_voting_members(deps, start_after, limit)?)
         }
         TotalPoints {} => to_binary(&query_total_points(deps)?),
-        TrustedCircle {} => to_binary(&query_trusted_circle(deps)?),
+        TotalPointsAtHeight { at_height } => {
+            to_binary(&query_total_points_at_height(deps, at_height)?)
+        }
+        ListMembersAtHeight {
+            at_height,
+            start_after,
+            limit,
+        } => to_binary(&list_members_at_height(
+            deps,
+            at_height,
+            start_after,
+            limit,
+            false,
+        )?),
+        TrustedCircle {} => to_binary(&query_trusted_circle(deps, env)?),
         Rules {} => to_binary(&query_rules(deps)?),
         Proposal { proposal_id } => to_binary(&query_proposal(deps, env, proposal_id)?),
+        ProposalTally { proposal_id } => to_binary(&query_proposal_tally(deps, env, proposal_id)?),
         Vote { proposal_id, voter } => to_binary(&query_vote(deps, proposal_id, voter)?),
         ListProposals { start_after, limit } => {
             to_binary(&list_proposals(deps, env, start_after, limit, false)?)
@@ -1341,11 +3305,148 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Bina
         ListVoters { start_after, limit } => {
             to_binary(&list_voting_members(deps, start_after, limit)?)
         }
+        ListVotersAtHeight {
+            at_height,
+            start_after,
+            limit,
+        } => to_binary(&list_members_at_height(
+            deps,
+            at_height,
+            start_after,
+            limit,
+            true,
+        )?),
         ListEscrows { start_after, limit } => to_binary(&list_escrows(deps, start_after, limit)?),
-        WithdrawableRewards { owner } => to_binary(&query_withdrawable_funds(deps, owner)?),
+        EscrowHistory {
+            addr,
+            start_after,
+            limit,
+        } => to_binary(&query_escrow_history(deps, addr, start_after, limit)?),
+        EscrowClaims { owner } => to_binary(&query_escrow_claims(deps, owner)?),
+        WithdrawableRewards { owner } => to_binary(&query_withdrawable_funds(deps, env, owner)?),
+        UnvestedRewards { owner } => to_binary(&query_unvested_funds(deps, env, owner)?),
+        Delegated { owner } => to_binary(&query_delegated(deps, owner)?),
+        RewardWeights {} => to_binary(&query_reward_weights(deps)?),
         DistributedRewards {} => to_binary(&query_distributed_funds(deps)?),
         UndistributedRewards {} => to_binary(&query_undistributed_funds(deps, env)?),
+        PendingWork {} => to_binary(&query_pending_work(deps, env)?),
+        Treasury {} => to_binary(&query_treasury(deps)?),
+        VotingKey { member } => to_binary(&query_voting_key(deps, member)?),
+        ListSpends {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&list_spends(deps, env, recipient, start_after, limit)?),
+        ContractPermissions { addr } => to_binary(&query_contract_permissions(deps, addr)?),
+        Draft { draft_id } => to_binary(&query_draft(deps, draft_id)?),
+        ListDrafts {
+            author,
+            start_after,
+            limit,
+        } => to_binary(&list_drafts(deps, author, start_after, limit)?),
+        Committee { name } => to_binary(&query_committee(deps, name)?),
+        ListCommittees { start_after, limit } => {
+            to_binary(&list_committees(deps, start_after, limit)?)
+        }
+        ExportState { start_after, limit } => to_binary(&export_state(deps, start_after, limit)?),
+    }
+}
+
+pub(crate) fn query_pending_work<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+) -> StdResult<PendingWorkResponse> {
+    let now = env.block.time.seconds();
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let mut capped = false;
+
+    // Either an escrow change in progress, or a pending one ready to be enforced
+    let escrow_start = match ESCROW_CHANGE.may_load(deps.storage)? {
+        Some(change) => Some(change.last_member),
+        None => match &trusted_circle.escrow_pending {
+            Some(pending)
+                if now >= pending.grace_ends_at
+                    && pending.amount != trusted_circle.escrow_amount =>
+            {
+                Some(None)
+            }
+            _ => None,
+        },
+    };
+    let escrow_members = match escrow_start {
+        Some(last_member) => {
+            let start = last_member.as_ref().map(Bound::exclusive);
+            let count = ESCROWS
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(PENDING_WORK_QUERY_LIMIT)
+                .count();
+            capped |= count == PENDING_WORK_QUERY_LIMIT;
+            count as u64
+        }
+        None => 0,
+    };
+
+    let remaining = |batch: &Batch| (batch.members.len() - batch.next_member as usize) as u64;
+
+    let mut promotion_batches = 0;
+    let mut promotion_members = 0;
+    for item in batches()
+        .idx
+        .promotion_time
+        .sub_prefix(0u8)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive((now, u64::MAX))),
+            Order::Ascending,
+        )
+        .take(PENDING_WORK_QUERY_LIMIT)
+    {
+        let (_, batch) = item?;
+        promotion_batches += 1;
+        promotion_members += remaining(&batch);
+    }
+
+    let mut expiry_batches = 0;
+    let mut expiry_members = 0;
+    let mut expiry_scanned = 0;
+    if let Some(deadline) = trusted_circle.payment_deadline_secs() {
+        if now >= deadline {
+            let start = EXPIRED_BATCHES_CURSOR
+                .may_load(deps.storage)?
+                .map(Bound::exclusive);
+            for item in batches()
+                .idx
+                .promotion_time
+                .sub_prefix(1u8)
+                .range(
+                    deps.storage,
+                    start,
+                    Some(Bound::inclusive((now - deadline, u64::MAX))),
+                    Order::Ascending,
+                )
+                .take(PENDING_WORK_QUERY_LIMIT)
+            {
+                let (_, batch) = item?;
+                expiry_scanned += 1;
+                if batch.waiting_escrow > 0 {
+                    expiry_batches += 1;
+                    expiry_members += remaining(&batch);
+                }
+            }
+        }
     }
+    capped |= promotion_batches == PENDING_WORK_QUERY_LIMIT as u64
+        || expiry_scanned == PENDING_WORK_QUERY_LIMIT;
+
+    Ok(PendingWorkResponse {
+        escrow_members,
+        promotion_batches,
+        promotion_members,
+        expiry_batches,
+        expiry_members,
+        capped,
+    })
 }
 
 pub(crate) fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsResponse> {
@@ -1353,6 +3454,16 @@ pub(crate) fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<Tot
     Ok(TotalPointsResponse { points })
 }
 
+pub(crate) fn query_total_points_at_height<Q: CustomQuery>(
+    deps: Deps<Q>,
+    height: u64,
+) -> StdResult<TotalPointsResponse> {
+    let points = TOTAL
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+    Ok(TotalPointsResponse { points })
+}
+
 pub(crate) fn query_rules<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<RulesResponse> {
     let rules = TRUSTED_CIRCLE.load(deps.storage)?.rules;
     Ok(RulesResponse { rules })
@@ -1360,7 +3471,10 @@ pub(crate) fn query_rules<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<RulesRespo
 
 pub(crate) fn query_trusted_circle<Q: CustomQuery>(
     deps: Deps<Q>,
+    env: Env,
 ) -> StdResult<TrustedCircleResponse> {
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let draft_period = trusted_circle.draft_period();
     let TrustedCircle {
         name,
         denom,
@@ -1369,7 +3483,17 @@ pub(crate) fn query_trusted_circle<Q: CustomQuery>(
         rules,
         deny_list,
         edit_trusted_circle_disabled,
-    } = TRUSTED_CIRCLE.load(deps.storage)?;
+        payment_deadline,
+        remove_expired_pending,
+        urgent_rules,
+        secret_rules,
+        guardian,
+        draft_period: _,
+        stake_contract,
+    } = trusted_circle;
+    let paused_until = PAUSED_UNTIL
+        .may_load(deps.storage)?
+        .filter(|until| env.block.time < *until);
     Ok(TrustedCircleResponse {
         name,
         denom,
@@ -1378,6 +3502,15 @@ pub(crate) fn query_trusted_circle<Q: CustomQuery>(
         rules,
         deny_list,
         edit_trusted_circle_disabled,
+        payment_deadline,
+        remove_expired_pending,
+        urgent_rules,
+        secret_rules,
+        guardian,
+        paused_until,
+        draft_period,
+        stake_contract,
+        membership_nft: MEMBERSHIP_NFT.may_load(deps.storage)?,
     })
 }
 
@@ -1405,6 +3538,8 @@ pub(crate) fn query_escrow<Q: CustomQuery>(
 // settings for pagination
 const MAX_LIMIT: u32 = 100;
 const DEFAULT_LIMIT: u32 = 30;
+/// How many members `list_members_at_height` checks at most for every member requested
+const AT_HEIGHT_SCAN_FACTOR: usize = 10;
 
 pub(crate) fn list_members<Q: CustomQuery>(
     deps: Deps<Q>,
@@ -1431,6 +3566,59 @@ pub(crate) fn list_members<Q: CustomQuery>(
     Ok(MemberListResponse { members: members? })
 }
 
+/// Lists members as they were at the beginning of `height` (only those with points, if
+/// `voters_only`). Members removed since then are not in the members map anymore, so we go
+/// through its changelog instead, which has entries for every member ever added. Only the first
+/// change of every member is read, skipping to the next member right after, so the cost doesn't
+/// depend on how many times members changed. At most `AT_HEIGHT_SCAN_FACTOR` members are checked
+/// per member requested, so skipping non-voting members stays bounded.
+pub(crate) fn list_members_at_height<Q: CustomQuery>(
+    deps: Deps<Q>,
+    height: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    voters_only: bool,
+) -> StdResult<MembersAtHeightResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut last = maybe_addr(deps.api, start_after)?;
+
+    let mut members_at_height = vec![];
+    let mut checked_all = false;
+    for _ in 0..limit * AT_HEIGHT_SCAN_FACTOR {
+        // Skip all the changes of the last member
+        let start = last.as_ref().map(|addr| Bound::exclusive((addr, u64::MAX)));
+        let addr = match members()
+            .changelog()
+            .keys(deps.storage, start, None, Order::Ascending)
+            .next()
+        {
+            Some(item) => item?.0,
+            None => {
+                checked_all = true;
+                break;
+            }
+        };
+        if let Some(member_info) = members().may_load_at_height(deps.storage, &addr, height)? {
+            if !voters_only || member_info.points > 0 {
+                members_at_height.push(Member {
+                    addr: addr.to_string(),
+                    points: member_info.points,
+                    start_height: member_info.start_height,
+                });
+            }
+        }
+        last = Some(addr);
+        if members_at_height.len() == limit {
+            break;
+        }
+    }
+
+    Ok(MembersAtHeightResponse {
+        members: members_at_height,
+        next_start_after: last.filter(|_| !checked_all).map(String::from),
+    })
+}
+
 pub(crate) fn list_voting_members<Q: CustomQuery>(
     deps: Deps<Q>,
     start_after: Option<String>,
@@ -1495,15 +3683,23 @@ pub(crate) fn list_escrows<Q: CustomQuery>(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let addr = maybe_addr(deps.api, start_after)?;
     let start = addr.as_ref().map(Bound::exclusive);
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
 
     let escrows: StdResult<Vec<_>> = ESCROWS
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
             let (addr, escrow_status) = item?;
+            let payment_deadline = match escrow_status.status {
+                MemberStatus::Pending { proposal_id } => {
+                    payment_deadline(deps.storage, &trusted_circle, proposal_id)?
+                }
+                _ => None,
+            };
             Ok(Escrow {
                 addr: addr.into(),
                 escrow_status,
+                payment_deadline,
             })
         })
         .collect();
@@ -1511,23 +3707,114 @@ pub(crate) fn list_escrows<Q: CustomQuery>(
     Ok(EscrowListResponse { escrows: escrows? })
 }
 
+pub(crate) fn query_escrow_history<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<EscrowHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let addr = deps.api.addr_validate(&addr)?;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries = ESCROW_LEDGER
+        .prefix(&addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, entry) = item?;
+            Ok(EscrowHistoryEntry {
+                id,
+                height: entry.height,
+                time: entry.time,
+                action: entry.action,
+                paid: entry.paid,
+                status: entry.status,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(EscrowHistoryResponse { entries })
+}
+
+pub(crate) fn query_escrow_claims<Q: CustomQuery>(
+    deps: Deps<Q>,
+    owner: String,
+) -> StdResult<EscrowClaimsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let claims = ESCROW_CLAIMS
+        .prefix(&owner)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (release_at, amount) = item?;
+            Ok(EscrowClaim {
+                amount,
+                release_at: Timestamp::from_nanos(release_at),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(EscrowClaimsResponse { claims })
+}
+
 pub(crate) fn query_proposal<Q: CustomQuery>(
     deps: Deps<Q>,
     env: Env,
     id: u64,
 ) -> StdResult<ProposalResponse> {
+    let prop = PROPOSALS.load(deps.storage, id)?;
+    map_proposal(deps.storage, &env.block, Ok((id, prop)))
+}
+
+pub(crate) fn query_proposal_tally<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    id: u64,
+) -> StdResult<ProposalTallyResponse> {
     let prop = PROPOSALS.load(deps.storage, id)?;
     let status = prop.current_status(&env.block);
-    Ok(ProposalResponse {
+    let VotingRules {
+        quorum,
+        threshold,
+        allow_end_early,
+        ..
+    } = prop.rules;
+    let votes = &prop.votes;
+
+    let quorum_needed = votes_needed(prop.total_points, quorum);
+    let quorum_reached = votes.total();
+    // Same semantics as `Proposal::is_passed`
+    let yes_needed_at_expiry = votes_needed(quorum_reached - votes.abstain, threshold);
+    let yes_needed_early =
+        allow_end_early.then(|| votes_needed(prop.total_points - votes.abstain, threshold));
+    let uncast_points = prop.total_points.saturating_sub(quorum_reached);
+
+    let early_pass_possible = status == Status::Open
+        && !prop.expires.is_expired(&env.block)
+        && yes_needed_early.is_some_and(|needed| votes.yes + uncast_points >= needed);
+    let projected_status = match status {
+        Status::Open => {
+            if quorum_reached >= quorum_needed && votes.yes >= yes_needed_at_expiry {
+                Status::Passed
+            } else {
+                Status::Rejected
+            }
+        }
+        status => status,
+    };
+
+    Ok(ProposalTallyResponse {
         id,
-        title: prop.title,
-        description: prop.description,
-        proposal: prop.proposal,
         status,
-        expires: prop.expires,
-        rules: prop.rules,
         total_points: prop.total_points,
-        votes: prop.votes,
+        votes: prop.votes.clone(),
+        quorum_needed,
+        quorum_reached,
+        yes_needed_at_expiry,
+        yes_needed_early,
+        uncast_points,
+        early_pass_possible,
+        projected_status,
     })
 }
 
@@ -1547,18 +3834,21 @@ pub(crate) fn list_proposals<Q: CustomQuery>(
     };
     let props: StdResult<Vec<_>> = range
         .take(limit)
-        .map(|p| map_proposal(&env.block, p))
+        .map(|p| map_proposal(deps.storage, &env.block, p))
         .collect();
 
     Ok(ProposalListResponse { proposals: props? })
 }
 
 fn map_proposal(
+    storage: &dyn Storage,
     block: &BlockInfo,
     item: StdResult<(u64, Proposal)>,
 ) -> StdResult<ProposalResponse> {
     let (id, prop) = item?;
     let status = prop.current_status(block);
+    let poll_results = poll::poll_results(storage, id, &prop.proposal)?;
+    let voting_period_secs = prop.voting_period_secs();
     Ok(ProposalResponse {
         id,
         title: prop.title,
@@ -1567,8 +3857,14 @@ fn map_proposal(
         status,
         expires: prop.expires,
         rules: prop.rules,
+        voting_period_secs,
         total_points: prop.total_points,
         votes: prop.votes,
+        urgent: prop.urgent,
+        secret: prop.secret,
+        poll_results,
+        cosponsors: prop.cosponsors,
+        committee: prop.committee,
     })
 }
 
@@ -1581,11 +3877,13 @@ pub(crate) fn query_vote<Q: CustomQuery>(
     let prop = ballots()
         .ballots
         .may_load(deps.storage, (proposal_id, &voter_addr))?;
+    let choices = POLL_BALLOTS.may_load(deps.storage, (proposal_id, &voter_addr))?;
     let vote = prop.map(|b| VoteInfo {
         proposal_id,
         voter,
         vote: b.vote,
         points: b.points,
+        choices,
     });
     Ok(VoteResponse { vote })
 }
@@ -1607,11 +3905,13 @@ pub(crate) fn list_votes_by_proposal<Q: CustomQuery>(
         .take(limit)
         .map(|item| {
             let (voter, ballot) = item?;
+            let choices = POLL_BALLOTS.may_load(deps.storage, (proposal_id, &voter))?;
             Ok(VoteInfo {
                 proposal_id,
                 voter: voter.into(),
                 vote: ballot.vote,
                 points: ballot.points,
+                choices,
             })
         })
         .collect();
@@ -1638,11 +3938,13 @@ pub(crate) fn list_votes_by_voter<Q: CustomQuery>(
         .take(limit)
         .map(|item| {
             let ((proposal_id, _), ballot) = item?;
+            let choices = POLL_BALLOTS.may_load(deps.storage, (proposal_id, &ballot.voter))?;
             Ok(VoteInfo {
                 proposal_id,
                 voter: ballot.voter.into(),
                 vote: ballot.vote,
                 points: ballot.points,
+                choices,
             })
         })
         .collect();
@@ -1651,61 +3953,171 @@ pub(crate) fn list_votes_by_voter<Q: CustomQuery>(
 }
 
 fn execute_distribute_funds<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+    mut deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
-    let total = TOTAL.load(deps.storage)?;
-    let funds = DISTRIBUTION.distribute_rewards(deps, env, total as u128)?;
+    // Escrow and treasury must always be fully covered by the contract balance
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let reserved = reserved_funds(deps.as_ref(), &env, &trusted_circle.denom)?;
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &trusted_circle.denom)?
+        .amount;
+    if balance < reserved {
+        return Err(ContractError::BalanceTooLow { balance, reserved });
+    }
+    // Slashed escrow claimed back by a previous call is burnt, or distributed without a share for
+    // the punished member
+    let slash = take_unbonded_slash(deps.branch(), &env, &trusted_circle)?;
+    for (punished, amount) in &slash.rewards {
+        distribute_slashed_escrow(deps.branch(), punished, amount.u128())?;
+    }
+    // Unbonded escrow is distributed by the next call, once it is back
+    let claim = claim_unbonded(deps.as_ref(), &env, &trusted_circle)?;
+
+    let total = REWARD_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+    let reward_denom = DISTRIBUTION.denom(deps.storage)?;
+    let mut reserved = reserved_funds(deps.as_ref(), &env, &reward_denom)?;
+    if reward_denom == trusted_circle.denom {
+        // Still in the balance until burnt
+        reserved += slash.burnt;
+    }
+    let funds = DISTRIBUTION.distribute_rewards(deps, env, total as u128, reserved.u128())?;
 
     let resp = Response::new()
+        .add_messages(slash.burn)
+        .add_messages(claim)
         .add_attribute("action", "distribute_tokens")
         .add_attribute("sender", info.sender)
         .add_attribute("denom", funds.denom)
-        .add_attribute("amount", &funds.amount.to_string());
+        .add_attribute("amount", funds.amount.to_string());
 
     Ok(resp)
 }
 
 fn execute_withdraw_funds<Q: CustomQuery>(
     deps: DepsMut<Q>,
+    env: Env,
     info: MessageInfo,
+    owner: Option<String>,
+    receiver: Option<String>,
 ) -> Result<Response, ContractError> {
-    let escrow = ESCROWS.load(deps.storage, &info.sender)?;
+    let owner = owner.map_or_else(
+        || Ok(info.sender.clone()),
+        |owner| deps.api.addr_validate(&owner),
+    )?;
+    if owner != info.sender
+        && DISTRIBUTION.delegated(deps.storage, &owner)?.as_ref() != Some(&info.sender)
+    {
+        return Err(ContractError::Unauthorized(
+            "Sender is neither owner or delegated".to_owned(),
+        ));
+    }
+    let receiver = receiver
+        .map(|receiver| deps.api.addr_validate(&receiver))
+        .transpose()?
+        .unwrap_or_else(|| owner.clone());
+
+    let escrow = ESCROWS.load(deps.storage, &owner)?;
 
-    if escrow.status != (MemberStatus::Voting {}) {
+    let points = reward_points(deps.storage, &escrow.status)?;
+    if points == 0 {
         return Err(ContractError::InvalidStatus(escrow.status));
     }
 
-    let token = DISTRIBUTION.withdraw_rewards(deps, &info.sender, 1)?;
+    let token = DISTRIBUTION.withdraw_rewards(deps, &owner, points.into(), env.block.time)?;
 
     let resp = Response::new()
         .add_attribute("action", "withdraw_tokens")
-        .add_attribute("owner", info.sender.as_str())
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("owner", owner.as_str())
+        .add_attribute("receiver", receiver.as_str())
         .add_attribute("token", &token.denom)
-        .add_attribute("amount", &token.amount.to_string())
+        .add_attribute("amount", token.amount.to_string())
         .add_submessage(SubMsg::new(BankMsg::Send {
-            to_address: info.sender.to_string(),
+            to_address: receiver.to_string(),
             amount: vec![token],
         }));
 
     Ok(resp)
 }
 
+fn execute_delegate_withdrawal<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let delegate = deps.api.addr_validate(&delegate)?;
+    DISTRIBUTION.delegate_withdrawal(deps.storage, &info.sender, Some(delegate.clone()))?;
+
+    let resp = Response::new()
+        .add_attribute("action", "delegate_withdrawal")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegated", delegate);
+
+    Ok(resp)
+}
+
+fn execute_undelegate_withdrawal<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    DISTRIBUTION.delegate_withdrawal(deps.storage, &info.sender, None)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "undelegate_withdrawal")
+        .add_attribute("sender", info.sender.as_str());
+
+    Ok(resp)
+}
+
+fn query_delegated<Q: CustomQuery>(deps: Deps<Q>, owner: String) -> StdResult<DelegatedResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+
+    let delegated = DISTRIBUTION
+        .delegated(deps.storage, &owner)?
+        .unwrap_or(owner);
+
+    Ok(DelegatedResponse { delegated })
+}
+
+fn query_reward_weights<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<RewardWeightsResponse> {
+    let weights = REWARD_WEIGHTS.may_load(deps.storage)?.unwrap_or_default();
+    let total_points = REWARD_TOTAL.may_load(deps.storage)?.unwrap_or_default();
+    Ok(RewardWeightsResponse {
+        weights,
+        total_points,
+    })
+}
+
 fn query_withdrawable_funds<Q: CustomQuery>(
     deps: Deps<Q>,
+    env: Env,
     owner: String,
 ) -> StdResult<RewardsResponse> {
     // Unchecked - if the address is invalid, querying escrow would fail
     let addr = Addr::unchecked(&owner);
     let escrow = ESCROWS.load(deps.storage, &addr)?;
 
-    let points = match escrow.status {
-        MemberStatus::Voting {} => 1,
-        _ => 0,
-    };
+    let points = reward_points(deps.storage, &escrow.status)?.into();
+
+    let rewards = DISTRIBUTION.adjusted_withdrawable_rewards(deps, addr, points, env.block.time)?;
+    Ok(RewardsResponse { rewards })
+}
+
+fn query_unvested_funds<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    owner: String,
+) -> StdResult<RewardsResponse> {
+    // Unchecked - if the address is invalid, querying escrow would fail
+    let addr = Addr::unchecked(&owner);
+    let escrow = ESCROWS.load(deps.storage, &addr)?;
 
-    let rewards = DISTRIBUTION.adjusted_withdrawable_rewards(deps, addr, points)?;
+    let points = reward_points(deps.storage, &escrow.status)?.into();
+
+    let rewards = DISTRIBUTION.unvested_rewards(deps, addr, points, env.block.time)?;
     Ok(RewardsResponse { rewards })
 }
 
@@ -1718,10 +4130,393 @@ fn query_undistributed_funds<Q: CustomQuery>(
     deps: Deps<Q>,
     env: Env,
 ) -> StdResult<RewardsResponse> {
-    let rewards = DISTRIBUTION.undistributed_rewards(deps, env)?;
+    let reward_denom = DISTRIBUTION.denom(deps.storage)?;
+    let reserved = reserved_funds(deps, &env, &reward_denom)?;
+    let rewards = DISTRIBUTION.undistributed_rewards(deps, env, reserved.u128())?;
     Ok(RewardsResponse { rewards })
 }
 
+fn execute_fund_treasury<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.funds.is_empty() {
+        return Err(PaymentError::NoFunds {}.into());
+    }
+
+    for fund in &info.funds {
+        let mut balance = treasury_balance(deps.storage, &fund.denom)?;
+        balance.available += fund.amount;
+        TREASURY.save(deps.storage, &fund.denom, &balance)?;
+    }
+
+    let funds: Vec<_> = info.funds.iter().map(Coin::to_string).collect();
+    let resp = Response::new()
+        .add_attribute("action", "fund_treasury")
+        .add_attribute("sender", info.sender)
+        .add_attribute("amount", funds.join(","));
+
+    Ok(resp)
+}
+
+fn execute_claim_spend<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    spend_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    ensure_not_paused(deps.storage, &env.block)?;
+
+    let mut spend = match spends().may_load(deps.storage, spend_id)? {
+        Some(spend) if spend.recipient == info.sender => spend,
+        _ => return Err(ContractError::SpendNotFound(spend_id)),
+    };
+
+    let claimed = spend.claimable(env.block.time);
+    let mut resp = Response::new()
+        .add_attribute("action", "claim_spend")
+        .add_attribute("spend_id", spend_id.to_string())
+        .add_attribute("amount", claimed.to_string());
+    if claimed.is_zero() {
+        return Ok(resp);
+    }
+
+    spend.claimed += claimed;
+    spends().save(deps.storage, spend_id, &spend)?;
+    let mut balance = treasury_balance(deps.storage, &spend.amount.denom)?;
+    balance.committed = balance.committed.checked_sub(claimed)?;
+    TREASURY.save(deps.storage, &spend.amount.denom, &balance)?;
+
+    resp = resp.add_message(BankMsg::Send {
+        to_address: spend.recipient.to_string(),
+        amount: coins(claimed.u128(), spend.amount.denom),
+    });
+    Ok(resp)
+}
+
+pub fn proposal_treasury_spend<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    proposal_id: u64,
+    recipient: String,
+    amount: Coin,
+    vesting: Option<u32>,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let mut balance = treasury_balance(deps.storage, &amount.denom)?;
+    if balance.available < amount.amount {
+        return Err(ContractError::TreasuryTooLow {
+            available: coin(balance.available.u128(), &amount.denom),
+            requested: amount,
+        });
+    }
+
+    let start = env.block.time;
+    let end = start.plus_seconds(vesting.unwrap_or_default() as u64 * 86_400);
+    let mut spend = Spend {
+        recipient,
+        amount,
+        claimed: Uint128::zero(),
+        start,
+        end,
+    };
+    // Without vesting, everything is sent right away
+    let claimed = spend.claimable(env.block.time);
+    spend.claimed = claimed;
+    spends().save(deps.storage, proposal_id, &spend)?;
+
+    balance.available -= spend.amount.amount;
+    balance.committed += spend.amount.amount - claimed;
+    TREASURY.save(deps.storage, &spend.amount.denom, &balance)?;
+
+    let mut res = Response::new()
+        .add_attribute("proposal", "treasury_spend")
+        .add_attribute("recipient", &spend.recipient)
+        .add_attribute("amount", spend.amount.to_string());
+    if !claimed.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: spend.recipient.to_string(),
+            amount: coins(claimed.u128(), &spend.amount.denom),
+        });
+    }
+    Ok(res)
+}
+
+fn query_voting_key<Q: CustomQuery>(deps: Deps<Q>, member: String) -> StdResult<VotingKeyResponse> {
+    let member = deps.api.addr_validate(&member)?;
+    let pubkey = VOTING_KEYS.may_load(deps.storage, &member)?;
+    let nonce = VOTE_NONCES
+        .may_load(deps.storage, &member)?
+        .unwrap_or_default();
+    Ok(VotingKeyResponse { pubkey, nonce })
+}
+
+fn query_treasury<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TreasuryResponse> {
+    let mut available = vec![];
+    let mut committed = vec![];
+    for item in TREASURY.range(deps.storage, None, None, Order::Ascending) {
+        let (denom, balance) = item?;
+        if !balance.available.is_zero() {
+            available.push(coin(balance.available.u128(), &denom));
+        }
+        if !balance.committed.is_zero() {
+            committed.push(coin(balance.committed.u128(), denom));
+        }
+    }
+
+    Ok(TreasuryResponse {
+        available,
+        committed,
+    })
+}
+
+fn list_spends<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    recipient: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SpendListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let recipient = maybe_addr(deps.api, recipient)?;
+
+    let spends = match recipient {
+        Some(recipient) => spends().idx.recipient.prefix(recipient).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        None => spends().range(deps.storage, start, None, Order::Ascending),
+    };
+    let spends = spends
+        .take(limit)
+        .map(|item| {
+            let (spend_id, spend) = item?;
+            Ok(SpendInfo {
+                spend_id,
+                claimable: spend.claimable(env.block.time),
+                recipient: spend.recipient,
+                amount: spend.amount,
+                claimed: spend.claimed,
+                start: spend.start,
+                end: spend.end,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(SpendListResponse { spends })
+}
+
+fn query_contract_permissions<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+) -> StdResult<ContractPermissionsResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let permissions = CONTRACT_PERMISSIONS.may_load(deps.storage, &addr)?;
+    Ok(ContractPermissionsResponse { permissions })
+}
+
+fn query_draft<Q: CustomQuery>(deps: Deps<Q>, draft_id: u64) -> StdResult<DraftResponse> {
+    let draft = drafts().load(deps.storage, draft_id)?;
+    Ok(map_draft(draft_id, draft))
+}
+
+fn list_drafts<Q: CustomQuery>(
+    deps: Deps<Q>,
+    author: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DraftListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let author = maybe_addr(deps.api, author)?;
+
+    let drafts = match author {
+        Some(author) => {
+            drafts()
+                .idx
+                .author
+                .prefix(author)
+                .range(deps.storage, start, None, Order::Ascending)
+        }
+        None => drafts().range(deps.storage, start, None, Order::Ascending),
+    };
+    let drafts = drafts
+        .take(limit)
+        .map(|item| item.map(|(draft_id, draft)| map_draft(draft_id, draft)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(DraftListResponse { drafts })
+}
+
+fn map_draft(draft_id: u64, draft: Draft) -> DraftResponse {
+    DraftResponse {
+        draft_id,
+        author: draft.author,
+        title: draft.title,
+        description: draft.description,
+        proposal: draft.proposal,
+        urgent: draft.urgent,
+        secret: draft.secret,
+        cosponsors: draft.cosponsors,
+        expires: draft.expires,
+    }
+}
+
+fn query_committee<Q: CustomQuery>(deps: Deps<Q>, name: String) -> StdResult<CommitteeResponse> {
+    let committee = COMMITTEES.load(deps.storage, &name)?;
+    Ok(map_committee(name, committee))
+}
+
+fn list_committees<Q: CustomQuery>(
+    deps: Deps<Q>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CommitteeListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let committees = COMMITTEES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(name, committee)| map_committee(name, committee)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(CommitteeListResponse { committees })
+}
+
+fn map_committee(name: String, committee: Committee) -> CommitteeResponse {
+    CommitteeResponse {
+        name,
+        members: committee.members,
+        powers: committee.powers,
+        rules: committee.rules,
+    }
+}
+
+/// Exports members, withdraw adjustments, batches and proposals, in that order. Every page
+/// continues where the previous one stopped, even across those sections
+fn export_state<Q: CustomQuery>(
+    deps: Deps<Q>,
+    start_after: Option<SnapshotKey>,
+    limit: Option<u32>,
+) -> StdResult<StateSnapshot> {
+    if ESCROW_CHANGE.may_load(deps.storage)?.is_some() {
+        return Err(StdError::generic_err(
+            "Cannot export the state while an escrow change is being applied",
+        ));
+    }
+    if TRUSTED_CIRCLE.load(deps.storage)?.stake_contract.is_some() {
+        return Err(StdError::generic_err(
+            "Cannot export the state of a trusted circle bonding its escrow",
+        ));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let header = match start_after {
+        Some(_) => None,
+        None => Some(export_header(deps)?),
+    };
+
+    let mut after = start_after;
+    let mut entries = vec![];
+    if matches!(after, None | Some(SnapshotKey::Member(_))) {
+        let start = match after.take() {
+            Some(SnapshotKey::Member(addr)) => Some(Addr::unchecked(addr)),
+            _ => None,
+        };
+        let escrows = ESCROWS
+            .range(
+                deps.storage,
+                start.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit);
+        for item in escrows {
+            let (addr, escrow) = item?;
+            let points = members()
+                .may_load(deps.storage, &addr)?
+                .map(|member| member.points);
+            entries.push(SnapshotEntry::Member {
+                addr: addr.into_string(),
+                escrow,
+                points,
+            });
+        }
+    }
+    if matches!(after, None | Some(SnapshotKey::Adjustment(_))) {
+        let start = match after.take() {
+            Some(SnapshotKey::Adjustment(addr)) => Some(Addr::unchecked(addr)),
+            _ => None,
+        };
+        let adjustments =
+            DISTRIBUTION.adjustments(deps.storage, start.as_ref(), limit - entries.len())?;
+        entries.extend(adjustments.into_iter().map(|(addr, adjustment)| {
+            SnapshotEntry::Adjustment {
+                addr: addr.into_string(),
+                adjustment,
+            }
+        }));
+    }
+    if matches!(after, None | Some(SnapshotKey::Batch(_))) {
+        let start = match after.take() {
+            Some(SnapshotKey::Batch(id)) => Some(Bound::exclusive(id)),
+            _ => None,
+        };
+        let batches = batches()
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit - entries.len());
+        for item in batches {
+            let (id, batch) = item?;
+            entries.push(SnapshotEntry::Batch { id, batch });
+        }
+    }
+    if matches!(after, None | Some(SnapshotKey::Proposal(_))) {
+        let start = match after.take() {
+            Some(SnapshotKey::Proposal(id)) => Some(Bound::exclusive(id)),
+            _ => None,
+        };
+        let proposals = PROPOSALS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit - entries.len());
+        for item in proposals {
+            let (id, proposal) = item?;
+            entries.push(SnapshotEntry::Proposal { id, proposal });
+        }
+    }
+
+    let next = match entries.len() {
+        len if len == limit => entries.last().map(SnapshotEntry::key),
+        _ => None,
+    };
+    Ok(StateSnapshot {
+        version: SNAPSHOT_VERSION,
+        header,
+        entries,
+        next,
+    })
+}
+
+fn export_header<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<SnapshotHeader> {
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let totals = ImportTotals {
+        points: TOTAL.may_load(deps.storage)?.unwrap_or_default(),
+        reward_points: REWARD_TOTAL.may_load(deps.storage)?.unwrap_or_default(),
+        escrow: escrow_total(deps.storage, &trusted_circle.denom)?,
+    };
+    Ok(SnapshotHeader {
+        denom: trusted_circle.denom,
+        escrow_amount: trusted_circle.escrow_amount,
+        reward_weights: REWARD_WEIGHTS.may_load(deps.storage)?.unwrap_or_default(),
+        distribution: DISTRIBUTION.export(deps.storage)?,
+        totals,
+        proposal_count: PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(
     mut deps: DepsMut<TgradeQuery>,
@@ -1733,6 +4528,11 @@ pub fn migrate(
     // FIXME: Currently we don't need mechanism for migrating ballots, as testnets starts from scratch anyway
     // migrate_ballots(deps.branch(), &env, &msg, &stored_version)?;
     migrate_proposals(deps.branch(), &env, &msg, &stored_version)?;
+    migrate_proposals_by_expiry(deps.branch(), &env, &msg, &stored_version)?;
+    migrate_total_snapshot(deps.branch(), &env, &msg, &stored_version)?;
+    migrate_escrow_totals(deps.branch(), &env, &msg, &stored_version)?;
+    migrate_reward_total(deps.branch(), &env, &msg, &stored_version)?;
+    migrate_voting_key_owners(deps.branch(), &env, &msg, &stored_version)?;
 
     Ok(Response::new())
 }
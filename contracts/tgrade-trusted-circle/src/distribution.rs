@@ -1,7 +1,10 @@
 use crate::error::ContractError;
 use crate::i128::Int128;
-use cosmwasm_std::{coin, Addr, Coin, CustomQuery, Deps, DepsMut, Env, StdResult, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{
+    coin, Addr, Coin, CustomQuery, Deps, DepsMut, Env, Order, StdResult, Storage, Timestamp,
+    Uint128,
+};
+use cw_storage_plus::{Bound, Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -15,8 +18,12 @@ use serde::{Deserialize, Serialize};
 /// calculations, but I256 is missing and it is required for this.
 pub const POINTS_SHIFT: u8 = 32;
 
+/// Rewards distributed within the same `1 / VESTING_TRANCHES` of the vesting period share a
+/// single vesting schedule. This bounds how many schedules are vesting at any time.
+pub const VESTING_TRANCHES: u64 = 20;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
-struct DistributionConfig {
+pub struct DistributionConfig {
     /// Tokens can be distributed by this denom.
     pub denom: String,
     /// How much points is single point of points worth at this point.
@@ -27,30 +34,115 @@ struct DistributionConfig {
     pub distributed_total: Uint128,
     /// Total funds not yet withdrawn.
     pub withdrawable_total: Uint128,
+    /// Seconds over which distributed rewards vest linearly. 0 if they are withdrawable at once.
+    #[serde(default)]
+    pub vesting_period: u64,
+    /// Id of the next vesting tranche
+    #[serde(default)]
+    pub next_tranche: u64,
+    /// Id of the oldest vesting tranche which may still be vesting
+    #[serde(default)]
+    pub first_tranche: u64,
+    /// If the last tranche can still take newly distributed rewards
+    #[serde(default)]
+    pub tranche_open: bool,
+}
+
+/// Rewards distributed at once (or close enough), vesting linearly between `start` and `end`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Tranche {
+    /// How much points every single point got in this tranche
+    pub points_per_points: Uint128,
+    /// Timestamp (seconds) the vesting started at
+    pub start: u64,
+    /// Timestamp (seconds) everything is vested at
+    pub end: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
-struct WithdrawAdjustment {
+pub struct WithdrawAdjustment {
     /// How much points should be added/removed from calculated funds while withdrawal.
     pub points_correction: Int128,
     /// How much funds addresses already withdrawn.
     pub withdrawn_funds: Uint128,
+    /// First tranche the address takes part in with its points, except for those in
+    /// `vesting_blocks`.
+    #[serde(default)]
+    pub vesting_from: u64,
+    /// Address allowed to withdraw funds on behalf of this one, besides itself
+    #[serde(default)]
+    pub delegated: Option<Addr>,
+    /// Points added later, which only take part in the tranches from their own `vesting_from`.
+    /// Oldest first
+    #[serde(default)]
+    pub vesting_blocks: Vec<VestingBlock>,
+}
+
+/// Points of an address taking part in the tranches from `vesting_from` on
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct VestingBlock {
+    pub points: Uint128,
+    pub vesting_from: u64,
+}
+
+impl WithdrawAdjustment {
+    fn new() -> Self {
+        WithdrawAdjustment {
+            points_correction: 0.into(),
+            withdrawn_funds: Uint128::zero(),
+            vesting_from: 0,
+            delegated: None,
+            vesting_blocks: vec![],
+        }
+    }
+
+    /// Points vesting from `vesting_from`, out of the `points` of the address
+    fn base_points(&self, points: u128) -> u128 {
+        let blocks: u128 = self.vesting_blocks.iter().map(|b| b.points.u128()).sum();
+        points.saturating_sub(blocks)
+    }
+
+    /// Blocks vesting from the first tranche still around are vesting like the base points
+    fn merge_vested_blocks(&mut self, first_tranche: u64) {
+        self.vesting_blocks
+            .retain(|block| block.vesting_from > first_tranche);
+    }
+}
+
+/// Distribution state of a trusted circle, without the withdraw adjustments of its members
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DistributionSnapshot {
+    pub config: DistributionConfig,
+    /// Tranches which may still be vesting, by id
+    pub tranches: Vec<(u64, Tranche)>,
 }
 
 pub struct Distribution<'a> {
     config: Item<'a, DistributionConfig>,
     withdraw_adjustment: Map<'a, &'a Addr, WithdrawAdjustment>,
+    tranches: Map<'a, u64, Tranche>,
 }
 
 impl<'a> Distribution<'a> {
-    pub const fn new(distribution_ns: &'a str, adjustment_ns: &'a str) -> Self {
+    pub const fn new(
+        distribution_ns: &'a str,
+        adjustment_ns: &'a str,
+        tranches_ns: &'a str,
+    ) -> Self {
         Self {
             config: Item::new(distribution_ns),
             withdraw_adjustment: Map::new(adjustment_ns),
+            tranches: Map::new(tranches_ns),
         }
     }
 
-    pub fn init<Q: CustomQuery>(&self, deps: DepsMut<Q>, denom: impl ToString) -> StdResult<()> {
+    /// `vesting_period` is in seconds, 0 to have rewards withdrawable at once
+    pub fn init<Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        denom: impl ToString,
+        vesting_period: u64,
+    ) -> StdResult<()> {
         self.config.save(
             deps.storage,
             &DistributionConfig {
@@ -59,16 +151,22 @@ impl<'a> Distribution<'a> {
                 points_leftover: 0,
                 distributed_total: Uint128::zero(),
                 withdrawable_total: Uint128::zero(),
+                vesting_period,
+                next_tranche: 0,
+                first_tranche: 0,
+                tranche_open: false,
             },
         )
     }
 
-    /// Returns total number of tokens distributed as rewards
+    /// Returns total number of tokens distributed as rewards. `reserved` funds of the contract
+    /// (like escrow) are never distributed
     pub fn distribute_rewards<Q: CustomQuery>(
         &self,
         deps: DepsMut<Q>,
         env: Env,
         total: u128,
+        reserved: u128,
     ) -> Result<Coin, ContractError> {
         // There are no shares in play - noone to distribute to
         if total == 0 {
@@ -83,46 +181,193 @@ impl<'a> Distribution<'a> {
             .amount
             .into();
 
-        let amount = balance - withdrawable;
+        let amount =
+            balance
+                .checked_sub(withdrawable + reserved)
+                .ok_or(ContractError::BalanceTooLow {
+                    balance: balance.into(),
+                    reserved: (withdrawable + reserved).into(),
+                })?;
         if amount == 0 {
             return Ok(coin(0, distribution.denom));
         }
 
-        let leftover: u128 = distribution.points_leftover.into();
-        let points = (amount << POINTS_SHIFT) + leftover;
-        let points_per_share = points / total;
-        distribution.points_leftover = (points % total) as u64;
+        let points_per_share = distribute(&mut distribution, amount, total);
+        if distribution.vesting_period > 0 {
+            self.add_to_tranche(
+                deps.storage,
+                &mut distribution,
+                points_per_share,
+                env.block.time,
+            )?;
+        }
+        self.config.save(deps.storage, &distribution)?;
+
+        Ok(coin(amount, distribution.denom))
+    }
+
+    /// Makes `points_per_share` vest from `now`, either in the open tranche, or in a new one
+    fn add_to_tranche(
+        &self,
+        storage: &mut dyn Storage,
+        distribution: &mut DistributionConfig,
+        points_per_share: u128,
+        now: Timestamp,
+    ) -> StdResult<()> {
+        let now = now.seconds();
+
+        // Tranches which are fully vested are not needed anymore
+        while distribution.first_tranche < distribution.next_tranche {
+            let first = distribution.first_tranche;
+            match self.tranches.may_load(storage, first)? {
+                Some(tranche) if tranche.end > now => break,
+                _ => {
+                    self.tranches.remove(storage, first);
+                    distribution.first_tranche += 1;
+                }
+            }
+        }
+        if distribution.first_tranche == distribution.next_tranche {
+            distribution.tranche_open = false;
+        }
 
-        // Everything goes back to 128-bits/16-bytes
-        // Full amount is added here to total withdrawable, as it should not be considered on its own
-        // on future distributions - even if because of calculation offsets it is not fully
-        // distributed, the error is handled by leftover.
-        distribution.points_per_points += Uint128::from(points_per_share);
-        distribution.distributed_total += Uint128::from(amount);
-        distribution.withdrawable_total += Uint128::from(amount);
+        let window = (distribution.vesting_period / VESTING_TRANCHES).max(1);
+        if distribution.tranche_open {
+            let last = distribution.next_tranche - 1;
+            let mut tranche = self.tranches.load(storage, last)?;
+            if tranche.start + window > now {
+                tranche.points_per_points += Uint128::from(points_per_share);
+                return self.tranches.save(storage, last, &tranche);
+            }
+        }
 
+        let tranche = Tranche {
+            points_per_points: points_per_share.into(),
+            start: now,
+            end: now + distribution.vesting_period,
+        };
+        self.tranches
+            .save(storage, distribution.next_tranche, &tranche)?;
+        distribution.next_tranche += 1;
+        distribution.tranche_open = true;
+        Ok(())
+    }
+
+    /// Distributes `amount` of funds the contract already holds, but which are not part of
+    /// the rewards yet (like slashed escrow), over `total` points. The `excluded` addresses
+    /// (with their points, which are part of `total`) get no share. Those funds don't vest.
+    /// If no points are left to distribute to, the funds are left for the next
+    /// `distribute_rewards`.
+    ///
+    /// Returns the distributed funds
+    pub fn distribute_funds<Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        amount: u128,
+        total: u128,
+        excluded: &[(&Addr, u128)],
+    ) -> StdResult<Coin> {
+        let mut distribution = self.config.load(deps.storage)?;
+        let excluded_points: u128 = excluded.iter().map(|(_, points)| points).sum();
+        let total = total - excluded_points;
+        if amount == 0 || total == 0 {
+            return Ok(coin(0, distribution.denom));
+        }
+
+        let points_per_share = distribute(&mut distribution, amount, total);
         self.config.save(deps.storage, &distribution)?;
 
+        let diff: Vec<_> = excluded
+            .iter()
+            .map(|(addr, points)| (*addr, *points as i128))
+            .collect();
+        self.correct_points(deps, points_per_share, &diff)?;
+
         Ok(coin(amount, distribution.denom))
     }
 
+    pub fn export(&self, storage: &dyn Storage) -> StdResult<DistributionSnapshot> {
+        let config = self.config.load(storage)?;
+        let tranches = self
+            .tranches
+            .range(
+                storage,
+                Some(Bound::inclusive(config.first_tranche)),
+                None,
+                Order::Ascending,
+            )
+            .collect::<StdResult<_>>()?;
+        Ok(DistributionSnapshot { config, tranches })
+    }
+
+    /// Replaces the distribution state with an exported one. Withdraw adjustments are imported
+    /// separately, with `import_adjustment`
+    pub fn import(
+        &self,
+        storage: &mut dyn Storage,
+        snapshot: &DistributionSnapshot,
+    ) -> StdResult<()> {
+        self.config.save(storage, &snapshot.config)?;
+        for (id, tranche) in &snapshot.tranches {
+            self.tranches.save(storage, *id, tranche)?;
+        }
+        Ok(())
+    }
+
+    /// Withdraw adjustments of all addresses which ever had points or delegated withdrawal,
+    /// ordered by address
+    pub fn adjustments(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<&Addr>,
+        limit: usize,
+    ) -> StdResult<Vec<(Addr, WithdrawAdjustment)>> {
+        self.withdraw_adjustment
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect()
+    }
+
+    pub fn import_adjustment(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        adjustment: &WithdrawAdjustment,
+    ) -> StdResult<()> {
+        self.withdraw_adjustment.save(storage, owner, adjustment)
+    }
+
+    /// Returns the rewards distributed, but not withdrawn yet
+    pub fn withdrawable_total(&self, storage: &dyn Storage) -> StdResult<Uint128> {
+        Ok(self.config.load(storage)?.withdrawable_total)
+    }
+
+    /// Returns the denom of the rewards
+    pub fn denom(&self, storage: &dyn Storage) -> StdResult<String> {
+        Ok(self.config.load(storage)?.denom)
+    }
+
     /// Returns Coin which should be send to receiver as a withdrawal
     pub fn withdraw_rewards<Q: CustomQuery>(
         &self,
         deps: DepsMut<Q>,
         owner: &Addr,
         points: u128,
+        now: Timestamp,
     ) -> Result<Coin, ContractError> {
         let mut distribution = self.config.load(deps.storage)?;
         let mut adjustment = self
             .withdraw_adjustment
             .may_load(deps.storage, owner)?
-            .unwrap_or_else(|| WithdrawAdjustment {
-                points_correction: Int128::zero(),
-                withdrawn_funds: Uint128::zero(),
-            });
+            .unwrap_or_else(WithdrawAdjustment::new);
 
-        let token = withdrawable_rewards(points, &distribution, &adjustment)?;
+        let unvested = self.unvested(deps.storage, points, &distribution, &adjustment, now)?;
+        let token = withdrawable_rewards(points, unvested, &distribution, &adjustment)?;
         if token.amount.is_zero() {
             // Just do nothing
             return Ok(coin(0, distribution.denom));
@@ -137,26 +382,225 @@ impl<'a> Distribution<'a> {
         Ok(token)
     }
 
+    /// Sets the address allowed to withdraw funds of owner, or removes it if `None`
+    pub fn delegate_withdrawal(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        delegated: Option<Addr>,
+    ) -> StdResult<()> {
+        let mut adjustment = self
+            .withdraw_adjustment
+            .may_load(storage, owner)?
+            .unwrap_or_else(WithdrawAdjustment::new);
+        adjustment.delegated = delegated;
+        self.withdraw_adjustment.save(storage, owner, &adjustment)
+    }
+
+    /// Returns the address allowed to withdraw funds of owner, besides owner itself
+    pub fn delegated(&self, storage: &dyn Storage, owner: &Addr) -> StdResult<Option<Addr>> {
+        Ok(self
+            .withdraw_adjustment
+            .may_load(storage, owner)?
+            .and_then(|adjustment| adjustment.delegated))
+    }
+
     /// Returns how much rewards is available for withdrawal for owner
     pub fn adjusted_withdrawable_rewards<Q: CustomQuery>(
         &self,
         deps: Deps<Q>,
         owner: Addr,
         points: u128,
+        now: Timestamp,
     ) -> StdResult<Coin> {
         let distribution = self.config.load(deps.storage)?;
         let adjustment = self
             .withdraw_adjustment
             .may_load(deps.storage, &owner)?
-            .unwrap_or_else(|| WithdrawAdjustment {
-                points_correction: 0.into(),
-                withdrawn_funds: Uint128::zero(),
-            });
+            .unwrap_or_else(WithdrawAdjustment::new);
 
-        let token = withdrawable_rewards(points, &distribution, &adjustment)?;
+        let unvested = self.unvested(deps.storage, points, &distribution, &adjustment, now)?;
+        let token = withdrawable_rewards(points, unvested, &distribution, &adjustment)?;
         Ok(token)
     }
 
+    /// Returns how much rewards of owner are not vested yet
+    pub fn unvested_rewards<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        owner: Addr,
+        points: u128,
+        now: Timestamp,
+    ) -> StdResult<Coin> {
+        let distribution = self.config.load(deps.storage)?;
+        let adjustment = self
+            .withdraw_adjustment
+            .may_load(deps.storage, &owner)?
+            .unwrap_or_else(WithdrawAdjustment::new);
+
+        let unvested = self.unvested(deps.storage, points, &distribution, &adjustment, now)?;
+        Ok(coin(unvested, &distribution.denom))
+    }
+
+    /// Calculates how much of the rewards for `points` are not vested yet
+    fn unvested(
+        &self,
+        storage: &dyn Storage,
+        points: u128,
+        distribution: &DistributionConfig,
+        adjustment: &WithdrawAdjustment,
+        now: Timestamp,
+    ) -> StdResult<u128> {
+        if points == 0 {
+            return Ok(0);
+        }
+
+        let base = adjustment.base_points(points);
+        let mut unvested =
+            self.unvested_points(storage, base, adjustment.vesting_from, distribution, now)?;
+        for block in &adjustment.vesting_blocks {
+            unvested += self.unvested_points(
+                storage,
+                block.points.u128(),
+                block.vesting_from,
+                distribution,
+                now,
+            )?;
+        }
+
+        Ok(unvested.u128() >> POINTS_SHIFT)
+    }
+
+    /// Calculates the unvested points (not shifted yet) of `points` taking part in the tranches
+    /// from `vesting_from` on
+    fn unvested_points(
+        &self,
+        storage: &dyn Storage,
+        points: u128,
+        vesting_from: u64,
+        distribution: &DistributionConfig,
+        now: Timestamp,
+    ) -> StdResult<Uint128> {
+        if points == 0 {
+            return Ok(Uint128::zero());
+        }
+
+        let now = now.seconds();
+        let first = distribution.first_tranche.max(vesting_from);
+        self.tranches
+            .range(
+                storage,
+                Some(Bound::inclusive(first)),
+                None,
+                Order::Ascending,
+            )
+            .map(|tranche| {
+                let (_, tranche) = tranche?;
+                let remaining = tranche.end.saturating_sub(now);
+                let period = tranche.end - tranche.start;
+                if remaining == 0 || period == 0 {
+                    return Ok(Uint128::zero());
+                }
+                let points = tranche.points_per_points * Uint128::from(points);
+                Ok(points.multiply_ratio(remaining, period))
+            })
+            .sum()
+    }
+
+    /// Removes `points` of the address, the most recently added first, and takes back their
+    /// unvested rewards. Returns the unvested rewards taken back
+    fn remove_vesting_points(
+        &self,
+        storage: &mut dyn Storage,
+        distribution: &mut DistributionConfig,
+        adjustment: &mut WithdrawAdjustment,
+        points: u128,
+        now: Timestamp,
+    ) -> StdResult<u128> {
+        let mut left = points;
+        let mut unvested = Uint128::zero();
+        while left > 0 {
+            let block = match adjustment.vesting_blocks.last_mut() {
+                Some(block) => block,
+                None => break,
+            };
+            let removed = left.min(block.points.u128());
+            unvested +=
+                self.unvested_points(storage, removed, block.vesting_from, distribution, now)?;
+            block.points -= Uint128::from(removed);
+            if block.points.is_zero() {
+                adjustment.vesting_blocks.pop();
+            }
+            left -= removed;
+        }
+        unvested +=
+            self.unvested_points(storage, left, adjustment.vesting_from, distribution, now)?;
+
+        let unvested = unvested.u128() >> POINTS_SHIFT;
+        if unvested > 0 {
+            let correction: i128 = adjustment.points_correction.into();
+            adjustment.points_correction = (correction - (unvested << POINTS_SHIFT) as i128).into();
+            distribution.distributed_total -= Uint128::from(unvested);
+            distribution.withdrawable_total -= Uint128::from(unvested);
+        }
+        Ok(unvested)
+    }
+
+    /// Makes `points` added to the address take part only in the tranches from the next one on
+    fn add_vesting_points(
+        distribution: &mut DistributionConfig,
+        adjustment: &mut WithdrawAdjustment,
+        points: u128,
+    ) {
+        adjustment.merge_vested_blocks(distribution.first_tranche);
+        let vesting_from = distribution.next_tranche;
+        match adjustment.vesting_blocks.last_mut() {
+            Some(block) if block.vesting_from == vesting_from => {
+                block.points += Uint128::from(points);
+            }
+            _ if adjustment.vesting_from >= vesting_from => {}
+            _ => adjustment.vesting_blocks.push(VestingBlock {
+                points: points.into(),
+                vesting_from,
+            }),
+        }
+        distribution.tranche_open = false;
+    }
+
+    /// Takes back the unvested rewards of `owner` for `points` of its points (the most recently
+    /// added first), so they are distributed again with the next `distribute_rewards`. Those
+    /// points are kept, but only take part in the rewards distributed afterwards.
+    ///
+    /// Returns the forfeited funds
+    pub fn forfeit_unvested<Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        owner: &Addr,
+        points: u128,
+        now: Timestamp,
+    ) -> StdResult<Coin> {
+        let mut distribution = self.config.load(deps.storage)?;
+        let mut adjustment = self
+            .withdraw_adjustment
+            .may_load(deps.storage, owner)?
+            .unwrap_or_else(WithdrawAdjustment::new);
+
+        let unvested = self.remove_vesting_points(
+            deps.storage,
+            &mut distribution,
+            &mut adjustment,
+            points,
+            now,
+        )?;
+        // Current tranches are settled for those points
+        Self::add_vesting_points(&mut distribution, &mut adjustment, points);
+        self.withdraw_adjustment
+            .save(deps.storage, owner, &adjustment)?;
+        self.config.save(deps.storage, &distribution)?;
+
+        Ok(coin(unvested, distribution.denom))
+    }
+
     /// Returns how much rewards was already distributed
     pub fn distributed_rewards<Q: CustomQuery>(&self, deps: Deps<Q>) -> StdResult<Coin> {
         let distribution = self.config.load(deps.storage)?;
@@ -171,6 +615,7 @@ impl<'a> Distribution<'a> {
         &self,
         deps: Deps<Q>,
         env: Env,
+        reserved: u128,
     ) -> StdResult<Coin> {
         let distribution = self.config.load(deps.storage)?;
         let balance = deps
@@ -178,30 +623,61 @@ impl<'a> Distribution<'a> {
             .query_balance(env.contract.address, distribution.denom.clone())?
             .amount;
 
-        Ok(coin(
-            (balance - distribution.withdrawable_total).into(),
-            &distribution.denom,
-        ))
+        let undistributed = balance
+            .saturating_sub(distribution.withdrawable_total)
+            .saturating_sub(reserved.into());
+        Ok(coin(undistributed.into(), &distribution.denom))
     }
 
-    /// Performs points correction basing on points changes
+    /// Performs points correction basing on points changes.
+    /// Removed points (the most recently added first) forfeit their unvested rewards, and added
+    /// points only take part in rewards distributed afterwards. The points left keep vesting as
+    /// they did.
     pub fn apply_points_correction<Q: CustomQuery>(
         &self,
         deps: DepsMut<Q>,
         diff: &[(&Addr, i128)],
+        now: Timestamp,
     ) -> StdResult<()> {
+        let mut distribution = self.config.load(deps.storage)?;
+        for (addr, diff) in diff {
+            let mut adjustment = self
+                .withdraw_adjustment
+                .may_load(deps.storage, addr)?
+                .unwrap_or_else(WithdrawAdjustment::new);
+            if *diff < 0 {
+                self.remove_vesting_points(
+                    deps.storage,
+                    &mut distribution,
+                    &mut adjustment,
+                    diff.unsigned_abs(),
+                    now,
+                )?;
+            } else {
+                Self::add_vesting_points(&mut distribution, &mut adjustment, *diff as u128);
+            }
+            self.withdraw_adjustment
+                .save(deps.storage, addr, &adjustment)?;
+        }
+        self.config.save(deps.storage, &distribution)?;
+
         let points_per_points = self.config.load(deps.storage)?.points_per_points.u128();
+        self.correct_points(deps, points_per_points, diff)
+    }
 
+    /// Corrects the points of addresses, so that their withdrawable rewards are as if
+    /// `diff` points were added to them when the distribution was at `points_per_points`
+    fn correct_points<Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        points_per_points: u128,
+        diff: &[(&Addr, i128)],
+    ) -> StdResult<()> {
         for (addr, diff) in diff {
             self.withdraw_adjustment
                 .update(deps.storage, addr, |old| -> StdResult<_> {
-                    let mut old = old.unwrap_or_else(|| {
-                        // This should never happen, but better this than panic
-                        WithdrawAdjustment {
-                            points_correction: 0.into(),
-                            withdrawn_funds: Uint128::zero(),
-                        }
-                    });
+                    // This should never happen, but better this than panic
+                    let mut old = old.unwrap_or_else(WithdrawAdjustment::new);
                     let points_correction: i128 = old.points_correction.into();
                     old.points_correction =
                         (points_correction - points_per_points as i128 * diff).into();
@@ -212,9 +688,30 @@ impl<'a> Distribution<'a> {
     }
 }
 
+/// Adds `amount` to the distribution, shared over `total` points.
+/// Returns how much points every single point got
+fn distribute(distribution: &mut DistributionConfig, amount: u128, total: u128) -> u128 {
+    let leftover: u128 = distribution.points_leftover.into();
+    let points = (amount << POINTS_SHIFT) + leftover;
+    let points_per_share = points / total;
+    distribution.points_leftover = (points % total) as u64;
+
+    // Everything goes back to 128-bits/16-bytes
+    // Full amount is added here to total withdrawable, as it should not be considered on its own
+    // on future distributions - even if because of calculation offsets it is not fully
+    // distributed, the error is handled by leftover.
+    distribution.points_per_points += Uint128::from(points_per_share);
+    distribution.distributed_total += Uint128::from(amount);
+    distribution.withdrawable_total += Uint128::from(amount);
+
+    points_per_share
+}
+
 /// Calculates withdrawable funds from distribution and adjustment info.
+/// `unvested` funds are not withdrawable yet.
 fn withdrawable_rewards(
     points: u128,
+    unvested: u128,
     distribution: &DistributionConfig,
     adjustment: &WithdrawAdjustment,
 ) -> StdResult<Coin> {
@@ -224,7 +721,7 @@ fn withdrawable_rewards(
     let points = (ppw * points) as i128;
     let points = points + correction;
     let amount = points as u128 >> POINTS_SHIFT;
-    let amount = amount - withdrawn;
+    let amount = amount.saturating_sub(withdrawn + unvested);
 
     Ok(coin(amount, &distribution.denom))
 }
@@ -244,28 +741,134 @@ mod tests {
 
     #[test]
     fn initialization() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
         let distributed = dist.distributed_rewards(deps.as_ref()).unwrap();
         assert_eq!(distributed, coin(0, DENOM));
 
         let undistributed = dist
-            .undistributed_rewards(deps.as_ref(), mock_env())
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
             .unwrap();
         assert_eq!(undistributed, coin(0, DENOM));
     }
 
+    #[test]
+    fn reserved_funds_not_distributed() {
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
+
+        let mut deps = mock_dependencies();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
+
+        let member = Addr::unchecked("member0");
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(1000, DENOM));
+
+        let undistributed = dist
+            .undistributed_rewards(deps.as_ref(), mock_env(), 600)
+            .unwrap();
+        assert_eq!(undistributed, coin(400, DENOM));
+
+        let distributed = dist
+            .distribute_rewards(deps.as_mut(), mock_env(), 1, 600)
+            .unwrap();
+        assert_eq!(distributed, coin(400, DENOM));
+
+        let funds = dist
+            .adjusted_withdrawable_rewards(deps.as_ref(), member, 1, mock_env().block.time)
+            .unwrap();
+        assert_eq!(funds, coin(400, DENOM));
+
+        // Reserved and withdrawable funds are more than the balance
+        let err = dist
+            .distribute_rewards(deps.as_mut(), mock_env(), 1, 700)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BalanceTooLow {
+                balance: Uint128::new(1000),
+                reserved: Uint128::new(1100),
+            }
+        );
+    }
+
+    #[test]
+    fn rewards_vest_linearly() {
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
+
+        let mut deps = mock_dependencies();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 1000).unwrap();
+
+        let member = Addr::unchecked("member0");
+        let env = mock_env();
+        let at = |secs| env.block.time.plus_seconds(secs);
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(1000, DENOM));
+        dist.distribute_rewards(deps.as_mut(), env.clone(), 1, 0)
+            .unwrap();
+
+        // Nothing is vested right after distribution
+        let funds = dist
+            .adjusted_withdrawable_rewards(deps.as_ref(), member.clone(), 1, at(0))
+            .unwrap();
+        assert_eq!(funds, coin(0, DENOM));
+
+        // A quarter is vested after a quarter of the period
+        let funds = dist
+            .adjusted_withdrawable_rewards(deps.as_ref(), member.clone(), 1, at(250))
+            .unwrap();
+        assert_eq!(funds, coin(250, DENOM));
+        let funds = dist
+            .unvested_rewards(deps.as_ref(), member.clone(), 1, at(250))
+            .unwrap();
+        assert_eq!(funds, coin(750, DENOM));
+        let funds = dist
+            .withdraw_rewards(deps.as_mut(), &member, 1, at(250))
+            .unwrap();
+        assert_eq!(funds, coin(250, DENOM));
+
+        // Rewards distributed shortly after are vesting together
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(1750, DENOM));
+        let mut later = env.clone();
+        later.block.time = at(10);
+        dist.distribute_rewards(deps.as_mut(), later, 1, 0).unwrap();
+        let funds = dist
+            .adjusted_withdrawable_rewards(deps.as_ref(), member.clone(), 1, at(500))
+            .unwrap();
+        assert_eq!(funds, coin(750, DENOM));
+
+        // Unvested rewards are given back
+        let funds = dist
+            .forfeit_unvested(deps.as_mut(), &member, 1, at(500))
+            .unwrap();
+        assert_eq!(funds, coin(1000, DENOM));
+        let funds = dist
+            .unvested_rewards(deps.as_ref(), member.clone(), 1, at(500))
+            .unwrap();
+        assert_eq!(funds, coin(0, DENOM));
+        let funds = dist
+            .adjusted_withdrawable_rewards(deps.as_ref(), member.clone(), 1, at(1000))
+            .unwrap();
+        assert_eq!(funds, coin(750, DENOM));
+        let undistributed = dist
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
+            .unwrap();
+        assert_eq!(undistributed, coin(1000, DENOM));
+        let distributed = dist.distributed_rewards(deps.as_ref()).unwrap();
+        assert_eq!(distributed, coin(1000, DENOM));
+    }
+
     #[test]
     fn divisible_funds_distributed() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let members = vec![
+        let members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 1,
@@ -280,7 +883,7 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1000, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(1000, DENOM));
 
@@ -289,6 +892,7 @@ mod tests {
                 deps.as_ref(),
                 members[0].addr.clone(),
                 members[0].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(250, DENOM));
@@ -298,6 +902,7 @@ mod tests {
                 deps.as_ref(),
                 members[1].addr.clone(),
                 members[1].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(750, DENOM));
@@ -306,19 +911,29 @@ mod tests {
         assert_eq!(distributed, coin(1000, DENOM));
 
         let undistributed = dist
-            .undistributed_rewards(deps.as_ref(), mock_env())
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
             .unwrap();
         assert_eq!(undistributed, coin(0, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(250, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(750, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(750, DENOM));
         deps.querier
@@ -329,6 +944,7 @@ mod tests {
                 deps.as_ref(),
                 members[0].addr.clone(),
                 members[0].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(0, DENOM));
@@ -338,6 +954,7 @@ mod tests {
                 deps.as_ref(),
                 members[1].addr.clone(),
                 members[1].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(0, DENOM));
@@ -346,19 +963,19 @@ mod tests {
         assert_eq!(distributed, coin(1000, DENOM));
 
         let undistributed = dist
-            .undistributed_rewards(deps.as_ref(), mock_env())
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
             .unwrap();
         assert_eq!(undistributed, coin(0, DENOM));
     }
 
     #[test]
     fn divisible_funds_distributed_twice() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let members = vec![
+        let members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 1,
@@ -373,19 +990,29 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1000, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(1000, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(250, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(750, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(750, DENOM));
         deps.querier
@@ -394,19 +1021,29 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(500, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(500, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(125, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(375, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(375, DENOM));
         deps.querier
@@ -416,19 +1053,19 @@ mod tests {
         assert_eq!(distributed, coin(1500, DENOM));
 
         let undistributed = dist
-            .undistributed_rewards(deps.as_ref(), mock_env())
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
             .unwrap();
         assert_eq!(undistributed, coin(0, DENOM));
     }
 
     #[test]
     fn divisible_funds_distributed_twice_accumulated() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let members = vec![
+        let members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 1,
@@ -443,14 +1080,14 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1000, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(1000, DENOM));
 
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1500, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(500, DENOM));
 
@@ -459,6 +1096,7 @@ mod tests {
                 deps.as_ref(),
                 members[0].addr.clone(),
                 members[0].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(375, DENOM));
@@ -468,6 +1106,7 @@ mod tests {
                 deps.as_ref(),
                 members[1].addr.clone(),
                 members[1].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(1125, DENOM));
@@ -476,19 +1115,29 @@ mod tests {
         assert_eq!(distributed, coin(1500, DENOM));
 
         let undistributed = dist
-            .undistributed_rewards(deps.as_ref(), mock_env())
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
             .unwrap();
         assert_eq!(undistributed, coin(0, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(375, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1125, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(1125, DENOM));
         deps.querier
@@ -499,6 +1148,7 @@ mod tests {
                 deps.as_ref(),
                 members[0].addr.clone(),
                 members[0].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(0, DENOM));
@@ -508,6 +1158,7 @@ mod tests {
                 deps.as_ref(),
                 members[1].addr.clone(),
                 members[1].points,
+                mock_env().block.time,
             )
             .unwrap();
         assert_eq!(funds, coin(0, DENOM));
@@ -516,19 +1167,19 @@ mod tests {
         assert_eq!(distributed, coin(1500, DENOM));
 
         let undistributed = dist
-            .undistributed_rewards(deps.as_ref(), mock_env())
+            .undistributed_rewards(deps.as_ref(), mock_env(), 0)
             .unwrap();
         assert_eq!(undistributed, coin(0, DENOM));
     }
 
     #[test]
     fn distribution_with_leftover() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let members = vec![
+        let members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 7,
@@ -547,26 +1198,41 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(100, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(100, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(22, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(78, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(35, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(43, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[2].addr, members[2].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[2].addr,
+                members[2].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(41, DENOM));
         deps.querier
@@ -575,38 +1241,53 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(3002, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(3000, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(678, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(2324, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(1065, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1259, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[2].addr, members[2].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[2].addr,
+                members[2].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(1259, DENOM));
     }
 
     #[test]
     fn distribution_with_leftover_accumulated() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let members = vec![
+        let members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 7,
@@ -625,45 +1306,60 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(100, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(100, DENOM));
 
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(3100, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(3000, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(700, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(2300, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(1100, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1300, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[2].addr, members[2].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[2].addr,
+                members[2].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(1300, DENOM));
     }
 
     #[test]
     fn points_changed_after_distribution() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let mut members = vec![
+        let mut members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 1,
@@ -682,7 +1378,7 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(400, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(400, DENOM));
 
@@ -694,25 +1390,41 @@ mod tests {
         dist.apply_points_correction(
             deps.as_mut(),
             &[(&members[0].addr, 5), (&members[1].addr, -2)],
+            mock_env().block.time,
         )
         .unwrap();
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(50, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(350, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(100, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(250, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[2].addr, members[2].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[2].addr,
+                members[2].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(250, DENOM));
         deps.querier
@@ -721,36 +1433,51 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1100, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(1100, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(600, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(500, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(0, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[2].addr, members[2].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[2].addr,
+                members[2].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(500, DENOM));
     }
 
     #[test]
     fn points_changed_after_distribution_accumulated() {
-        let dist = Distribution::new("distribution", "adjustment");
+        let dist = Distribution::new("distribution", "adjustment", "tranches");
 
         let mut deps = mock_dependencies();
-        dist.init(deps.as_mut(), DENOM.to_owned()).unwrap();
+        dist.init(deps.as_mut(), DENOM.to_owned(), 0).unwrap();
 
-        let mut members = vec![
+        let mut members = [
             Member {
                 addr: Addr::unchecked("member0"),
                 points: 1,
@@ -769,7 +1496,7 @@ mod tests {
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(400, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(400, DENOM));
 
@@ -781,32 +1508,48 @@ mod tests {
         dist.apply_points_correction(
             deps.as_mut(),
             &[(&members[0].addr, 5), (&members[1].addr, -2)],
+            mock_env().block.time,
         )
         .unwrap();
 
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(1500, DENOM));
         let distributed = dist
-            .distribute_rewards(deps.as_mut(), mock_env(), total_points)
+            .distribute_rewards(deps.as_mut(), mock_env(), total_points, 0)
             .unwrap();
         assert_eq!(distributed, coin(1100, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[0].addr, members[0].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[0].addr,
+                members[0].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(650, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(850, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[1].addr, members[1].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[1].addr,
+                members[1].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(100, DENOM));
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(750, DENOM));
 
         let funds = dist
-            .withdraw_rewards(deps.as_mut(), &members[2].addr, members[2].points)
+            .withdraw_rewards(
+                deps.as_mut(),
+                &members[2].addr,
+                members[2].points,
+                mock_env().block.time,
+            )
             .unwrap();
         assert_eq!(funds, coin(750, DENOM));
     }
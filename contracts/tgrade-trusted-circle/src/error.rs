@@ -1,8 +1,8 @@
-use cosmwasm_std::{Addr, Decimal, OverflowError, StdError, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, OverflowError, StdError, Timestamp, Uint128};
 use thiserror::Error;
 
 use crate::state::MemberStatus;
-use cw_utils::PaymentError;
+use cw_utils::{ParseReplyError, PaymentError};
 use tg_voting_contract::ContractError as VotingContractError;
 
 #[derive(Error, Debug, PartialEq)]
@@ -10,6 +10,12 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("Unrecognized sudo message")]
+    UnknownSudoMsg {},
+
+    #[error("Unrecognized reply id: {0}")]
+    UnknownReplyId(u64),
+
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
@@ -28,6 +34,12 @@ pub enum ContractError {
     #[error("Invalid voting period, must be 1-365 days: {0}")]
     InvalidVotingPeriod(u32),
 
+    #[error("Invalid payment deadline, must be 1-365 days: {0}")]
+    InvalidPaymentDeadline(u32),
+
+    #[error("Payment deadline passed at {0} seconds after epoch")]
+    PaymentDeadlinePassed(u64),
+
     #[error("Invalid escrow, must be at least 1 TGD. Paid {0} utgd")]
     InvalidEscrow(Uint128),
 
@@ -40,6 +52,9 @@ pub enum ContractError {
     #[error("Insufficient escrow amount: {0}")]
     InsufficientFunds(Uint128),
 
+    #[error("Escrow amount {0} is more than the escrow of the initial voters and the sender")]
+    ExcessFunds(Uint128),
+
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
@@ -111,6 +126,142 @@ pub enum ContractError {
     #[error("No members to distribute tokens to")]
     NoMembersToDistributeTo,
 
+    #[error("Contract balance {balance} doesn't cover reserved funds and withdrawable rewards {reserved}")]
+    BalanceTooLow { balance: Uint128, reserved: Uint128 },
+
+    #[error("Escrow ({denom}) cannot be distributed as rewards ({reward_denom})")]
+    EscrowNotRewardable { denom: String, reward_denom: String },
+
+    #[error("Invalid urgent proposal rules: {0}")]
+    InvalidUrgentRules(String),
+
+    #[error("This proposal cannot be urgent")]
+    UrgentNotAllowed {},
+
+    #[error("At most {0} urgent proposals per member are allowed in the limit period")]
+    UrgentLimitReached(u32),
+
+    #[error("Reveal period must be between 1 hour and a year, got {0} hours")]
+    InvalidRevealPeriod(u32),
+
+    #[error("Secret proposals are not allowed, or cannot be urgent nor polls")]
+    SecretNotAllowed {},
+
+    #[error("Votes on secret proposals must be committed and revealed")]
+    SecretProposal {},
+
+    #[error("Only votes on secret proposals are committed and revealed")]
+    NotSecretProposal {},
+
+    #[error("Vote commitment must be a sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("Votes cannot be committed anymore, they are being revealed")]
+    CommitPeriodEnded {},
+
+    #[error("Votes cannot be revealed before the voting period ends")]
+    RevealNotStarted {},
+
+    #[error("No vote committed on proposal {0}")]
+    NoCommitment(u64),
+
+    #[error("Revealed vote doesn't match the commitment")]
+    InvalidReveal {},
+
+    #[error("Trusted circle is paused until {until}")]
+    Paused { until: Timestamp },
+
+    #[error("Trusted circle is already paused until {until}")]
+    AlreadyPaused { until: Timestamp },
+
+    #[error("Pause must last between 1 hour and 30 days, got {0} hours")]
+    InvalidPausePeriod(u32),
+
+    #[error("Pause proposals must be urgent")]
+    PauseNotUrgent {},
+
+    #[error("Invalid draft period, must be 1-365 days: {0}")]
+    InvalidDraftPeriod(u32),
+
+    #[error("No draft {0}")]
+    DraftNotFound(u64),
+
+    #[error("Draft {0} expired")]
+    DraftExpired(u64),
+
+    #[error("Draft already sponsored by this member")]
+    AlreadyCosponsored {},
+
+    #[error("Contract {0} is not whitelisted")]
+    NotWhitelisted(String),
+
+    #[error("Initial voter {0} is listed more than once")]
+    DuplicateInitialVoter(String),
+
+    #[error("Invalid poll: {0}")]
+    InvalidPoll(String),
+
+    #[error("Invalid poll choices: {0}")]
+    InvalidPollChoices(String),
+
+    #[error("Polls must be voted with poll ballots")]
+    PollBallotRequired {},
+
+    #[error("Only polls are voted with poll ballots")]
+    NotPoll {},
+
+    #[error("Voting key must be a compressed or uncompressed secp256k1 public key")]
+    InvalidVotingKey {},
+
+    #[error("Voting key is already registered by another member")]
+    VotingKeyTaken {},
+
+    #[error("No signed votes")]
+    NoSignedVotes {},
+
+    #[error("Member {0} has no voting key registered")]
+    NoVotingKey(String),
+
+    #[error("Invalid nonce {nonce} for signed vote of {voter}, expected {expected}")]
+    InvalidVoteNonce {
+        voter: String,
+        nonce: u64,
+        expected: u64,
+    },
+
+    #[error("Invalid signature for vote of {0}")]
+    InvalidVoteSignature(String),
+
+    #[error("Treasury spend cannot be zero")]
+    ZeroTreasurySpend {},
+
+    #[error("Treasury has only {available} available, {requested} requested")]
+    TreasuryTooLow { available: Coin, requested: Coin },
+
+    #[error("No treasury spend {0} for this recipient")]
+    SpendNotFound(u64),
+
+    #[error("Trusted circle is importing its state")]
+    Importing {},
+
+    #[error("Trusted circle is not importing its state")]
+    NotImporting {},
+
+    #[error("Invalid state snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    #[error("No unbonded escrow to claim")]
+    NoEscrowClaims {},
+
+    #[error("Committee {0} not found")]
+    CommitteeNotFound(String),
+
+    #[error("Invalid committee: {0}")]
+    InvalidCommittee(String),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
     #[error("{0}")]
     VotingContract(#[from] VotingContractError),
 }
@@ -2,10 +2,21 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg};
-use tg4::Tg4Contract;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Coin, CosmosMsg, CustomQuery, QuerierWrapper, QueryRequest, StdResult,
+    WasmMsg, WasmQuery,
+};
+use tg3::Vote;
+use tg4::{Member, MemberListResponse, MemberResponse, Tg4Contract};
 
-use crate::msg::ExecuteMsg;
+use crate::msg::{
+    CommitteeListResponse, CommitteeResponse, ContractPermissionsResponse, DelegatedResponse,
+    DraftListResponse, DraftResponse, EscrowClaimsResponse, EscrowListResponse, EscrowResponse,
+    ExecuteMsg, PendingWorkResponse, ProposalListResponse, ProposalResponse, ProposalTallyResponse,
+    QueryMsg, RewardsResponse, SignedVote, SpendListResponse, TreasuryResponse,
+    TrustedCircleResponse, VoteInfo, VoteListResponse, VoteResponse, VotingKeyResponse,
+};
+use crate::state::{ContractPermissions, ProposalContent};
 
 /// TgTrustedCircleContract is a wrapper around Tg4Contract that provides a helpers
 /// for working with tgrade-trusted_circle contracts.
@@ -27,13 +38,484 @@ impl TgTrustedCircleContract {
         TgTrustedCircleContract(Tg4Contract(addr))
     }
 
-    #[allow(dead_code)]
-    fn encode_msg(&self, msg: ExecuteMsg) -> StdResult<CosmosMsg> {
+    pub fn encode_msg<T>(&self, msg: ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
         Ok(WasmMsg::Execute {
             contract_addr: self.addr().into(),
             msg: to_binary(&msg)?,
-            funds: vec![],
+            funds,
         }
         .into())
     }
+
+    /// Pays in escrow for the sender. `funds` have to be in the trusted circle denom
+    pub fn deposit_escrow<T>(&self, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::DepositEscrow {}, funds)
+    }
+
+    pub fn return_escrow<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::ReturnEscrow {}, vec![])
+    }
+
+    /// Claims escrow returned to the sender, once it is unbonded from the stake contract
+    pub fn claim_escrow<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::ClaimEscrow {}, vec![])
+    }
+
+    pub fn propose<T>(
+        &self,
+        title: impl Into<String>,
+        description: impl Into<String>,
+        proposal: ProposalContent,
+        urgent: bool,
+        secret: bool,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::Propose {
+            title: title.into(),
+            description: description.into(),
+            proposal,
+            urgent,
+            secret,
+        };
+        self.encode_msg(msg, vec![])
+    }
+
+    /// Proposes to a committee, which the sender has to be a member of
+    pub fn propose_to_committee<T>(
+        &self,
+        committee: impl Into<String>,
+        title: impl Into<String>,
+        description: impl Into<String>,
+        proposal: ProposalContent,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::ProposeToCommittee {
+            committee: committee.into(),
+            title: title.into(),
+            description: description.into(),
+            proposal,
+        };
+        self.encode_msg(msg, vec![])
+    }
+
+    pub fn create_draft<T>(
+        &self,
+        title: impl Into<String>,
+        description: impl Into<String>,
+        proposal: ProposalContent,
+        urgent: bool,
+        secret: bool,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::CreateDraft {
+            title: title.into(),
+            description: description.into(),
+            proposal,
+            urgent,
+            secret,
+        };
+        self.encode_msg(msg, vec![])
+    }
+
+    pub fn amend_draft<T>(
+        &self,
+        draft_id: u64,
+        title: Option<String>,
+        description: Option<String>,
+        proposal: Option<ProposalContent>,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::AmendDraft {
+            draft_id,
+            title,
+            description,
+            proposal,
+        };
+        self.encode_msg(msg, vec![])
+    }
+
+    pub fn cosponsor_draft<T>(&self, draft_id: u64) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::CosponsorDraft { draft_id }, vec![])
+    }
+
+    pub fn publish_draft<T>(&self, draft_id: u64) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::PublishDraft { draft_id }, vec![])
+    }
+
+    pub fn vote<T>(&self, proposal_id: u64, vote: Vote) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::Vote { proposal_id, vote }, vec![])
+    }
+
+    pub fn execute<T>(&self, proposal_id: u64) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::Execute { proposal_id }, vec![])
+    }
+
+    pub fn close<T>(&self, proposal_id: u64) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::Close { proposal_id }, vec![])
+    }
+
+    pub fn leave_trusted_circle<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::LeaveTrustedCircle {}, vec![])
+    }
+
+    pub fn check_pending<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::CheckPending {}, vec![])
+    }
+
+    /// Distributes `funds` together with everything sent to the contract since the last call
+    pub fn distribute_rewards<T>(&self, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::DistributeRewards {}, funds)
+    }
+
+    pub fn withdraw_rewards<T>(
+        &self,
+        owner: Option<String>,
+        receiver: Option<String>,
+    ) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::WithdrawRewards { owner, receiver }, vec![])
+    }
+
+    pub fn delegate_withdrawal<T>(&self, delegate: impl Into<String>) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::DelegateWithdrawal {
+            delegate: delegate.into(),
+        };
+        self.encode_msg(msg, vec![])
+    }
+
+    pub fn undelegate_withdrawal<T>(&self) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::UndelegateWithdrawal {}, vec![])
+    }
+
+    pub fn fund_treasury<T>(&self, funds: Vec<Coin>) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::FundTreasury {}, funds)
+    }
+
+    pub fn claim_spend<T>(&self, spend_id: u64) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::ClaimSpend { spend_id }, vec![])
+    }
+
+    pub fn pause<T>(&self, hours: u32) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::Pause { hours }, vec![])
+    }
+
+    pub fn vote_poll<T>(&self, proposal_id: u64, choices: Vec<u32>) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(
+            ExecuteMsg::VotePoll {
+                proposal_id,
+                choices,
+            },
+            vec![],
+        )
+    }
+
+    pub fn commit_vote<T>(&self, proposal_id: u64, hash: Binary) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::CommitVote { proposal_id, hash }, vec![])
+    }
+
+    pub fn reveal_vote<T>(
+        &self,
+        proposal_id: u64,
+        vote: Vote,
+        salt: Binary,
+    ) -> StdResult<CosmosMsg<T>> {
+        let msg = ExecuteMsg::RevealVote {
+            proposal_id,
+            vote,
+            salt,
+        };
+        self.encode_msg(msg, vec![])
+    }
+
+    pub fn register_voting_key<T>(&self, pubkey: Binary) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::RegisterVotingKey { pubkey }, vec![])
+    }
+
+    pub fn submit_signed_votes<T>(&self, votes: Vec<SignedVote>) -> StdResult<CosmosMsg<T>> {
+        self.encode_msg(ExecuteMsg::SubmitSignedVotes { votes }, vec![])
+    }
+
+    fn encode_smart_query<Q: CustomQuery>(&self, msg: QueryMsg) -> StdResult<QueryRequest<Q>> {
+        Ok(WasmQuery::Smart {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&msg)?,
+        }
+        .into())
+    }
+
+    fn query<Q: CustomQuery, R: serde::de::DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        msg: QueryMsg,
+    ) -> StdResult<R> {
+        querier.query(&self.encode_smart_query(msg)?)
+    }
+
+    /// Read the configuration of the trusted circle
+    pub fn trusted_circle<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+    ) -> StdResult<TrustedCircleResponse> {
+        self.query(querier, QueryMsg::TrustedCircle {})
+    }
+
+    /// Escrow status of `addr`, None for non-members
+    pub fn escrow<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        addr: impl Into<String>,
+    ) -> StdResult<EscrowResponse> {
+        self.query(querier, QueryMsg::Escrow { addr: addr.into() })
+    }
+
+    pub fn list_escrows<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<EscrowListResponse> {
+        self.query(querier, QueryMsg::ListEscrows { start_after, limit })
+    }
+
+    pub fn escrow_claims<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        owner: impl Into<String>,
+    ) -> StdResult<EscrowClaimsResponse> {
+        self.query(
+            querier,
+            QueryMsg::EscrowClaims {
+                owner: owner.into(),
+            },
+        )
+    }
+
+    pub fn proposal<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        proposal_id: u64,
+    ) -> StdResult<ProposalResponse> {
+        self.query(querier, QueryMsg::Proposal { proposal_id })
+    }
+
+    pub fn proposal_tally<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        proposal_id: u64,
+    ) -> StdResult<ProposalTallyResponse> {
+        self.query(querier, QueryMsg::ProposalTally { proposal_id })
+    }
+
+    pub fn list_proposals<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<ProposalResponse>> {
+        let res: ProposalListResponse =
+            self.query(querier, QueryMsg::ListProposals { start_after, limit })?;
+        Ok(res.proposals)
+    }
+
+    /// Vote of `voter` on a proposal, if any
+    pub fn vote_info<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        proposal_id: u64,
+        voter: impl Into<String>,
+    ) -> StdResult<Option<VoteInfo>> {
+        let msg = QueryMsg::Vote {
+            proposal_id,
+            voter: voter.into(),
+        };
+        let res: VoteResponse = self.query(querier, msg)?;
+        Ok(res.vote)
+    }
+
+    pub fn list_votes<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<VoteInfo>> {
+        let msg = QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        };
+        let res: VoteListResponse = self.query(querier, msg)?;
+        Ok(res.votes)
+    }
+
+    /// Voting points of `address`, None for non-members
+    pub fn voter<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        address: impl Into<String>,
+    ) -> StdResult<Option<u64>> {
+        let msg = QueryMsg::Voter {
+            address: address.into(),
+        };
+        let res: MemberResponse = self.query(querier, msg)?;
+        Ok(res.points)
+    }
+
+    /// Active voting members (points > 0)
+    pub fn list_voters<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Member>> {
+        let res: MemberListResponse =
+            self.query(querier, QueryMsg::ListVoters { start_after, limit })?;
+        Ok(res.members)
+    }
+
+    pub fn list_non_voting_members<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Member>> {
+        let msg = QueryMsg::ListNonVotingMembers { start_after, limit };
+        let res: MemberListResponse = self.query(querier, msg)?;
+        Ok(res.members)
+    }
+
+    /// Vested rewards `owner` can withdraw
+    pub fn withdrawable_rewards<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        owner: impl Into<String>,
+    ) -> StdResult<Coin> {
+        let msg = QueryMsg::WithdrawableRewards {
+            owner: owner.into(),
+        };
+        let res: RewardsResponse = self.query(querier, msg)?;
+        Ok(res.rewards)
+    }
+
+    pub fn unvested_rewards<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        owner: impl Into<String>,
+    ) -> StdResult<Coin> {
+        let msg = QueryMsg::UnvestedRewards {
+            owner: owner.into(),
+        };
+        let res: RewardsResponse = self.query(querier, msg)?;
+        Ok(res.rewards)
+    }
+
+    pub fn undistributed_rewards<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+    ) -> StdResult<Coin> {
+        let res: RewardsResponse = self.query(querier, QueryMsg::UndistributedRewards {})?;
+        Ok(res.rewards)
+    }
+
+    /// Address allowed to withdraw the rewards of `owner`
+    pub fn delegated<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        owner: impl Into<String>,
+    ) -> StdResult<Addr> {
+        let msg = QueryMsg::Delegated {
+            owner: owner.into(),
+        };
+        let res: DelegatedResponse = self.query(querier, msg)?;
+        Ok(res.delegated)
+    }
+
+    pub fn pending_work<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+    ) -> StdResult<PendingWorkResponse> {
+        self.query(querier, QueryMsg::PendingWork {})
+    }
+
+    pub fn treasury<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+    ) -> StdResult<TreasuryResponse> {
+        self.query(querier, QueryMsg::Treasury {})
+    }
+
+    pub fn list_spends<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        recipient: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<SpendListResponse> {
+        let msg = QueryMsg::ListSpends {
+            recipient,
+            start_after,
+            limit,
+        };
+        self.query(querier, msg)
+    }
+
+    pub fn voting_key<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        member: impl Into<String>,
+    ) -> StdResult<VotingKeyResponse> {
+        let msg = QueryMsg::VotingKey {
+            member: member.into(),
+        };
+        self.query(querier, msg)
+    }
+
+    /// Permissions of a whitelisted contract, None if `addr` is not one
+    pub fn contract_permissions<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        addr: impl Into<String>,
+    ) -> StdResult<Option<ContractPermissions>> {
+        let msg = QueryMsg::ContractPermissions { addr: addr.into() };
+        let res: ContractPermissionsResponse = self.query(querier, msg)?;
+        Ok(res.permissions)
+    }
+
+    pub fn draft<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        draft_id: u64,
+    ) -> StdResult<DraftResponse> {
+        self.query(querier, QueryMsg::Draft { draft_id })
+    }
+
+    pub fn list_drafts<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        author: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<DraftResponse>> {
+        let msg = QueryMsg::ListDrafts {
+            author,
+            start_after,
+            limit,
+        };
+        let res: DraftListResponse = self.query(querier, msg)?;
+        Ok(res.drafts)
+    }
+
+    pub fn committee<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        name: impl Into<String>,
+    ) -> StdResult<CommitteeResponse> {
+        self.query(querier, QueryMsg::Committee { name: name.into() })
+    }
+
+    pub fn list_committees<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<CommitteeResponse>> {
+        let msg = QueryMsg::ListCommittees { start_after, limit };
+        let res: CommitteeListResponse = self.query(querier, msg)?;
+        Ok(res.committees)
+    }
 }
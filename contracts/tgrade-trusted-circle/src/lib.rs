@@ -3,8 +3,13 @@ pub mod distribution;
 pub mod error;
 pub mod helpers;
 pub mod i128;
+pub mod membership;
 pub mod migration;
 pub mod msg;
+#[cfg(any(test, feature = "multitest"))]
+pub mod multitest;
+pub mod poll;
+pub mod stake;
 pub mod state;
 mod tests;
 
@@ -0,0 +1,116 @@
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, Env, Order, Reply, StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw_utils::parse_reply_instantiate_data;
+use tg_bindings::TgradeMsg;
+use tgrade_membership_nft::msg::{
+    ExecuteMsg as NftExecuteMsg, InstantiateMsg as NftInstantiateMsg, Metadata, MintMsg,
+};
+
+use crate::error::ContractError;
+use crate::msg::MembershipNftInit;
+use crate::state::{
+    track_membership, MemberStatus, ESCROWS, MEMBERSHIP_CHANGES, MEMBERSHIP_NFT, MEMBERSHIP_TOKENS,
+};
+
+pub const MEMBERSHIP_NFT_REPLY_ID: u64 = 1;
+
+/// Instantiates the membership NFT, with the trusted circle as its minter
+pub fn instantiate_membership_nft(
+    env: &Env,
+    init: MembershipNftInit,
+) -> StdResult<SubMsg<TgradeMsg>> {
+    let msg = NftInstantiateMsg {
+        name: init.name.clone(),
+        symbol: init.symbol,
+        minter: env.contract.address.to_string(),
+    };
+    let msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id: init.code_id,
+        msg: to_binary(&msg)?,
+        funds: vec![],
+        label: init.name,
+    };
+    Ok(SubMsg::reply_on_success(msg, MEMBERSHIP_NFT_REPLY_ID))
+}
+
+/// Stores the address of the membership NFT, and mints the tokens of the voting members
+/// (the initial voters)
+pub fn membership_nft_instantiated(
+    storage: &mut dyn Storage,
+    msg: Reply,
+) -> Result<Vec<CosmosMsg<TgradeMsg>>, ContractError> {
+    let res = parse_reply_instantiate_data(msg)?;
+    MEMBERSHIP_NFT.save(storage, &Addr::unchecked(res.contract_address))?;
+
+    let voters = ESCROWS
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((addr, escrow)) if escrow.status.is_voting() => Some(Ok(addr)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    for voter in &voters {
+        track_membership(storage, voter)?;
+    }
+    Ok(membership_token_msgs(storage)?)
+}
+
+/// Mints, updates or burns the membership tokens of the members whose status changed. Members
+/// get a token once voting, and lose it when leaving or removed
+pub fn membership_token_msgs(storage: &mut dyn Storage) -> StdResult<Vec<CosmosMsg<TgradeMsg>>> {
+    let nft = match MEMBERSHIP_NFT.may_load(storage)? {
+        Some(nft) => nft,
+        None => return Ok(vec![]),
+    };
+    let changed = MEMBERSHIP_CHANGES
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs = vec![];
+    for member in changed {
+        MEMBERSHIP_CHANGES.remove(storage, &member);
+        let status = ESCROWS
+            .may_load(storage, &member)?
+            .map(|escrow| escrow.status);
+        let token = MEMBERSHIP_TOKENS.may_load(storage, &member)?;
+        let msg = match (token, status) {
+            (None, Some(status @ MemberStatus::Voting {})) => {
+                let status = status.to_string();
+                MEMBERSHIP_TOKENS.save(storage, &member, &status)?;
+                NftExecuteMsg::Mint(MintMsg {
+                    token_id: member.to_string(),
+                    owner: member.to_string(),
+                    token_uri: None,
+                    extension: Metadata { status },
+                })
+            }
+            (Some(_), None | Some(MemberStatus::Leaving { .. })) => {
+                MEMBERSHIP_TOKENS.remove(storage, &member);
+                NftExecuteMsg::Burn {
+                    token_id: member.to_string(),
+                }
+            }
+            (Some(old), Some(status)) if old != status.to_string() => {
+                let status = status.to_string();
+                MEMBERSHIP_TOKENS.save(storage, &member, &status)?;
+                NftExecuteMsg::UpdateMetadata {
+                    token_id: member.to_string(),
+                    extension: Metadata { status },
+                }
+            }
+            _ => continue,
+        };
+        msgs.push(
+            WasmMsg::Execute {
+                contract_addr: nft.to_string(),
+                msg: to_binary(&msg)?,
+                funds: vec![],
+            }
+            .into(),
+        );
+    }
+    Ok(msgs)
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{CustomQuery, DepsMut, Empty, Env, Order};
+use cosmwasm_std::{CustomQuery, DepsMut, Empty, Env, Order, StdResult, Uint128};
 use cw_storage_plus::Map;
 use cw_utils::Expiration;
 use semver::Version;
@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize};
 use tg3::Status;
 
 use crate::error::ContractError;
-use crate::state::{Proposal, ProposalContent, Votes, VotingRules, PROPOSALS};
+use crate::state::{
+    Proposal, ProposalContent, Votes, VotingRules, ESCROWS, ESCROW_TOTALS, PROPOSALS,
+    PROPOSAL_BY_EXPIRY, REWARD_TOTAL, TOTAL, TRUSTED_CIRCLE, VOTING_KEYS, VOTING_KEY_OWNERS,
+};
 
 /// `crate::state::Proposal` version from v0.6.0-beta1 and before
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -37,6 +40,11 @@ impl ProposalV0_6_0Beta1 {
             rules: self.rules,
             total_points: self.total_weight,
             votes: self.votes,
+            urgent: false,
+            urgent_period: None,
+            secret: None,
+            cosponsors: vec![],
+            committee: None,
         }
     }
 }
@@ -67,3 +75,119 @@ pub fn migrate_proposals<Q: CustomQuery>(
 
     Ok(())
 }
+
+/// Proposals used to be indexed by their expiration only, so of the proposals expiring in the
+/// same second only the last one was kept. The index is rebuilt from the open proposals (the only
+/// ones it is used for).
+pub fn migrate_proposals_by_expiry<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    _env: &Env,
+    _msg: &Empty,
+    _version: &Version,
+) -> Result<(), ContractError> {
+    let old_index: Map<u64, u64> = Map::new("proposals_by_expiry");
+
+    let expiries = old_index
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if expiries.is_empty() {
+        return Ok(());
+    }
+    for expiry in expiries {
+        old_index.remove(deps.storage, expiry);
+    }
+
+    let open = PROPOSALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((id, prop)) => match (prop.status, prop.expires) {
+                (Status::Open, Expiration::AtTime(expires)) => Some(Ok((expires.seconds(), id))),
+                _ => None,
+            },
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    for key in open {
+        PROPOSAL_BY_EXPIRY.save(deps.storage, key, &Empty {})?;
+    }
+
+    Ok(())
+}
+
+/// `TOTAL` used to be a plain item. Saving it once as a snapshot starts its changelog, so that
+/// queries at heights before the migration return the total at the time of the migration.
+pub fn migrate_total_snapshot<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    _msg: &Empty,
+    _version: &Version,
+) -> Result<(), ContractError> {
+    let seeded = TOTAL
+        .changelog()
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if !seeded {
+        let total = TOTAL.may_load(deps.storage)?.unwrap_or_default();
+        TOTAL.save(deps.storage, &total, env.block.height)?;
+    }
+
+    Ok(())
+}
+
+/// Escrow totals weren't tracked before, so they are computed once from all the escrows.
+pub fn migrate_escrow_totals<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    _env: &Env,
+    _msg: &Empty,
+    _version: &Version,
+) -> Result<(), ContractError> {
+    let denom = TRUSTED_CIRCLE.load(deps.storage)?.denom;
+    if ESCROW_TOTALS.has(deps.storage, &denom) {
+        return Ok(());
+    }
+
+    let total = ESCROWS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|escrow| escrow.map(|(_, escrow)| escrow.paid))
+        .sum::<StdResult<Uint128>>()?;
+    ESCROW_TOTALS.save(deps.storage, &denom, &total)?;
+
+    Ok(())
+}
+
+/// Reward points weren't tracked before, rewards were distributed over voting points only
+/// (what the default reward weights still do).
+pub fn migrate_reward_total<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    _env: &Env,
+    _msg: &Empty,
+    _version: &Version,
+) -> Result<(), ContractError> {
+    if REWARD_TOTAL.may_load(deps.storage)?.is_none() {
+        let total = TOTAL.may_load(deps.storage)?.unwrap_or_default();
+        REWARD_TOTAL.save(deps.storage, &total)?;
+    }
+
+    Ok(())
+}
+
+/// Indexes the member of every voting key registered before keys had to be unique. Keys shared by
+/// several members stay with the first of them
+pub fn migrate_voting_key_owners<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    _env: &Env,
+    _msg: &Empty,
+    _version: &Version,
+) -> Result<(), ContractError> {
+    let keys = VOTING_KEYS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (member, pubkey) in keys {
+        if !VOTING_KEY_OWNERS.has(deps.storage, &pubkey) {
+            VOTING_KEY_OWNERS.save(deps.storage, &pubkey, &member)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,10 +1,16 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{EscrowStatus, PendingEscrow, ProposalContent, Votes, VotingRules};
-use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use crate::distribution::{DistributionSnapshot, WithdrawAdjustment};
+use crate::state::{
+    Batch, ContractPermissions, EscrowAction, EscrowStatus, ImportTotals, MemberStatus,
+    PendingEscrow, Proposal, ProposalContent, ProposalKind, RewardWeights, SecretBallot,
+    SecretRules, UrgentRules, Votes, VotingRules,
+};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Timestamp, Uint128};
 use cw_utils::Expiration;
 use tg3::{Status, Vote};
+use tg4::Member;
 
 // "Hardcoded" for business reasons
 fn default_denom() -> String {
@@ -32,12 +38,71 @@ pub struct InstantiateMsg {
     pub allow_end_early: bool,
     /// List of non-voting members to be added to the TRUSTED_CIRCLE upon creation
     pub initial_members: Vec<String>,
+    /// List of voting members to be added to the TRUSTED_CIRCLE upon creation, besides the
+    /// sender. Their escrow (`escrow_amount` each) is sent along with the sender's
+    #[serde(default)]
+    pub initial_voters: Vec<String>,
     /// cw4 contract with list of addresses denied being part of the trusted circle
     pub deny_list: Option<String>,
     /// If true, no further adjustments may happen
     pub edit_trusted_circle_disabled: bool,
     /// Distributed reward denom
     pub reward_denom: String,
+    /// Days after a batch's grace period ends for its pending members to pay in their escrow.
+    /// Unpaid pending members are expired by `CheckPending` after that.
+    /// If not set, pending members never expire
+    #[serde(default)]
+    pub payment_deadline: Option<u32>,
+    /// If true, expired pending members are removed from the trusted circle.
+    /// Otherwise they are reverted to non-voting members
+    #[serde(default)]
+    pub remove_expired_pending: bool,
+    /// Days over which distributed rewards vest linearly. Members who leave or are punished
+    /// forfeit their unvested rewards. If not set, rewards can be withdrawn right away
+    #[serde(default)]
+    pub reward_vesting_period: Option<u32>,
+    /// Share of the rewards for each membership tier. If not set, only voting members get
+    /// rewards
+    #[serde(default)]
+    pub reward_weights: Option<RewardWeights>,
+    /// Rules of urgent proposals. If not set, urgent proposals are not allowed
+    #[serde(default)]
+    pub urgent_rules: Option<UrgentRules>,
+    /// Rules of secret proposals. If not set, secret proposals are not allowed
+    #[serde(default)]
+    pub secret_rules: Option<SecretRules>,
+    /// Address allowed to pause the trusted circle in an emergency
+    #[serde(default)]
+    pub guardian: Option<String>,
+    /// Days drafts can be amended and cosponsored before expiring. Defaults to 30 days
+    #[serde(default)]
+    pub draft_period: Option<u32>,
+    /// If set, the trusted circle is created empty (no funds are sent, and no initial members
+    /// are allowed), and this address imports the state of another one with `ImportState`
+    #[serde(default)]
+    pub importer: Option<String>,
+    /// tg4-stake contract to bond the escrow in, so it also counts as stake of the trusted
+    /// circle. Returned escrow is unbonded, and claimable with `ClaimEscrow` after the unbonding
+    /// period. Slashed escrow is unbonded as well, so the trusted circle doesn't need to be a
+    /// slasher of the stake contract
+    #[serde(default)]
+    pub stake_contract: Option<String>,
+    /// If set, a `tgrade-membership-nft` contract is instantiated, and every voting member gets
+    /// a non-transferable token on it, for as long as they are a member
+    #[serde(default)]
+    pub membership_nft: Option<MembershipNftInit>,
+}
+
+/// Membership NFT instantiated along with the trusted circle, which is its minter
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct MembershipNftInit {
+    /// Code id of the `tgrade-membership-nft` contract
+    pub code_id: u64,
+    /// Name of the NFT collection
+    pub name: String,
+    /// Symbol of the NFT collection
+    pub symbol: String,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -46,10 +111,54 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     DepositEscrow {},
     ReturnEscrow {},
+    /// Sends escrow returned to the sender once it is unbonded from the stake contract
+    ClaimEscrow {},
     Propose {
         title: String,
         description: String,
         proposal: ProposalContent,
+        /// Decide the proposal faster, with the urgent rules of the trusted circle. Only allowed
+        /// for some proposals, and a limited number of times per member
+        #[serde(default)]
+        urgent: bool,
+        /// Vote by commit and reveal, with the secret rules of the trusted circle
+        #[serde(default)]
+        secret: bool,
+    },
+    /// Proposes to a committee one of the kinds of proposals it decides. Only committee members
+    /// may propose and vote, with the voting rules of the committee
+    ProposeToCommittee {
+        committee: String,
+        title: String,
+        description: String,
+        proposal: ProposalContent,
+    },
+    /// Creates a draft proposal, which can be amended and cosponsored before voting starts.
+    /// Drafts expire after the draft period of the trusted circle if not published
+    CreateDraft {
+        title: String,
+        description: String,
+        proposal: ProposalContent,
+        #[serde(default)]
+        urgent: bool,
+        #[serde(default)]
+        secret: bool,
+    },
+    /// Amends a draft. Only allowed to its author. Cosponsors are cleared, as they sponsored the
+    /// previous version
+    AmendDraft {
+        draft_id: u64,
+        title: Option<String>,
+        description: Option<String>,
+        proposal: Option<ProposalContent>,
+    },
+    /// Cosponsors a draft of another voting member
+    CosponsorDraft {
+        draft_id: u64,
+    },
+    /// Opens a draft for voting, exactly like `Propose`. Only allowed to its author
+    PublishDraft {
+        draft_id: u64,
     },
     Vote {
         proposal_id: u64,
@@ -65,15 +174,121 @@ pub enum ExecuteMsg {
     LeaveTrustedCircle {},
     /// This checks any batches whose grace period has passed, and who have not all paid escrow.
     /// Run through these groups and promote anyone who has paid escrow.
-    /// This also checks if there's a pending escrow that needs to be applied.
+    /// This also checks if there's a pending escrow that needs to be applied, and expires
+    /// pending members who missed the payment deadline (refunding any partial escrow).
+    /// A bounded number of members is checked on every call, the rest is resumed by the next
+    /// ones (see `QueryMsg::PendingWork`).
     CheckPending {},
 
     /// Distributes rewards sent with this message, and all funds transferred since last call of this
     /// to members equally. Rewards are not immediately send to members, but assigned to them for later
     /// withdrawal (see: `ExecuteMsg::WithdrawRewards`)
     DistributeRewards {},
-    /// Withdraws rewards which were previously distributed and assigned to sender.
-    WithdrawRewards {},
+    /// Withdraws rewards which were previously distributed and assigned to owner.
+    WithdrawRewards {
+        /// Account from which assigned rewards would be withdrawn; `sender` by default. `sender` has
+        /// to be eligible for withdrawal from `owner` address to perform this call (`owner` has to
+        /// call `DelegateWithdrawal { delegate: sender }` before)
+        owner: Option<String>,
+        /// Address where to transfer funds. If not present, funds would be sent to `owner`.
+        receiver: Option<String>,
+    },
+    /// Sets given address as allowed for senders funds withdrawal. Funds still can be withdrawn by
+    /// sender himself, but this additional account is allowed to perform it as well. There can be only
+    /// one account delegated for withdrawal for any owner at any single time.
+    DelegateWithdrawal {
+        /// Account delegated for withdrawal
+        delegate: String,
+    },
+    /// Removes the account delegated for senders funds withdrawal, if any
+    UndelegateWithdrawal {},
+    /// Adds the funds sent with this message to the treasury. They are only spent by
+    /// `ProposalContent::TreasurySpend` proposals, and never distributed as rewards
+    FundTreasury {},
+    /// Sends the vested part of a treasury spend to its recipient, who has to be the sender
+    ClaimSpend {
+        /// Id of the proposal which granted the spend
+        spend_id: u64,
+    },
+    /// Pauses proposal execution, membership changes and escrow withdrawals for `hours`. Only
+    /// allowed to the guardian, when the trusted circle is not paused already. Only the timeout or
+    /// an `Unpause` proposal lifts it
+    Pause {
+        hours: u32,
+    },
+    /// Votes on a poll, with the indexes of the chosen options. For ranked choice polls, they are
+    /// in order of preference
+    VotePoll {
+        proposal_id: u64,
+        choices: Vec<u32>,
+    },
+    /// Commits a vote on a secret proposal during its voting period. `hash` is the sha256 hash of
+    /// the JSON encoded `VoteCommitment`
+    CommitVote {
+        proposal_id: u64,
+        hash: Binary,
+    },
+    /// Reveals a vote committed on a secret proposal, after its voting period
+    RevealVote {
+        proposal_id: u64,
+        vote: Vote,
+        salt: Binary,
+    },
+    /// Registers the secp256k1 public key (compressed or uncompressed) verifying signed votes of
+    /// the sender, replacing the previous one if any
+    RegisterVotingKey {
+        pubkey: Binary,
+    },
+    /// Votes on behalf of members who signed their ballots off-chain. Anyone (e.g. a relayer) can
+    /// submit them. Fails if any of the votes is invalid
+    SubmitSignedVotes {
+        votes: Vec<SignedVote>,
+    },
+    /// Imports a page of the state of another trusted circle, as returned by
+    /// `QueryMsg::ExportState`. Only allowed to the importer, before the import is finished.
+    /// Pages have to be imported in order, starting with the one with the header
+    ImportState {
+        snapshot: StateSnapshot,
+    },
+    /// Checks the imported state against the snapshot header and the contract balance, and opens
+    /// the trusted circle. Only allowed to the importer
+    FinishImport {},
+}
+
+/// Vote committed on a secret proposal
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoteCommitment {
+    pub voter: String,
+    pub proposal_id: u64,
+    pub vote: Vote,
+    /// Random data keeping the vote secret until revealed
+    pub salt: Binary,
+}
+
+/// Ballot signed off-chain by a member
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignedVote {
+    pub voter: String,
+    pub proposal_id: u64,
+    pub vote: Vote,
+    /// Has to be the next nonce of the voter, as returned by `QueryMsg::VotingKey`
+    pub nonce: u64,
+    /// Compact (64 bytes) secp256k1 signature of the sha256 hash of the JSON encoded
+    /// `VoteSignDoc`, made with the registered key of the voter
+    pub signature: Binary,
+}
+
+/// Document signed by members voting off-chain
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VoteSignDoc {
+    pub chain_id: String,
+    /// Address of the trusted circle contract
+    pub contract: String,
+    /// Address of the member voting
+    pub voter: String,
+    pub proposal_id: u64,
+    pub vote: Vote,
+    pub nonce: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -83,11 +298,20 @@ pub enum QueryMsg {
     TrustedCircle {},
     /// Returns TotalPointsResponse
     TotalPoints {},
+    /// Returns TotalPointsResponse, with the total points at the beginning of the given height
+    TotalPointsAtHeight { at_height: u64 },
     /// Returns MemberListResponse, for all (voting and non-voting) members
     ListMembers {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Returns MembersAtHeightResponse, for all (voting and non-voting) members at the beginning
+    /// of the given height. This includes members who were removed after that height
+    ListMembersAtHeight {
+        at_height: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Returns MemberListResponse, only points == 0 members
     ListNonVotingMembers {
         start_after: Option<String>,
@@ -105,6 +329,8 @@ pub enum QueryMsg {
     Rules {},
     /// Returns ProposalResponse
     Proposal { proposal_id: u64 },
+    /// Returns ProposalTallyResponse, with what is still needed for the proposal to pass
+    ProposalTally { proposal_id: u64 },
     /// Returns ProposalListResponse
     ListProposals {
         start_after: Option<u64>,
@@ -136,14 +362,36 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Returns MembersAtHeightResponse, only voting members (points > 0) at the beginning of the
+    /// given height. A bounded number of members is checked per call, so a page can come back
+    /// short (or empty) before the end of the members
+    ListVotersAtHeight {
+        at_height: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Returns an EscrowListResponse, with all members that have escrow.
+    /// Pending members are marked with their payment deadline, if any.
     ListEscrows {
         start_after: Option<String>,
         limit: Option<u32>,
     },
-    /// Return how much rewards are assigned for withdrawal to given address. Returns
-    /// `RewardsResponse`.
+    /// Returns an EscrowHistoryResponse, with all the changes to the escrow and status of a
+    /// member (oldest first), including members who already left
+    EscrowHistory {
+        addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns an EscrowClaimsResponse, with the escrow returned to `owner` which is unbonding
+    /// from the stake contract or not claimed yet
+    EscrowClaims { owner: String },
+    /// Return how much rewards are assigned for withdrawal to given address. Only vested rewards
+    /// are included. Returns `RewardsResponse`.
     WithdrawableRewards { owner: String },
+    /// Return how much rewards assigned to given address are not vested yet. Returns
+    /// `RewardsResponse`.
+    UnvestedRewards { owner: String },
     /// Return how much rewards were distributed in total by this contract. Returns
     /// `RewardsResponse`.
     DistributedRewards {},
@@ -151,6 +399,46 @@ pub enum QueryMsg {
     /// `ExecuteMsg::DistributeRewards`, and wait for distribution.
     /// Returns `RewardsResponse`.
     UndistributedRewards {},
+    /// Return address allowed for withdrawal of the funds assigned to owner. Returns
+    /// `DelegatedResponse`
+    Delegated { owner: String },
+    /// Returns `RewardWeightsResponse`, with the reward share of each membership tier
+    RewardWeights {},
+    /// Returns PendingWorkResponse, with how much work is left for `ExecuteMsg::CheckPending`
+    PendingWork {},
+    /// Returns TreasuryResponse, with the treasury funds of every denom
+    Treasury {},
+    /// Returns VotingKeyResponse, with the key verifying signed votes of `member`
+    VotingKey { member: String },
+    /// Returns SpendListResponse, with the treasury spends (optionally only those of `recipient`)
+    ListSpends {
+        recipient: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns ContractPermissionsResponse, with the permissions of a whitelisted contract
+    ContractPermissions { addr: String },
+    /// Returns DraftResponse
+    Draft { draft_id: u64 },
+    /// Returns DraftListResponse, with the unpublished drafts (optionally only those of `author`)
+    ListDrafts {
+        author: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns CommitteeResponse
+    Committee { name: String },
+    /// Returns CommitteeListResponse
+    ListCommittees {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns StateSnapshot, a page of the state of the trusted circle to be imported into a new
+    /// one. Pass the `next` key of a page as `start_after` to get the following one
+    ExportState {
+        start_after: Option<SnapshotKey>,
+        limit: Option<u32>,
+    },
 }
 
 pub type EscrowResponse = Option<EscrowStatus>;
@@ -168,6 +456,20 @@ pub struct TrustedCircleResponse {
     pub rules: VotingRules,
     pub deny_list: Option<Addr>,
     pub edit_trusted_circle_disabled: bool,
+    /// Days after a batch's grace period ends for its pending members to pay in their escrow
+    pub payment_deadline: Option<u32>,
+    pub remove_expired_pending: bool,
+    pub urgent_rules: Option<UrgentRules>,
+    pub secret_rules: Option<SecretRules>,
+    pub guardian: Option<Addr>,
+    /// Set while the trusted circle is paused
+    pub paused_until: Option<Timestamp>,
+    /// Days drafts can be amended and cosponsored before expiring
+    pub draft_period: u32,
+    /// tg4-stake contract the escrow is bonded in, if any
+    pub stake_contract: Option<Addr>,
+    /// Contract of the membership tokens of voting members, if any
+    pub membership_nft: Option<Addr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -187,9 +489,57 @@ pub struct ProposalResponse {
     /// as well as the total_points of the voting group may have changed since this time. That means
     /// that the generic `Threshold{}` query does not provide valid information for existing proposals.
     pub rules: VotingRules,
+    /// Length of the voting period. Unlike `rules.voting_period`, also exact for urgent proposals,
+    /// voted in hours
+    pub voting_period_secs: u64,
     pub total_points: u64,
     /// This is a running tally of all votes cast on this proposal so far.
     pub votes: Votes,
+    /// Urgent proposals are voted with the urgent rules of the trusted circle
+    pub urgent: bool,
+    /// Set for secret proposals, voted by commit and reveal. Only revealed votes are tallied
+    pub secret: Option<SecretBallot>,
+    /// Results of polls
+    pub poll_results: Option<PollResults>,
+    /// Members who cosponsored the draft of the proposal
+    pub cosponsors: Vec<Addr>,
+    /// Committee deciding the proposal, if not the whole trusted circle
+    pub committee: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PollResults {
+    /// Points of every option. Ranked choice polls have a round per elimination, plurality polls
+    /// a single one
+    pub rounds: Vec<Vec<u64>>,
+    /// Option with most points (or the majority for ranked choice polls). Not set if tied
+    pub winner: Option<u32>,
+}
+
+/// Breakdown of the pass requirements of a proposal, and how far its votes are from them.
+/// Every count is in voting points.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ProposalTallyResponse {
+    pub id: u64,
+    pub status: Status,
+    pub total_points: u64,
+    pub votes: Votes,
+    /// Votes (of any kind, abstain included) needed to reach the quorum
+    pub quorum_needed: u64,
+    /// Votes cast so far
+    pub quorum_reached: u64,
+    /// Yes votes needed to pass once expired, out of the votes cast so far (minus abstain)
+    pub yes_needed_at_expiry: u64,
+    /// Yes votes needed to pass before expiry, counting all uncast points as No.
+    /// None if the rules don't allow ending early
+    pub yes_needed_early: Option<u64>,
+    /// Points of the voters who did not vote yet
+    pub uncast_points: u64,
+    /// True if the proposal is open, and can still pass before expiry if enough uncast points
+    /// vote yes
+    pub early_pass_possible: bool,
+    /// Status at expiry, if no more votes are cast
+    pub projected_status: Status,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -200,9 +550,13 @@ pub struct ProposalListResponse {
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct VoteInfo {
     pub voter: String,
+    /// Poll ballots are recorded as yes votes
     pub vote: Vote,
     pub proposal_id: u64,
     pub points: u64,
+    /// Options chosen on a poll
+    #[serde(default)]
+    pub choices: Option<Vec<u32>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -219,6 +573,9 @@ pub struct VoteResponse {
 pub struct Escrow {
     pub addr: String,
     pub escrow_status: EscrowStatus,
+    /// Timestamp (seconds) after which this pending member is expired if escrow is still unpaid.
+    /// Only set for pending members when a payment deadline is configured
+    pub payment_deadline: Option<u64>,
 }
 
 #[cfg(test)]
@@ -228,12 +585,222 @@ impl Escrow {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MembersAtHeightResponse {
+    pub members: Vec<Member>,
+    /// Last member checked, unless all members were. Pass it as `start_after` to continue
+    pub next_start_after: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct EscrowListResponse {
     pub escrows: Vec<Escrow>,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EscrowHistoryEntry {
+    /// Used for pagination
+    pub id: u64,
+    pub height: u64,
+    pub time: Timestamp,
+    pub action: EscrowAction,
+    /// Escrow paid after the change
+    pub paid: Uint128,
+    /// Status after the change, None if the member was removed
+    pub status: Option<MemberStatus>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EscrowHistoryResponse {
+    pub entries: Vec<EscrowHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EscrowClaim {
+    pub amount: Uint128,
+    /// Claimable from this time on
+    pub release_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EscrowClaimsResponse {
+    pub claims: Vec<EscrowClaim>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PendingWorkResponse {
+    /// Members still to be checked against a new escrow amount
+    pub escrow_members: u64,
+    /// Batches ready to be promoted
+    pub promotion_batches: u64,
+    /// Members of those batches still to be checked for promotion
+    pub promotion_members: u64,
+    /// Promoted batches with unpaid members past their payment deadline
+    pub expiry_batches: u64,
+    /// Members of those batches still to be checked for expiration
+    pub expiry_members: u64,
+    /// At most a fixed number of members (or batches) is counted. If true, some counts stopped
+    /// there, and more work is left
+    pub capped: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct RewardsResponse {
     pub rewards: Coin,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DelegatedResponse {
+    pub delegated: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct VotingKeyResponse {
+    pub pubkey: Option<Binary>,
+    /// Nonce of the next signed vote of the member
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct TreasuryResponse {
+    /// Funds which can be granted by new spends
+    pub available: Vec<Coin>,
+    /// Funds granted to spends, but not claimed yet
+    pub committed: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SpendInfo {
+    /// Id of the proposal which granted the spend
+    pub spend_id: u64,
+    pub recipient: Addr,
+    pub amount: Coin,
+    pub claimed: Uint128,
+    /// Vested part of the spend which is not claimed yet
+    pub claimable: Uint128,
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SpendListResponse {
+    pub spends: Vec<SpendInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ContractPermissionsResponse {
+    /// None if `addr` is not a whitelisted contract
+    pub permissions: Option<ContractPermissions>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DraftResponse {
+    pub draft_id: u64,
+    pub author: Addr,
+    pub title: String,
+    pub description: String,
+    pub proposal: ProposalContent,
+    pub urgent: bool,
+    pub secret: bool,
+    pub cosponsors: Vec<Addr>,
+    /// The draft cannot be amended, cosponsored nor published anymore after that
+    pub expires: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DraftListResponse {
+    pub drafts: Vec<DraftResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct CommitteeResponse {
+    pub name: String,
+    pub members: Vec<Addr>,
+    pub powers: Vec<ProposalKind>,
+    pub rules: VotingRules,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct CommitteeListResponse {
+    pub committees: Vec<CommitteeResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct RewardWeightsResponse {
+    pub weights: RewardWeights,
+    /// Sum of the reward points of all members
+    pub total_points: u64,
+}
+
+/// Version of the `StateSnapshot` format
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A page of the exported state of a trusted circle
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StateSnapshot {
+    pub version: u32,
+    /// Only set on the first page
+    pub header: Option<SnapshotHeader>,
+    /// Members, then withdraw adjustments, batches and proposals
+    pub entries: Vec<SnapshotEntry>,
+    /// Key of the last entry, if there may be more pages
+    pub next: Option<SnapshotKey>,
+}
+
+/// State of the trusted circle which is not kept per member, batch or proposal
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SnapshotHeader {
+    pub denom: String,
+    pub escrow_amount: Uint128,
+    pub reward_weights: RewardWeights,
+    pub distribution: DistributionSnapshot,
+    /// Voting points, reward points and escrow of all members, checked when finishing the import
+    pub totals: ImportTotals,
+    pub proposal_count: u64,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotEntry {
+    Member {
+        addr: String,
+        escrow: EscrowStatus,
+        /// Voting points, None if not in the tg4 member list
+        points: Option<u64>,
+    },
+    Adjustment {
+        addr: String,
+        adjustment: WithdrawAdjustment,
+    },
+    Batch {
+        /// Id of the proposal which added the batch
+        id: u64,
+        batch: Batch,
+    },
+    Proposal {
+        id: u64,
+        proposal: Proposal,
+    },
+}
+
+impl SnapshotEntry {
+    pub fn key(&self) -> SnapshotKey {
+        match self {
+            SnapshotEntry::Member { addr, .. } => SnapshotKey::Member(addr.clone()),
+            SnapshotEntry::Adjustment { addr, .. } => SnapshotKey::Adjustment(addr.clone()),
+            SnapshotEntry::Batch { id, .. } => SnapshotKey::Batch(*id),
+            SnapshotEntry::Proposal { id, .. } => SnapshotKey::Proposal(*id),
+        }
+    }
+}
+
+/// Position in the exported state, used for pagination
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotKey {
+    Member(String),
+    Adjustment(String),
+    Batch(u64),
+    Proposal(u64),
+}
@@ -0,0 +1,168 @@
+//! Helpers for testing contracts integrating with a trusted circle in multi-test, enabled with
+//! the `multitest` feature.
+use anyhow::{anyhow, Result as AnyResult};
+use std::cmp::max;
+
+use cosmwasm_std::{coins, Addr, CosmosMsg, Uint128};
+use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
+use tg3::{Status, Vote};
+use tg_bindings::{TgradeMsg, TgradeQuery};
+use tg_bindings_test::TgradeApp;
+
+use crate::helpers::TgTrustedCircleContract;
+use crate::msg::InstantiateMsg;
+use crate::state::ProposalContent;
+
+pub fn contract_trusted_circle() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    Box::new(
+        ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )
+        .with_sudo(crate::contract::sudo)
+        .with_reply(crate::contract::reply),
+    )
+}
+
+/// Deploys and drives a trusted circle in multi-test, through the messages encoded by
+/// `TgTrustedCircleContract`
+pub trait TrustedCircleExt {
+    fn execute_circle(&mut self, sender: &str, msg: CosmosMsg<TgradeMsg>)
+        -> AnyResult<AppResponse>;
+
+    /// Stores the trusted circle code and instantiates it. `admin` pays in the escrow of every
+    /// initial voter and its own, so it has to hold enough of `msg.denom`
+    fn deploy_trusted_circle(
+        &mut self,
+        admin: &str,
+        msg: &InstantiateMsg,
+    ) -> AnyResult<TgTrustedCircleContract>;
+
+    /// Pays in the escrow currently required by the trusted circle for `member`
+    fn deposit_escrow(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        member: &str,
+    ) -> AnyResult<AppResponse>;
+
+    /// Creates a regular proposal, returning its id
+    fn propose(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        proposer: &str,
+        proposal: ProposalContent,
+    ) -> AnyResult<u64>;
+
+    /// Votes yes with `voters` while the proposal is open, then executes it as the first of them
+    fn pass_and_execute(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        proposal_id: u64,
+        voters: &[&str],
+    ) -> AnyResult<AppResponse>;
+
+    /// Proposes as the first of `voters`, and passes and executes the proposal with all of them.
+    /// Returns the proposal id
+    fn propose_and_execute(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        voters: &[&str],
+        proposal: ProposalContent,
+    ) -> AnyResult<u64>;
+}
+
+impl TrustedCircleExt for TgradeApp {
+    fn execute_circle(
+        &mut self,
+        sender: &str,
+        msg: CosmosMsg<TgradeMsg>,
+    ) -> AnyResult<AppResponse> {
+        self.execute(Addr::unchecked(sender), msg)
+    }
+
+    fn deploy_trusted_circle(
+        &mut self,
+        admin: &str,
+        msg: &InstantiateMsg,
+    ) -> AnyResult<TgTrustedCircleContract> {
+        let code_id = self.store_code(contract_trusted_circle());
+        let escrow = msg.escrow_amount * Uint128::from(msg.initial_voters.len() as u64 + 1);
+        let addr = self.instantiate_contract(
+            code_id,
+            Addr::unchecked(admin),
+            msg,
+            &coins(escrow.u128(), &msg.denom),
+            msg.name.clone(),
+            Some(admin.to_owned()),
+        )?;
+        Ok(TgTrustedCircleContract::new(addr))
+    }
+
+    fn deposit_escrow(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        member: &str,
+    ) -> AnyResult<AppResponse> {
+        let trusted_circle = circle.trusted_circle(&self.wrap())?;
+        let pending = trusted_circle.escrow_pending.map(|p| p.amount);
+        let escrow = max(trusted_circle.escrow_amount, pending.unwrap_or_default());
+        let msg = circle.deposit_escrow(coins(escrow.u128(), trusted_circle.denom))?;
+        self.execute_circle(member, msg)
+    }
+
+    fn propose(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        proposer: &str,
+        proposal: ProposalContent,
+    ) -> AnyResult<u64> {
+        let msg = circle.propose("Proposal", "", proposal, false, false)?;
+        let res = self.execute_circle(proposer, msg)?;
+        let proposal_id = res
+            .events
+            .iter()
+            .flat_map(|evt| &evt.attributes)
+            .find(|attr| attr.key == "proposal_id")
+            .ok_or_else(|| anyhow!("No proposal_id in the propose response"))?;
+        Ok(proposal_id.value.parse()?)
+    }
+
+    fn pass_and_execute(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        proposal_id: u64,
+        voters: &[&str],
+    ) -> AnyResult<AppResponse> {
+        let executor = voters
+            .first()
+            .ok_or_else(|| anyhow!("No voter to execute the proposal"))?;
+        for voter in voters {
+            let prop = circle.proposal(&self.wrap(), proposal_id)?;
+            if prop.status != Status::Open {
+                break;
+            }
+            if circle
+                .vote_info(&self.wrap(), proposal_id, *voter)?
+                .is_none()
+            {
+                self.execute_circle(voter, circle.vote(proposal_id, Vote::Yes)?)?;
+            }
+        }
+        self.execute_circle(executor, circle.execute(proposal_id)?)
+    }
+
+    fn propose_and_execute(
+        &mut self,
+        circle: &TgTrustedCircleContract,
+        voters: &[&str],
+        proposal: ProposalContent,
+    ) -> AnyResult<u64> {
+        let proposer = voters
+            .first()
+            .ok_or_else(|| anyhow!("No voter to propose"))?;
+        let proposal_id = self.propose(circle, proposer, proposal)?;
+        self.pass_and_execute(circle, proposal_id, voters)?;
+        Ok(proposal_id)
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{Order, StdResult, Storage};
+use tg_voting_contract::ballots::ballots;
+
+use crate::error::ContractError;
+use crate::msg::PollResults;
+use crate::state::{PollMethod, ProposalContent, POLL_BALLOTS};
+
+pub const MAX_POLL_OPTIONS: usize = 32;
+
+pub fn validate_poll(options: &[String], max_choices: u32) -> Result<(), ContractError> {
+    if options.len() < 2 || options.len() > MAX_POLL_OPTIONS {
+        return Err(ContractError::InvalidPoll(format!(
+            "must have between 2 and {} options",
+            MAX_POLL_OPTIONS
+        )));
+    }
+    let mut unique = HashSet::new();
+    if !options
+        .iter()
+        .all(|option| !option.trim().is_empty() && unique.insert(option.trim()))
+    {
+        return Err(ContractError::InvalidPoll(
+            "options must be non-empty and unique".to_owned(),
+        ));
+    }
+    if max_choices == 0 || max_choices as usize > options.len() {
+        return Err(ContractError::InvalidPoll(
+            "max choices must be between 1 and the number of options".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that `choices` are indexes of distinct options, and there are at most `max_choices`
+pub fn validate_choices(
+    options: usize,
+    max_choices: u32,
+    choices: &[u32],
+) -> Result<(), ContractError> {
+    if choices.is_empty() || choices.len() > max_choices as usize {
+        return Err(ContractError::InvalidPollChoices(format!(
+            "between 1 and {} options must be chosen",
+            max_choices
+        )));
+    }
+    let mut unique = HashSet::new();
+    if !choices
+        .iter()
+        .all(|choice| (*choice as usize) < options && unique.insert(choice))
+    {
+        return Err(ContractError::InvalidPollChoices(
+            "choices must be distinct options".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Tallies the ballots of a poll proposal. Returns `None` for other proposals
+pub fn poll_results(
+    storage: &dyn Storage,
+    proposal_id: u64,
+    proposal: &ProposalContent,
+) -> StdResult<Option<PollResults>> {
+    let (options, method) = match proposal {
+        ProposalContent::Poll {
+            options, method, ..
+        } => (options.len(), *method),
+        _ => return Ok(None),
+    };
+
+    let poll_ballots = POLL_BALLOTS
+        .prefix(proposal_id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (voter, choices) = item?;
+            let points = ballots()
+                .ballots
+                .load(storage, (proposal_id, &voter))?
+                .points;
+            Ok((points, choices))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Some(tally(method, options, &poll_ballots)))
+}
+
+/// Tallies `ballots` of voting points and chosen options
+pub fn tally(method: PollMethod, options: usize, ballots: &[(u64, Vec<u32>)]) -> PollResults {
+    match method {
+        PollMethod::Plurality => {
+            let mut points = vec![0; options];
+            for (ballot_points, choices) in ballots {
+                for choice in choices {
+                    points[*choice as usize] += ballot_points;
+                }
+            }
+            let winner = leader(&points);
+            PollResults {
+                rounds: vec![points],
+                winner,
+            }
+        }
+        PollMethod::RankedChoice => instant_runoff(options, ballots),
+    }
+}
+
+/// Option with most points, unless it's tied or there are no points at all
+fn leader(points: &[u64]) -> Option<u32> {
+    let max = points.iter().copied().max().unwrap_or_default();
+    let mut leaders = points.iter().enumerate().filter(|(_, p)| **p == max);
+    match (leaders.next(), leaders.next()) {
+        (Some((option, _)), None) if max > 0 => Some(option as u32),
+        _ => None,
+    }
+}
+
+/// Every round, ballots count for their most preferred option not eliminated yet. The options
+/// with fewest points are eliminated, until one has the majority of the points counted
+fn instant_runoff(options: usize, ballots: &[(u64, Vec<u32>)]) -> PollResults {
+    let mut eliminated = vec![false; options];
+    let mut rounds = vec![];
+    loop {
+        let mut points = vec![0; options];
+        for (ballot_points, choices) in ballots {
+            if let Some(choice) = choices.iter().find(|c| !eliminated[**c as usize]) {
+                points[*choice as usize] += ballot_points;
+            }
+        }
+        let counted: u64 = points.iter().sum();
+        rounds.push(points.clone());
+
+        let remaining: Vec<_> = (0..options).filter(|o| !eliminated[*o]).collect();
+        if let Some(winner) = remaining.iter().find(|o| points[**o] * 2 > counted) {
+            return PollResults {
+                rounds,
+                winner: Some(*winner as u32),
+            };
+        }
+        let fewest = remaining
+            .iter()
+            .map(|o| points[*o])
+            .min()
+            .unwrap_or_default();
+        let losers: Vec<_> = remaining.iter().filter(|o| points[**o] == fewest).collect();
+        // No winner if no one voted, or all the remaining options are tied
+        if counted == 0 || losers.len() == remaining.len() {
+            return PollResults {
+                rounds,
+                winner: None,
+            };
+        }
+        for loser in losers {
+            eliminated[*loser] = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plurality() {
+        let ballots = vec![(1, vec![0, 2]), (1, vec![2]), (1, vec![1, 0])];
+        let results = tally(PollMethod::Plurality, 3, &ballots);
+        assert_eq!(results.rounds, vec![vec![2, 1, 2]]);
+        assert_eq!(results.winner, None);
+
+        let ballots = vec![(1, vec![0, 2]), (1, vec![2]), (1, vec![1])];
+        let results = tally(PollMethod::Plurality, 3, &ballots);
+        assert_eq!(results.winner, Some(2));
+
+        assert_eq!(tally(PollMethod::Plurality, 3, &[]).winner, None);
+    }
+
+    #[test]
+    fn ranked_choice() {
+        // First preferences: 0 -> 4, 1 -> 3, 2 -> 2. Option 2 is eliminated, and its ballots
+        // go to 1
+        let ballots = vec![
+            (4, vec![0]),
+            (3, vec![1, 0]),
+            (1, vec![2, 1]),
+            (1, vec![2, 1, 0]),
+        ];
+        let results = tally(PollMethod::RankedChoice, 3, &ballots);
+        assert_eq!(results.rounds, vec![vec![4, 3, 2], vec![4, 5, 0]]);
+        assert_eq!(results.winner, Some(1));
+
+        // Exhausted ballots are not counted anymore
+        let ballots = vec![(2, vec![0]), (2, vec![1]), (1, vec![2])];
+        let results = tally(PollMethod::RankedChoice, 3, &ballots);
+        assert_eq!(results.rounds, vec![vec![2, 2, 1], vec![2, 2, 0]]);
+        assert_eq!(results.winner, None);
+    }
+
+    #[test]
+    fn invalid_choices() {
+        validate_choices(3, 2, &[2, 0]).unwrap();
+        validate_choices(3, 2, &[]).unwrap_err();
+        validate_choices(3, 2, &[0, 1, 2]).unwrap_err();
+        validate_choices(3, 2, &[1, 1]).unwrap_err();
+        validate_choices(3, 2, &[3]).unwrap_err();
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_std::{
+    coin, to_binary, Addr, BankMsg, Coin, CosmosMsg, CustomQuery, Deps, DepsMut, Env, Order,
+    StdResult, Uint128, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use tg4_stake::msg::{
+    ClaimsResponse, ExecuteMsg as StakeExecuteMsg, QueryMsg as StakeQueryMsg,
+    UnbondingPeriodResponse,
+};
+use tg_bindings::TgradeMsg;
+
+use crate::error::ContractError;
+use crate::state::{
+    escrow_total, TrustedCircle, ESCROW_BURNS, ESCROW_CLAIMS, ESCROW_REWARDS, TRUSTED_CIRCLE,
+};
+
+/// Claims of the trusted circle queried from the stake contract at once
+const CLAIMS_PAGE: u32 = 100;
+
+fn stake_msg(
+    stake: &Addr,
+    msg: &StakeExecuteMsg,
+    funds: Vec<Coin>,
+) -> StdResult<CosmosMsg<TgradeMsg>> {
+    Ok(WasmMsg::Execute {
+        contract_addr: stake.to_string(),
+        msg: to_binary(msg)?,
+        funds,
+    }
+    .into())
+}
+
+fn unbond_msg(stake: &Addr, denom: &str, amount: Uint128) -> StdResult<CosmosMsg<TgradeMsg>> {
+    let msg = StakeExecuteMsg::Unbond {
+        tokens: coin(amount.u128(), denom),
+    };
+    stake_msg(stake, &msg, vec![])
+}
+
+/// Release time (in nanoseconds) of escrow unbonded now
+fn release_at<Q: CustomQuery>(deps: Deps<Q>, env: &Env, stake: &Addr) -> StdResult<u64> {
+    let period: UnbondingPeriodResponse = deps
+        .querier
+        .query_wasm_smart(stake, &StakeQueryMsg::UnbondingPeriod {})?;
+    Ok(period.unbonding_period.after(&env.block).as_key())
+}
+
+/// Release times (in nanoseconds) of the claims of the trusted circle which are still held by
+/// the stake contract
+fn unbonding_claims<Q: CustomQuery>(
+    deps: Deps<Q>,
+    stake: &Addr,
+    contract: &Addr,
+) -> StdResult<BTreeSet<u64>> {
+    let mut unbonding = BTreeSet::new();
+    let mut start_after = None;
+    loop {
+        let res: ClaimsResponse = deps.querier.query_wasm_smart(
+            stake,
+            &StakeQueryMsg::Claims {
+                address: contract.to_string(),
+                limit: Some(CLAIMS_PAGE),
+                start_after,
+            },
+        )?;
+        // Claims slashed down to zero cannot be claimed anymore
+        unbonding.extend(
+            res.claims
+                .iter()
+                .filter(|claim| !claim.amount.is_zero())
+                .map(|claim| claim.release_at.as_key()),
+        );
+        match res.claims.last() {
+            Some(last) if res.claims.len() == CLAIMS_PAGE as usize => {
+                start_after = Some(last.release_at)
+            }
+            _ => return Ok(unbonding),
+        }
+    }
+}
+
+/// Claims released after `now` are always unbonding. Released ones are until the stake contract
+/// sends them back to the trusted circle
+fn is_unbonding(release_at: u64, unbonding: &BTreeSet<u64>, env: &Env) -> bool {
+    release_at > env.block.time.nanos() || unbonding.contains(&release_at)
+}
+
+/// Bonds escrow paid into the trusted circle, if it bonds its escrow
+pub fn bond_escrow(
+    trusted_circle: &TrustedCircle,
+    amount: Uint128,
+) -> StdResult<Option<CosmosMsg<TgradeMsg>>> {
+    match &trusted_circle.stake_contract {
+        Some(stake) if !amount.is_zero() => {
+            let msg = StakeExecuteMsg::Bond {
+                vesting_tokens: None,
+            };
+            let funds = vec![coin(amount.u128(), &trusted_circle.denom)];
+            stake_msg(stake, &msg, funds).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Sends escrow leaving the trusted circle to `recipient`. Bonded escrow is unbonded instead, and
+/// `recipient` can claim it once the unbonding period is over
+pub fn release_escrow<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    trusted_circle: &TrustedCircle,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg<TgradeMsg>> {
+    let stake = match &trusted_circle.stake_contract {
+        Some(stake) => stake,
+        None => {
+            return Ok(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(amount.u128(), &trusted_circle.denom)],
+            }
+            .into())
+        }
+    };
+
+    let release_at = release_at(deps.as_ref(), env, stake)?;
+    ESCROW_CLAIMS.update(
+        deps.storage,
+        (recipient, release_at),
+        |claim| -> StdResult<_> { Ok(claim.unwrap_or_default() + amount) },
+    )?;
+    unbond_msg(stake, &trusted_circle.denom, amount)
+}
+
+/// Unbonds escrow of `punished` which is turned into rewards. Once it is back, it is distributed
+/// by the next `DistributeRewards`, still without a share for `punished`. Returns None if the
+/// escrow is not bonded, so it can be distributed right away
+pub fn unbond_escrow<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    trusted_circle: &TrustedCircle,
+    punished: &Addr,
+    amount: Uint128,
+) -> StdResult<Option<CosmosMsg<TgradeMsg>>> {
+    let stake = match &trusted_circle.stake_contract {
+        Some(stake) => stake,
+        None => return Ok(None),
+    };
+
+    let release_at = release_at(deps.as_ref(), env, stake)?;
+    ESCROW_REWARDS.update(
+        deps.storage,
+        (release_at, punished),
+        |reward| -> StdResult<_> { Ok(reward.unwrap_or_default() + amount) },
+    )?;
+    unbond_msg(stake, &trusted_circle.denom, amount).map(Some)
+}
+
+/// Burns slashed escrow. Bonded escrow is unbonded instead, and burnt by the next
+/// `DistributeRewards` once it is back. The stake contract is never slashed, so the escrow still
+/// unbonding for other addresses is left untouched
+pub fn burn_escrow<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    trusted_circle: &TrustedCircle,
+    amount: Uint128,
+) -> StdResult<CosmosMsg<TgradeMsg>> {
+    let stake = match &trusted_circle.stake_contract {
+        Some(stake) => stake,
+        None => {
+            return Ok(BankMsg::Burn {
+                amount: vec![coin(amount.u128(), &trusted_circle.denom)],
+            }
+            .into())
+        }
+    };
+
+    let release_at = release_at(deps.as_ref(), env, stake)?;
+    ESCROW_BURNS.update(deps.storage, release_at, |burn| -> StdResult<_> {
+        Ok(burn.unwrap_or_default() + amount)
+    })?;
+    unbond_msg(stake, &trusted_circle.denom, amount)
+}
+
+/// Slashed escrow which is back from the stake contract, taken out of the pending burns and
+/// rewards by `take_unbonded_slash`
+#[derive(Default)]
+pub struct UnbondedSlash {
+    /// Burn message for the escrow to be burnt, if any
+    pub burn: Option<CosmosMsg<TgradeMsg>>,
+    /// Amount burnt by `burn`
+    pub burnt: Uint128,
+    /// Escrow to be distributed as rewards, by punished member
+    pub rewards: Vec<(Addr, Uint128)>,
+}
+
+/// Takes the slashed escrow which finished unbonding and was claimed back from the stake
+/// contract, to be burnt or distributed as rewards
+pub fn take_unbonded_slash<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    trusted_circle: &TrustedCircle,
+) -> StdResult<UnbondedSlash> {
+    let stake = match &trusted_circle.stake_contract {
+        Some(stake) => stake,
+        None => return Ok(UnbondedSlash::default()),
+    };
+
+    let unbonding = unbonding_claims(deps.as_ref(), stake, &env.contract.address)?;
+    let released = |release_at: &u64| !is_unbonding(*release_at, &unbonding, env);
+    let burns = ESCROW_BURNS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(release_at, _)| released(release_at))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let rewards = ESCROW_REWARDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |((release_at, _), _)| released(release_at))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut burnt = Uint128::zero();
+    for (release_at, amount) in burns {
+        ESCROW_BURNS.remove(deps.storage, release_at);
+        burnt += amount;
+    }
+    let burn = (!burnt.is_zero()).then(|| {
+        BankMsg::Burn {
+            amount: vec![coin(burnt.u128(), &trusted_circle.denom)],
+        }
+        .into()
+    });
+    let rewards = rewards
+        .into_iter()
+        .map(|((release_at, punished), amount)| {
+            ESCROW_REWARDS.remove(deps.storage, (release_at, &punished));
+            (punished, amount)
+        })
+        .collect();
+
+    Ok(UnbondedSlash {
+        burn,
+        burnt,
+        rewards,
+    })
+}
+
+/// Escrow of `denom` held by the contract itself. Bonded escrow is held by the stake contract,
+/// but returned escrow is held by the contract from the moment it is unbonded until claimed, and
+/// slashed escrow until it is burnt or distributed
+pub fn held_escrow<Q: CustomQuery>(deps: Deps<Q>, env: &Env, denom: &str) -> StdResult<Uint128> {
+    let trusted_circle = TRUSTED_CIRCLE.load(deps.storage)?;
+    let stake = match trusted_circle.stake_contract {
+        Some(stake) if trusted_circle.denom == denom => stake,
+        _ => return escrow_total(deps.storage, denom),
+    };
+
+    let unbonding = unbonding_claims(deps, &stake, &env.contract.address)?;
+    let released = |release_at: &u64| !is_unbonding(*release_at, &unbonding, env);
+    let claims: Uint128 = ESCROW_CLAIMS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |((_, release_at), _)| released(release_at))
+        })
+        .map(|item| item.map(|(_, claim)| claim))
+        .sum::<StdResult<_>>()?;
+    let burns: Uint128 = ESCROW_BURNS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(release_at, _)| released(release_at))
+        })
+        .map(|item| item.map(|(_, burn)| burn))
+        .sum::<StdResult<_>>()?;
+    let rewards: Uint128 = ESCROW_REWARDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |((release_at, _), _)| released(release_at))
+        })
+        .map(|item| item.map(|(_, reward)| reward))
+        .sum::<StdResult<_>>()?;
+    Ok(claims + burns + rewards)
+}
+
+/// Claims the escrow of the trusted circle which finished unbonding, if the stake contract still
+/// holds any
+pub fn claim_unbonded<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
+    trusted_circle: &TrustedCircle,
+) -> StdResult<Option<CosmosMsg<TgradeMsg>>> {
+    let stake = match &trusted_circle.stake_contract {
+        Some(stake) => stake,
+        None => return Ok(None),
+    };
+
+    let unbonding = unbonding_claims(deps, stake, &env.contract.address)?;
+    match unbonding.iter().next() {
+        Some(first) if *first <= env.block.time.nanos() => {
+            stake_msg(stake, &StakeExecuteMsg::Claim {}, vec![]).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Settles the unbonded escrow claims of `recipient`, claiming them from the stake contract
+/// first if it still holds them. Returns the claimed amount, and the messages sending it
+pub fn claim_escrow<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    trusted_circle: &TrustedCircle,
+    recipient: &Addr,
+) -> Result<(Uint128, Vec<CosmosMsg<TgradeMsg>>), ContractError> {
+    let end = Bound::inclusive(env.block.time.nanos());
+    let released = ESCROW_CLAIMS
+        .prefix(recipient)
+        .range(deps.storage, None, Some(end), Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if released.is_empty() {
+        return Err(ContractError::NoEscrowClaims {});
+    }
+
+    let mut msgs: Vec<_> = claim_unbonded(deps.as_ref(), env, trusted_circle)?
+        .into_iter()
+        .collect();
+    let mut amount = Uint128::zero();
+    for (release_at, claim) in released {
+        ESCROW_CLAIMS.remove(deps.storage, (recipient, release_at));
+        amount += claim;
+    }
+    if !amount.is_zero() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(amount.u128(), &trusted_circle.denom)],
+            }
+            .into(),
+        );
+    }
+    Ok((amount, msgs))
+}
@@ -5,16 +5,21 @@ use std::fmt;
 
 use crate::distribution::Distribution;
 use crate::error::ContractError;
+use crate::stake::held_escrow;
 use crate::state::MemberStatus::NonVoting;
 use cosmwasm_std::{
-    attr, Addr, Attribute, BlockInfo, CustomQuery, Decimal, Deps, Env, Event, StdError, StdResult,
-    Storage, Timestamp, Uint128,
+    attr, Addr, Attribute, Binary, BlockInfo, Coin, CustomQuery, Decimal, Deps, Empty, Env, Event,
+    StdError, StdResult, Storage, Timestamp, Uint128,
+};
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, Strategy,
 };
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use cw_utils::Expiration;
 use tg3::{Status, Vote};
+use tg4::TOTAL_KEY;
 
 const ONE_TGD: u128 = 1_000_000; // One million µTGD
+const DEFAULT_DRAFT_PERIOD: u32 = 30;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct TrustedCircle {
@@ -27,6 +32,31 @@ pub struct TrustedCircle {
     pub deny_list: Option<Addr>,
     /// If true, no further adjustments may happen.
     pub edit_trusted_circle_disabled: bool,
+    /// Days after a batch's grace period ends for its pending members to pay in their escrow.
+    /// If not set, unpaid pending members are kept forever.
+    #[serde(default)]
+    pub payment_deadline: Option<u32>,
+    /// If true, pending members who miss the payment deadline are removed.
+    /// Otherwise they are reverted to non-voting members.
+    #[serde(default)]
+    pub remove_expired_pending: bool,
+    /// Rules of urgent proposals. If not set, urgent proposals are not allowed
+    #[serde(default)]
+    pub urgent_rules: Option<UrgentRules>,
+    /// Rules of secret proposals. If not set, secret proposals are not allowed
+    #[serde(default)]
+    pub secret_rules: Option<SecretRules>,
+    /// Address allowed to pause the trusted circle
+    #[serde(default)]
+    pub guardian: Option<Addr>,
+    /// Days drafts can be amended and cosponsored before expiring. If not set, the default
+    /// period is used
+    #[serde(default)]
+    pub draft_period: Option<u32>,
+    /// tg4-stake contract the escrow is bonded in. If not set, the escrow is kept in the
+    /// contract balance
+    #[serde(default)]
+    pub stake_contract: Option<Addr>,
 }
 
 /// Pending escrow
@@ -77,6 +107,101 @@ impl VotingRules {
     }
 }
 
+/// Rules of urgent proposals, which are decided faster, but by more members than the regular ones
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, JsonSchema)]
+pub struct UrgentRules {
+    /// Length of voting period in hours. Shorter than the regular voting period
+    pub voting_period: u32,
+    /// quorum requirement, at least the regular one
+    pub quorum: Decimal,
+    /// threshold requirement, at least the regular one
+    pub threshold: Decimal,
+    /// How many urgent proposals a single member can create within `limit_period`
+    pub max_proposals: u32,
+    /// Length of the period in days over which urgent proposals are limited
+    pub limit_period: u32,
+}
+
+impl UrgentRules {
+    pub fn validate(&self, rules: &VotingRules) -> Result<(), ContractError> {
+        let hundred = Decimal::percent(100);
+
+        if self.voting_period == 0 || self.voting_period as u64 * 3600 >= rules.voting_period_secs()
+        {
+            return Err(ContractError::InvalidUrgentRules(
+                "voting period must be shorter than the regular one".to_owned(),
+            ));
+        }
+        if self.quorum < rules.quorum || self.quorum > hundred {
+            return Err(ContractError::InvalidUrgentRules(
+                "quorum must be at least the regular one".to_owned(),
+            ));
+        }
+        if self.threshold < rules.threshold || self.threshold > hundred {
+            return Err(ContractError::InvalidUrgentRules(
+                "threshold must be at least the regular one".to_owned(),
+            ));
+        }
+        if self.max_proposals == 0 || self.limit_period == 0 || self.limit_period > 365 {
+            return Err(ContractError::InvalidUrgentRules(
+                "limit must allow at least one proposal per at most a year".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn voting_period_secs(&self) -> u64 {
+        self.voting_period as u64 * 3600
+    }
+
+    pub fn limit_period_secs(&self) -> u64 {
+        self.limit_period as u64 * 86_400
+    }
+
+    /// Pass requirements of urgent proposals. They always end as soon as they are decided. Their
+    /// voting period is in hours, so it is kept in `Proposal::urgent_period` instead
+    pub fn voting_rules(&self) -> VotingRules {
+        VotingRules {
+            voting_period: 0,
+            quorum: self.quorum,
+            threshold: self.threshold,
+            allow_end_early: true,
+        }
+    }
+}
+
+/// Rules of secret proposals, whose votes are committed as hashes during the voting period, and
+/// revealed afterwards
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, JsonSchema)]
+pub struct SecretRules {
+    /// Length of the reveal window in hours, following the voting period
+    pub reveal_period: u32,
+    /// How votes committed but never revealed are counted
+    pub unrevealed: UnrevealedVotes,
+}
+
+impl SecretRules {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.reveal_period == 0 || self.reveal_period > 24 * 365 {
+            return Err(ContractError::InvalidRevealPeriod(self.reveal_period));
+        }
+        Ok(())
+    }
+
+    pub fn reveal_period_secs(&self) -> u64 {
+        self.reveal_period as u64 * 3600
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnrevealedVotes {
+    /// Counted as abstain, so only toward the quorum
+    Abstain,
+    /// Not counted at all
+    Ignored,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, JsonSchema)]
 pub struct TrustedCircleAdjustments {
     /// Escrow name
@@ -93,6 +218,18 @@ pub struct TrustedCircleAdjustments {
     pub allow_end_early: Option<bool>,
     /// If true, no further adjustments may happen.
     pub edit_trusted_circle_disabled: Option<bool>,
+    /// Rules of urgent proposals
+    #[serde(default)]
+    pub urgent_rules: Option<UrgentRules>,
+    /// Rules of secret proposals
+    #[serde(default)]
+    pub secret_rules: Option<SecretRules>,
+    /// Address allowed to pause the trusted circle
+    #[serde(default)]
+    pub guardian: Option<String>,
+    /// Days drafts can be amended and cosponsored before expiring
+    #[serde(default)]
+    pub draft_period: Option<u32>,
 }
 
 impl TrustedCircle {
@@ -114,6 +251,22 @@ impl TrustedCircle {
                 return Err(ContractError::InvalidPendingEscrow(pending_escrow.amount));
             }
         }
+        if let Some(payment_deadline) = self.payment_deadline {
+            if payment_deadline == 0 || payment_deadline > 365 {
+                return Err(ContractError::InvalidPaymentDeadline(payment_deadline));
+            }
+        }
+        if let Some(urgent_rules) = &self.urgent_rules {
+            urgent_rules.validate(&self.rules)?;
+        }
+        if let Some(secret_rules) = &self.secret_rules {
+            secret_rules.validate()?;
+        }
+        if let Some(draft_period) = self.draft_period {
+            if draft_period == 0 || draft_period > 365 {
+                return Err(ContractError::InvalidDraftPeriod(draft_period));
+            }
+        }
         Ok(())
     }
 
@@ -156,9 +309,35 @@ impl TrustedCircle {
         if let Some(edit_trusted_circle_disabled) = adjustments.edit_trusted_circle_disabled {
             self.edit_trusted_circle_disabled = edit_trusted_circle_disabled;
         }
+        if let Some(urgent_rules) = adjustments.urgent_rules {
+            self.urgent_rules = Some(urgent_rules);
+        }
+        if let Some(secret_rules) = adjustments.secret_rules {
+            self.secret_rules = Some(secret_rules);
+        }
+        // Validated with the proposal
+        if let Some(guardian) = adjustments.guardian {
+            self.guardian = Some(Addr::unchecked(guardian));
+        }
+        if let Some(draft_period) = adjustments.draft_period {
+            self.draft_period = Some(draft_period);
+        }
         Ok(())
     }
 
+    pub fn payment_deadline_secs(&self) -> Option<u64> {
+        self.payment_deadline.map(|days| days as u64 * 86_400)
+    }
+
+    /// Draft period in days
+    pub fn draft_period(&self) -> u32 {
+        self.draft_period.unwrap_or(DEFAULT_DRAFT_PERIOD)
+    }
+
+    pub fn draft_period_secs(&self) -> u64 {
+        self.draft_period() as u64 * 86_400
+    }
+
     /// Gets the max of the pending escrow (if any) and the current escrow amount
     pub fn get_escrow(&self) -> Uint128 {
         max(
@@ -192,6 +371,26 @@ impl TrustedCircleAdjustments {
         if let Some(allow_end_early) = self.allow_end_early {
             res.push(attr("allow_end_early", allow_end_early.to_string()));
         }
+        if let Some(urgent_rules) = &self.urgent_rules {
+            res.push(attr(
+                "urgent_voting_period",
+                urgent_rules.voting_period.to_string(),
+            ));
+            res.push(attr("urgent_quorum", urgent_rules.quorum.to_string()));
+            res.push(attr("urgent_threshold", urgent_rules.threshold.to_string()));
+        }
+        if let Some(secret_rules) = &self.secret_rules {
+            res.push(attr(
+                "secret_reveal_period",
+                secret_rules.reveal_period.to_string(),
+            ));
+        }
+        if let Some(guardian) = &self.guardian {
+            res.push(attr("guardian", guardian));
+        }
+        if let Some(draft_period) = self.draft_period {
+            res.push(attr("draft_period", draft_period.to_string()));
+        }
         res
     }
 }
@@ -223,6 +422,18 @@ pub enum Punishment {
         /// If set to true, slashed member is effectively demoted to `Leaving`
         kick_out: bool,
     },
+    /// The slashed escrow is distributed as rewards to the other voting members.
+    /// Only possible if the escrow and the rewards are in the same denom
+    RewardEscrow {
+        /// Member to slash / expel
+        member: String,
+        /// Slashing percentage
+        slashing_percentage: Decimal,
+        /// If set to false, slashed member is demoted to `Pending`. Or not demoted at all,
+        /// depending on the amount of funds he retains in escrow.
+        /// If set to true, slashed member is effectively demoted to `Leaving`
+        kick_out: bool,
+    },
 }
 
 const PUNISHMENT_TYPE: &str = "punishment";
@@ -239,7 +450,7 @@ impl Punishment {
                 kick_out,
             } => {
                 evt = evt.add_attribute("member", member);
-                evt = evt.add_attribute("slashing_percentage", &slashing_percentage.to_string());
+                evt = evt.add_attribute("slashing_percentage", slashing_percentage.to_string());
                 evt = evt.add_attribute("slashed_escrow", "distribute");
                 evt = evt.add_attribute("distribution_list", distribution_list.join(", "));
                 evt = evt.add_attribute("kick_out", kick_out.to_string());
@@ -250,10 +461,20 @@ impl Punishment {
                 kick_out,
             } => {
                 evt = evt.add_attribute("member", member);
-                evt = evt.add_attribute("slashing_percentage", &slashing_percentage.to_string());
+                evt = evt.add_attribute("slashing_percentage", slashing_percentage.to_string());
                 evt = evt.add_attribute("slashed_escrow", "burn");
                 evt = evt.add_attribute("kick_out", kick_out.to_string());
             }
+            Punishment::RewardEscrow {
+                member,
+                slashing_percentage,
+                kick_out,
+            } => {
+                evt = evt.add_attribute("member", member);
+                evt = evt.add_attribute("slashing_percentage", slashing_percentage.to_string());
+                evt = evt.add_attribute("slashed_escrow", "reward");
+                evt = evt.add_attribute("kick_out", kick_out.to_string());
+            }
         };
         evt
     }
@@ -297,10 +518,27 @@ impl Punishment {
                 member,
                 slashing_percentage,
                 ..
+            }
+            | Punishment::RewardEscrow {
+                member,
+                slashing_percentage,
+                ..
             } => {
                 // Validate member address
                 let addr = deps.api.addr_validate(member)?;
 
+                // Validate the slashed escrow can be distributed as rewards
+                if let Punishment::RewardEscrow { .. } = self {
+                    let denom = TRUSTED_CIRCLE.load(deps.storage)?.denom;
+                    let reward_denom = DISTRIBUTION.denom(deps.storage)?;
+                    if denom != reward_denom {
+                        return Err(ContractError::EscrowNotRewardable {
+                            denom,
+                            reward_denom,
+                        });
+                    }
+                }
+
                 // Validate slashing percentage
                 if !(Decimal::zero()..=Decimal::one()).contains(slashing_percentage) {
                     return Err(ContractError::InvalidSlashingPercentage(
@@ -394,6 +632,262 @@ impl fmt::Display for MemberStatus {
 
 pub const ESCROWS: Map<&Addr, EscrowStatus> = Map::new("escrows");
 
+/// What changed the escrow or the status of a member
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum EscrowAction {
+    /// Proposed as voting member by the given proposal, now pending
+    AddVoting { proposal_id: u64 },
+    /// Paid in escrow
+    Deposit { amount: Uint128 },
+    /// Withdrew escrow (excess escrow, or all of it after leaving)
+    Return { amount: Uint128 },
+    /// Escrow slashed by the given punishment proposal
+    Slash { proposal_id: u64, amount: Uint128 },
+    /// Became a voting member
+    Promote {},
+    /// Lost voting rights until they pay in the required escrow, because of the given proposal
+    /// (a punishment, or an escrow increase)
+    Demote { proposal_id: u64 },
+    /// Paid in enough escrow, after the escrow was lowered by the given proposal
+    EscrowLowered { proposal_id: u64 },
+    /// Started leaving (or left right away, if they had no escrow)
+    Leave {},
+    /// Missed the payment deadline. Any partial escrow was refunded
+    Expire { refund: Uint128 },
+}
+
+/// An entry of the escrow ledger of a member, with the escrow and status after the change
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EscrowLedgerEntry {
+    pub height: u64,
+    pub time: Timestamp,
+    pub action: EscrowAction,
+    pub paid: Uint128,
+    /// None if the member was removed
+    pub status: Option<MemberStatus>,
+}
+
+/// Total escrow held by the contract, per denom. Those funds are never distributed as rewards
+pub const ESCROW_TOTALS: Map<&str, Uint128> = Map::new("escrow_totals");
+
+/// Total escrow held by the contract in `denom`
+pub fn escrow_total(storage: &dyn Storage, denom: &str) -> StdResult<Uint128> {
+    Ok(ESCROW_TOTALS.may_load(storage, denom)?.unwrap_or_default())
+}
+
+/// Accounts for `amount` of `denom` paid into escrow
+pub fn escrow_deposited(storage: &mut dyn Storage, denom: &str, amount: Uint128) -> StdResult<()> {
+    let total = escrow_total(storage, denom)? + amount;
+    ESCROW_TOTALS.save(storage, denom, &total)
+}
+
+/// Accounts for `amount` of `denom` leaving escrow (refunded, slashed or turned into rewards)
+pub fn escrow_released(storage: &mut dyn Storage, denom: &str, amount: Uint128) -> StdResult<()> {
+    let total = escrow_total(storage, denom)?.checked_sub(amount)?;
+    ESCROW_TOTALS.save(storage, denom, &total)
+}
+
+/// Treasury funds of a single denom. Committed funds are granted to spends, but not claimed yet
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
+pub struct TreasuryBalance {
+    pub available: Uint128,
+    pub committed: Uint128,
+}
+
+/// Treasury balances, per denom. Those funds are never distributed as rewards
+pub const TREASURY: Map<&str, TreasuryBalance> = Map::new("treasury");
+
+/// Treasury balance of `denom`
+pub fn treasury_balance(storage: &dyn Storage, denom: &str) -> StdResult<TreasuryBalance> {
+    Ok(TREASURY.may_load(storage, denom)?.unwrap_or_default())
+}
+
+/// Funds of `denom` held by the contract which are not rewards: escrow and treasury. Escrow
+/// bonded in the stake contract is not held by the contract, see `stake::held_escrow`
+pub fn reserved_funds<Q: CustomQuery>(deps: Deps<Q>, env: &Env, denom: &str) -> StdResult<Uint128> {
+    let treasury = treasury_balance(deps.storage, denom)?;
+    Ok(held_escrow(deps, env, denom)? + treasury.available + treasury.committed)
+}
+
+/// Escrow which left the trusted circle while bonded in the stake contract, by recipient and
+/// release time (in nanoseconds). It is claimable with `ClaimEscrow` once unbonded
+pub const ESCROW_CLAIMS: Map<(&Addr, u64), Uint128> = Map::new("escrow_claims");
+
+/// Slashed escrow unbonded from the stake contract to be burnt once it is back, by release time
+/// (in nanoseconds)
+pub const ESCROW_BURNS: Map<u64, Uint128> = Map::new("escrow_burns");
+
+/// Slashed escrow unbonded from the stake contract to be distributed as rewards once it is back,
+/// by release time (in nanoseconds) and punished member, who doesn't get a share of it
+pub const ESCROW_REWARDS: Map<(u64, &Addr), Uint128> = Map::new("escrow_rewards");
+
+/// Treasury funds granted by a `TreasurySpend` proposal, vesting linearly from `start` to `end`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Spend {
+    pub recipient: Addr,
+    pub amount: Coin,
+    pub claimed: Uint128,
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+impl Spend {
+    /// Part of the spend vested at `now`
+    pub fn vested(&self, now: Timestamp) -> Uint128 {
+        if now >= self.end {
+            self.amount.amount
+        } else if now <= self.start {
+            Uint128::zero()
+        } else {
+            let elapsed = now.seconds() - self.start.seconds();
+            let period = self.end.seconds() - self.start.seconds();
+            self.amount.amount.multiply_ratio(elapsed, period)
+        }
+    }
+
+    /// Part of the spend which can be claimed at `now`
+    pub fn claimable(&self, now: Timestamp) -> Uint128 {
+        self.vested(now) - self.claimed
+    }
+}
+
+pub struct SpendIndexes<'a> {
+    pub recipient: MultiIndex<'a, Addr, Spend, u64>,
+}
+
+impl<'a> IndexList<Spend> for SpendIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Spend>> + '_> {
+        let v: Vec<&dyn Index<Spend>> = vec![&self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Treasury spends, by id of the proposal which granted them
+pub fn spends<'a>() -> IndexedMap<'a, u64, Spend, SpendIndexes<'a>> {
+    let indexes = SpendIndexes {
+        recipient: MultiIndex::new(
+            |_, spend: &Spend| spend.recipient.clone(),
+            "spends",
+            "spends__recipient",
+        ),
+    };
+    IndexedMap::new("spends", indexes)
+}
+
+/// Permissions of whitelisted contracts. Every contract whitelisted by proposal has an entry,
+/// without any permission until granted
+pub const CONTRACT_PERMISSIONS: Map<&Addr, ContractPermissions> = Map::new("contract_permissions");
+
+/// Working group of voting members, created by a `CreateCommittee` proposal
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Committee {
+    /// Only they propose and vote on the proposals of the committee
+    pub members: Vec<Addr>,
+    /// Kinds of proposals the committee decides
+    pub powers: Vec<ProposalKind>,
+    /// Voting rules of the proposals of the committee
+    pub rules: VotingRules,
+}
+
+impl Committee {
+    pub fn is_member(&self, addr: &Addr) -> bool {
+        self.members.contains(addr)
+    }
+
+    pub fn can_propose(&self, proposal: &ProposalContent) -> bool {
+        self.powers.contains(&proposal.kind())
+    }
+}
+
+/// Committees by name
+pub const COMMITTEES: Map<&str, Committee> = Map::new("committees");
+
+/// Proposal being drafted, before voting starts
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Draft {
+    pub author: Addr,
+    pub title: String,
+    pub description: String,
+    pub proposal: ProposalContent,
+    pub urgent: bool,
+    pub secret: bool,
+    pub cosponsors: Vec<Addr>,
+    pub expires: Timestamp,
+}
+
+pub struct DraftIndexes<'a> {
+    pub author: MultiIndex<'a, Addr, Draft, u64>,
+}
+
+impl<'a> IndexList<Draft> for DraftIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Draft>> + '_> {
+        let v: Vec<&dyn Index<Draft>> = vec![&self.author];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Unpublished drafts, by id. Published drafts are removed
+pub fn drafts<'a>() -> IndexedMap<'a, u64, Draft, DraftIndexes<'a>> {
+    let indexes = DraftIndexes {
+        author: MultiIndex::new(
+            |_, draft: &Draft| draft.author.clone(),
+            "drafts",
+            "drafts__author",
+        ),
+    };
+    IndexedMap::new("drafts", indexes)
+}
+
+pub const DRAFT_COUNT: Item<u64> = Item::new("draft_count");
+
+/// Append-only history of escrow and status changes, per member.
+/// Keys are (member, entry id), ids are increasing over all members
+pub const ESCROW_LEDGER: Map<(&Addr, u64), EscrowLedgerEntry> = Map::new("escrow_ledger");
+
+/// Membership NFT contract instantiated by the trusted circle, if any. It mints a token for
+/// every voting member
+pub const MEMBERSHIP_NFT: Item<Addr> = Item::new("membership_nft");
+
+/// Members whose status changed since their membership token was last updated
+pub const MEMBERSHIP_CHANGES: Map<&Addr, Empty> = Map::new("membership_changes");
+
+/// Status in the metadata of the membership token of every member holding one
+pub const MEMBERSHIP_TOKENS: Map<&Addr, String> = Map::new("membership_tokens");
+
+/// Marks the membership token of `addr` for an update, if the trusted circle has a membership
+/// NFT. Tokens are updated at the end of the message, see `membership_token_msgs`
+pub fn track_membership(storage: &mut dyn Storage, addr: &Addr) -> StdResult<()> {
+    if MEMBERSHIP_NFT.may_load(storage)?.is_some() {
+        MEMBERSHIP_CHANGES.save(storage, addr, &Empty {})?;
+    }
+    Ok(())
+}
+
+pub const ESCROW_LEDGER_COUNT: Item<u64> = Item::new("escrow_ledger_count");
+
+/// Records a change of `addr`'s escrow in its ledger. `escrow` is the state after the change, or
+/// None if the member was removed
+pub fn record_escrow(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    addr: &Addr,
+    action: EscrowAction,
+    escrow: Option<&EscrowStatus>,
+) -> StdResult<()> {
+    let id = ESCROW_LEDGER_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    ESCROW_LEDGER_COUNT.save(storage, &id)?;
+    let entry = EscrowLedgerEntry {
+        height: block.height,
+        time: block.time,
+        action,
+        paid: escrow.map(|e| e.paid).unwrap_or_default(),
+        status: escrow.map(|e| e.status),
+    };
+    ESCROW_LEDGER.save(storage, (addr, id), &entry)?;
+    track_membership(storage, addr)
+}
+
 /// A Batch is a group of members who got voted in together. We need this to
 /// calculate moving from *Paid, Pending Voter* to *Voter*
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -407,6 +901,15 @@ pub struct Batch {
     pub batch_promoted: bool,
     /// List of all members that are part of this batch (look up ESCROWS with these keys)
     pub members: Vec<Addr>,
+    /// Index of the next member to check, when promoting (or, once promoted, expiring) the batch
+    /// takes more than one call
+    #[serde(default)]
+    pub next_member: u32,
+    /// Timestamp (seconds) when an early promotion (everybody paid) started without finishing in
+    /// one call. The remaining members are then promoted from this time on, instead of the grace
+    /// end (which also sets the payment deadline, so it is never changed)
+    #[serde(default)]
+    pub promoting_since: Option<u64>,
 }
 
 impl Batch {
@@ -416,6 +919,21 @@ impl Batch {
     }
 }
 
+/// Returns the timestamp (seconds) at which a pending member of the given batch stops being able
+/// to pay in escrow, or None if no payment deadline is configured
+pub fn payment_deadline(
+    storage: &dyn Storage,
+    trusted_circle: &TrustedCircle,
+    batch_id: u64,
+) -> StdResult<Option<u64>> {
+    let deadline = match trusted_circle.payment_deadline_secs() {
+        Some(deadline) => deadline,
+        None => return Ok(None),
+    };
+    let batch = batches().may_load(storage, batch_id)?;
+    Ok(batch.map(|b| b.grace_ends_at + deadline))
+}
+
 pub(crate) fn create_batch(
     storage: &mut dyn Storage,
     env: &Env,
@@ -429,6 +947,8 @@ pub(crate) fn create_batch(
             waiting_escrow: addrs.len() as u32,
             batch_promoted: false,
             members: addrs.into(),
+            next_member: 0,
+            promoting_since: None,
         };
         batches().update(storage, proposal_id, |old| match old {
             Some(_) => Err(ContractError::AlreadyUsedProposal(proposal_id)),
@@ -438,9 +958,82 @@ pub(crate) fn create_batch(
     Ok(())
 }
 
+/// Like `create_batch`, but appends the members to the batch if it already exists.
+/// Used when a batch is filled over several calls
+pub(crate) fn add_to_batch(
+    storage: &mut dyn Storage,
+    env: &Env,
+    proposal_id: u64,
+    grace_period: u64,
+    addrs: &[Addr],
+) -> StdResult<()> {
+    if !addrs.is_empty() {
+        let batch = match batches().may_load(storage, proposal_id)? {
+            Some(mut batch) => {
+                batch.waiting_escrow += addrs.len() as u32;
+                batch.members.extend_from_slice(addrs);
+                // The payment deadline of the batch may already have been checked
+                if batch.batch_promoted {
+                    rewind_expired_batches_cursor(storage, batch.grace_ends_at, proposal_id)?;
+                }
+                batch
+            }
+            None => Batch {
+                grace_ends_at: env.block.time.plus_seconds(grace_period).seconds(),
+                waiting_escrow: addrs.len() as u32,
+                batch_promoted: false,
+                members: addrs.into(),
+                next_member: 0,
+                promoting_since: None,
+            },
+        };
+        batches().save(storage, proposal_id, &batch)?;
+    }
+    Ok(())
+}
+
+/// A new escrow amount being enforced on the existing members.
+/// Members are checked a bounded number at a time, in address order
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EscrowChange {
+    /// The proposal that changed the escrow
+    pub proposal_id: u64,
+    pub old_amount: Uint128,
+    pub new_amount: Uint128,
+    /// Last member already checked against the new amount
+    pub last_member: Option<Addr>,
+}
+
+pub const ESCROW_CHANGE: Item<EscrowChange> = Item::new("escrow_change");
+
+/// Last (grace_ends_at, batch_id) of promoted batches whose unpaid members have already been
+/// expired. Batches are checked in order of their grace end, so we never look at them twice.
+pub const EXPIRED_BATCHES_CURSOR: Item<(u64, u64)> = Item::new("expired_batches_cursor");
+
+/// Moves `EXPIRED_BATCHES_CURSOR` back right before the given promoted batch, if it is already
+/// past it, so the batch is checked again
+fn rewind_expired_batches_cursor(
+    storage: &mut dyn Storage,
+    grace_ends_at: u64,
+    batch_id: u64,
+) -> StdResult<()> {
+    match EXPIRED_BATCHES_CURSOR.may_load(storage)? {
+        Some(cursor) if cursor >= (grace_ends_at, batch_id) => {
+            let before = match batch_id.checked_sub(1) {
+                Some(previous_id) => (grace_ends_at, previous_id),
+                None => (grace_ends_at.saturating_sub(1), u64::MAX),
+            };
+            EXPIRED_BATCHES_CURSOR.save(storage, &before)
+        }
+        _ => Ok(()),
+    }
+}
+
 // We need a secondary index for batches, such that we can look up batches that have
 // not been promoted, ordered by expiration (ascending) up to now.
-// Index: (u8/bool: batch_promoted, u64: grace_ends_at) -> (u64: pk, Batch: batch)
+// Index: (u8/bool: batch_promoted, u64: promotion time) -> (u64: pk, Batch: batch)
+// The promotion time is the grace end, unless an early promotion was started before it.
+// Once promoted, it is always the grace end (ordering batches by payment deadline).
 pub struct BatchIndexes<'a> {
     pub promotion_time: MultiIndex<'a, (u8, u64), Batch, u64>,
 }
@@ -455,9 +1048,9 @@ impl<'a> IndexList<Batch> for BatchIndexes<'a> {
 pub fn batches<'a>() -> IndexedMap<'a, u64, Batch, BatchIndexes<'a>> {
     let indexes = BatchIndexes {
         promotion_time: MultiIndex::new(
-            |_, b: &Batch| {
-                let promoted = u8::from(b.batch_promoted);
-                (promoted, b.grace_ends_at)
+            |_, b: &Batch| match (b.batch_promoted, b.promoting_since) {
+                (false, Some(promoting_since)) => (0, promoting_since),
+                (promoted, _) => (u8::from(promoted), b.grace_ends_at),
             },
             "batch",
             "batch__promotion",
@@ -482,6 +1075,146 @@ pub enum ProposalContent {
     PunishMembers(Vec<Punishment>),
     WhitelistContract(String),
     RemoveContract(String),
+    /// Grants `amount` from the treasury to `recipient`. It vests linearly over `vesting` days,
+    /// and is claimed with `ExecuteMsg::ClaimSpend`. Without vesting, it is sent right away
+    TreasurySpend {
+        recipient: String,
+        amount: Coin,
+        vesting: Option<u32>,
+    },
+    /// Asks members to choose among `options`, with up to `max_choices` each. Polls are voted
+    /// with `ExecuteMsg::VotePoll`, and end as executed if they reach the quorum, without any
+    /// effect
+    Poll {
+        options: Vec<String>,
+        max_choices: u32,
+        method: PollMethod,
+    },
+    /// Pauses the trusted circle for `hours` from execution, replacing any current pause
+    Pause {
+        hours: u32,
+    },
+    /// Lifts the current pause, if any
+    Unpause {},
+    /// Replaces the permissions of a whitelisted contract
+    SetContractPermissions {
+        contract: String,
+        permissions: ContractPermissions,
+    },
+    /// Creates a committee of some voting members, which decides the `powers` kinds of proposals
+    /// on its own, with its own voting `rules`. Replaces the committee with the same name, if any
+    CreateCommittee {
+        name: String,
+        members: Vec<String>,
+        powers: Vec<ProposalKind>,
+        rules: VotingRules,
+    },
+    /// Removes a committee. Its proposals cannot be executed anymore
+    DissolveCommittee {
+        name: String,
+    },
+}
+
+/// Kind of a `ProposalContent`, to grant proposing it
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalKind {
+    AddRemoveNonVotingMembers,
+    EditTrustedCircle,
+    AddVotingMembers,
+    PunishMembers,
+    WhitelistContract,
+    RemoveContract,
+    TreasurySpend,
+    Poll,
+    Pause,
+    Unpause,
+    SetContractPermissions,
+    CreateCommittee,
+    DissolveCommittee,
+}
+
+impl ProposalKind {
+    /// Committees cannot manage committees
+    pub fn can_be_delegated(&self) -> bool {
+        !matches!(
+            self,
+            ProposalKind::CreateCommittee | ProposalKind::DissolveCommittee
+        )
+    }
+}
+
+/// Capabilities granted to a whitelisted contract. Whitelisted contracts may only execute what
+/// they were granted, besides handling their own rewards
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ContractPermissions {
+    /// May call `ExecuteMsg::DistributeRewards`
+    #[serde(default)]
+    pub distribute_rewards: bool,
+    /// May call `ExecuteMsg::CheckPending`
+    #[serde(default)]
+    pub check_pending: bool,
+    /// Kinds of proposals it may propose (and draft), despite having no voting power
+    #[serde(default)]
+    pub propose: Vec<ProposalKind>,
+}
+
+impl ContractPermissions {
+    pub fn can_propose(&self, proposal: &ProposalContent) -> bool {
+        self.propose.contains(&proposal.kind())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PollMethod {
+    /// Every chosen option gets the points of the voter. The option with most points wins
+    Plurality,
+    /// Choices are ranked by preference. Options with fewest points are eliminated until one has
+    /// the majority of the points counted (instant runoff)
+    RankedChoice,
+}
+
+impl ProposalContent {
+    /// Only proposals dealing with misbehaving members, or pausing the trusted circle, may be
+    /// urgent
+    pub fn can_be_urgent(&self) -> bool {
+        matches!(
+            self,
+            ProposalContent::PunishMembers(_)
+                | ProposalContent::RemoveContract(_)
+                | ProposalContent::Pause { .. }
+                | ProposalContent::Unpause {}
+        )
+    }
+
+    pub fn kind(&self) -> ProposalKind {
+        match self {
+            ProposalContent::AddRemoveNonVotingMembers { .. } => {
+                ProposalKind::AddRemoveNonVotingMembers
+            }
+            ProposalContent::EditTrustedCircle(_) => ProposalKind::EditTrustedCircle,
+            ProposalContent::AddVotingMembers { .. } => ProposalKind::AddVotingMembers,
+            ProposalContent::PunishMembers(_) => ProposalKind::PunishMembers,
+            ProposalContent::WhitelistContract(_) => ProposalKind::WhitelistContract,
+            ProposalContent::RemoveContract(_) => ProposalKind::RemoveContract,
+            ProposalContent::TreasurySpend { .. } => ProposalKind::TreasurySpend,
+            ProposalContent::Poll { .. } => ProposalKind::Poll,
+            ProposalContent::Pause { .. } => ProposalKind::Pause,
+            ProposalContent::Unpause {} => ProposalKind::Unpause,
+            ProposalContent::SetContractPermissions { .. } => ProposalKind::SetContractPermissions,
+            ProposalContent::CreateCommittee { .. } => ProposalKind::CreateCommittee,
+            ProposalContent::DissolveCommittee { .. } => ProposalKind::DissolveCommittee,
+        }
+    }
+
+    /// Only the pause itself may be proposed and executed while the trusted circle is paused
+    pub fn allowed_when_paused(&self) -> bool {
+        matches!(
+            self,
+            ProposalContent::Pause { .. } | ProposalContent::Unpause {}
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -498,6 +1231,40 @@ pub struct Proposal {
     pub total_points: u64,
     // summary of existing votes
     pub votes: Votes,
+    /// Urgent proposals use the `UrgentRules` of the trusted circle
+    #[serde(default)]
+    pub urgent: bool,
+    /// Voting period in seconds of urgent proposals, which is in hours rather than the days of
+    /// `rules`
+    #[serde(default)]
+    pub urgent_period: Option<u64>,
+    /// Set for secret proposals, voted by commit and reveal
+    #[serde(default)]
+    pub secret: Option<SecretBallot>,
+    /// Members who cosponsored the draft of the proposal
+    #[serde(default)]
+    pub cosponsors: Vec<Addr>,
+    /// Set for proposals of a committee, voted by its members only
+    #[serde(default)]
+    pub committee: Option<String>,
+}
+
+/// Commit-reveal voting of a secret proposal
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SecretBallot {
+    /// Votes are committed until then, and revealed afterwards until the proposal expires
+    pub commit_ends: Expiration,
+    /// How votes committed but never revealed are counted
+    pub unrevealed: UnrevealedVotes,
+}
+
+/// Vote committed on a secret proposal, not revealed yet
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct VoteCommit {
+    /// sha256 hash of the JSON encoded `VoteCommitment`
+    pub hash: Binary,
+    /// Voting points of the member when the proposal started
+    pub points: u64,
 }
 
 // we multiply by this when calculating needed_votes in order to round up properly
@@ -540,6 +1307,11 @@ impl Votes {
 }
 
 impl Proposal {
+    pub fn voting_period_secs(&self) -> u64 {
+        self.urgent_period
+            .unwrap_or_else(|| self.rules.voting_period_secs())
+    }
+
     /// current_status is non-mutable and returns what the status should be.
     /// (designed for queries)
     pub fn current_status(&self, block: &BlockInfo) -> Status {
@@ -552,6 +1324,10 @@ impl Proposal {
         if status == Status::Open && self.expires.is_expired(block) {
             status = Status::Rejected;
         }
+        // Polls have nothing to execute, so they are concluded as soon as they pass on expiry
+        if status == Status::Passed && self.proposal.kind() == ProposalKind::Poll {
+            status = Status::Executed;
+        }
 
         status
     }
@@ -593,20 +1369,83 @@ impl Proposal {
 
 // this is a helper function so Decimal works with u64 rather than Uint128
 // also, we must *round up* here, as we need 8, not 7 votes to reach 50% of 15 total
-fn votes_needed(points: u64, percentage: Decimal) -> u64 {
+pub(crate) fn votes_needed(points: u64, percentage: Decimal) -> u64 {
     let applied = percentage * Uint128::new(PRECISION_FACTOR * points as u128);
     // Divide by PRECISION_FACTOR, rounding up to the nearest integer
-    ((applied.u128() + PRECISION_FACTOR - 1) / PRECISION_FACTOR) as u64
+    applied.u128().div_ceil(PRECISION_FACTOR) as u64
 }
 
 // unique items
 pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
 
 pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
-// This maps expiration timestamp (seconds) to Proposal primary key,
-// needed for bounded size queries in adjust_open_proposals_for_leaver
+// This indexes proposals by (expiration timestamp (seconds), primary key), so proposals
+// expiring in the same second are all kept. Needed for bounded size queries in
+// adjust_open_proposals_for_leaver and close_expired_proposals
 // Just add in create_proposal
-pub const PROPOSAL_BY_EXPIRY: Map<u64, u64> = Map::new("proposals_by_expiry");
+pub const PROPOSAL_BY_EXPIRY: Map<(u64, u64), Empty> = Map::new("proposals_by_expiry_id");
+
+/// Creation times (seconds) of the recent urgent proposals of every member, within the limit
+/// period of `UrgentRules`
+pub const URGENT_PROPOSALS: Map<&Addr, Vec<u64>> = Map::new("urgent_proposals");
+
+/// Time until which proposal execution, membership changes, escrow withdrawals and treasury
+/// claims are paused
+pub const PAUSED_UNTIL: Item<Timestamp> = Item::new("paused_until");
+
+/// Fails if the trusted circle is paused
+pub fn ensure_not_paused(storage: &dyn Storage, block: &BlockInfo) -> Result<(), ContractError> {
+    match PAUSED_UNTIL.may_load(storage)? {
+        Some(until) if block.time < until => Err(ContractError::Paused { until }),
+        _ => Ok(()),
+    }
+}
+
+/// Voting points, reward points and escrow of all the members of a trusted circle
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ImportTotals {
+    pub points: u64,
+    pub reward_points: u64,
+    pub escrow: Uint128,
+}
+
+/// State import in progress. Set at instantiation with an `importer`, and removed once the import
+/// is finished. Only the import messages are allowed meanwhile
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Import {
+    pub importer: Addr,
+    /// Totals of the exported trusted circle. None until the snapshot header is imported
+    pub expected: Option<ImportTotals>,
+    /// Totals of the members imported so far
+    pub imported: ImportTotals,
+}
+
+pub const IMPORT: Item<Import> = Item::new("import");
+
+/// Fails if the trusted circle is importing its state
+pub fn ensure_not_importing(storage: &dyn Storage) -> Result<(), ContractError> {
+    match IMPORT.may_load(storage)? {
+        Some(_) => Err(ContractError::Importing {}),
+        None => Ok(()),
+    }
+}
+
+/// Options chosen by every voter of a poll, in order of preference
+pub const POLL_BALLOTS: Map<(u64, &Addr), Vec<u32>> = Map::new("poll_ballots");
+
+/// Votes committed on secret proposals, until they are revealed
+pub const VOTE_COMMITS: Map<(u64, &Addr), VoteCommit> = Map::new("vote_commits");
+
+/// Public keys verifying signed votes of members
+pub const VOTING_KEYS: Map<&Addr, Binary> = Map::new("voting_keys");
+/// Member of every registered voting key, so a key is never shared by members
+pub const VOTING_KEY_OWNERS: Map<&[u8], Addr> = Map::new("voting_key_owners");
+/// Nonce of the next signed vote of every member, so signed votes cannot be replayed
+pub const VOTE_NONCES: Map<&Addr, u64> = Map::new("vote_nonces");
+
+// Last (expiration timestamp (seconds), proposal id) of proposals already closed by the end
+// blocker
+pub const CLOSED_PROPOSALS_CURSOR: Item<(u64, u64)> = Item::new("closed_proposals_cursor");
 
 pub fn create_proposal(store: &mut dyn Storage, proposal: &Proposal) -> StdResult<u64> {
     let expiry = match proposal.expires {
@@ -616,11 +1455,58 @@ pub fn create_proposal(store: &mut dyn Storage, proposal: &Proposal) -> StdResul
     let id: u64 = PROPOSAL_COUNT.may_load(store)?.unwrap_or_default() + 1;
     PROPOSAL_COUNT.save(store, &id)?;
     PROPOSALS.save(store, id, proposal)?;
-    PROPOSAL_BY_EXPIRY.save(store, expiry, &id)?;
+    PROPOSAL_BY_EXPIRY.save(store, (expiry, id), &Empty {})?;
     Ok(id)
 }
 
-pub const DISTRIBUTION: Distribution = Distribution::new("distribution", "distribution-adj");
+/// Reward points of members, per membership status. Independent of voting points.
+/// Pending (unpaid) and leaving members never get rewards
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct RewardWeights {
+    pub non_voting: u64,
+    pub pending_paid: u64,
+    pub voting: u64,
+}
+
+impl Default for RewardWeights {
+    /// Only voting members get rewards
+    fn default() -> Self {
+        RewardWeights {
+            non_voting: 0,
+            pending_paid: 0,
+            voting: 1,
+        }
+    }
+}
+
+impl RewardWeights {
+    /// Reward points of a member with given status
+    pub fn points(&self, status: &MemberStatus) -> u64 {
+        match status {
+            MemberStatus::NonVoting {} => self.non_voting,
+            MemberStatus::PendingPaid { .. } => self.pending_paid,
+            MemberStatus::Voting {} => self.voting,
+            MemberStatus::Pending { .. } | MemberStatus::Leaving { .. } => 0,
+        }
+    }
+}
+
+pub const REWARD_WEIGHTS: Item<RewardWeights> = Item::new("reward_weights");
+/// Total reward points of all members, rewards are distributed over them
+pub const REWARD_TOTAL: Item<u64> = Item::new("reward_total");
+
+/// Total voting points. Snapshotted on every change, so it can be queried at past heights.
+/// It is stored under the same key as `tg_utils::TOTAL`, so raw queries of the total keep working.
+pub const TOTAL: SnapshotItem<u64> = SnapshotItem::new(
+    TOTAL_KEY,
+    "total__checkpoints",
+    "total__changelog",
+    Strategy::EveryBlock,
+);
+
+pub const DISTRIBUTION: Distribution =
+    Distribution::new("distribution", "distribution-adj", "distribution-tranches");
 
 #[cfg(test)]
 mod test {
@@ -680,6 +1566,11 @@ mod test {
             rules,
             total_points,
             votes,
+            urgent: false,
+            urgent_period: None,
+            secret: None,
+            cosponsors: vec![],
+            committee: None,
         };
         prop.is_passed(&block)
     }
@@ -99,9 +99,21 @@ fn setup_bdd(mut deps: DepsMut<TgradeQuery>) {
         threshold: Decimal::percent(60),
         allow_end_early: true,
         initial_members: vec![NON_VOTING.into()],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
         deny_list: None,
         edit_trusted_circle_disabled: false,
         reward_denom: "usdc".to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     };
     let info = mock_info(VOTING, &coins(VOTING_ESCROW, TRUSTED_CIRCLE_DENOM));
     instantiate(deps.branch(), start.clone(), info, msg).unwrap();
@@ -192,6 +204,8 @@ fn demo_proposal() -> ExecuteMsg {
         title: "Demo Proposal".to_string(),
         description: "To test who can vote".to_string(),
         proposal,
+        urgent: false,
+        secret: false,
     }
 }
 
@@ -204,12 +218,18 @@ fn edit_trusted_circle_proposal(escrow_funds: u128) -> ExecuteMsg {
         threshold: None,
         allow_end_early: None,
         edit_trusted_circle_disabled: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     });
     ExecuteMsg::Propose {
         title: "Triple Escrow Amount Proposal".to_string(),
         description: "To test who can still vote after grace period (also changed to 1 day) ends"
             .to_string(),
         proposal,
+        urgent: false,
+        secret: false,
     }
 }
 
@@ -219,6 +239,8 @@ fn voting_members_proposal(members: Vec<String>) -> ExecuteMsg {
         title: "Add Voting Members".to_string(),
         description: "To add voting members through the proposal mechanism".to_string(),
         proposal,
+        urgent: false,
+        secret: false,
     }
 }
 
@@ -235,6 +257,8 @@ fn punish_member_proposal(member: String, slashing_percentage: u64, kick_out: bo
             "To punish a member with a given slashing / expulsion through the proposal mechanism"
                 .to_string(),
         proposal,
+        urgent: false,
+        secret: false,
     }
 }
 
@@ -0,0 +1,174 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coin, coins, Addr, CosmosMsg, Decimal, Uint128};
+use cw_multi_test::CosmosRouter;
+use tg3::Status;
+use tg_bindings::TgradeMsg;
+use tg_bindings_test::TgradeApp;
+
+use super::suite::SuiteBuilder;
+use crate::error::ContractError;
+use crate::msg::InstantiateMsg;
+use crate::multitest::TrustedCircleExt;
+use crate::state::{EscrowStatus, MemberStatus, ProposalContent};
+
+#[test]
+fn client_adds_voting_member() {
+    let member = "member";
+    let mut suite = SuiteBuilder::new().with_funds(member, 1_000_000).build();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    let msg = client
+        .propose(
+            "Add member",
+            "",
+            ProposalContent::AddVotingMembers {
+                voters: vec![member.to_owned()],
+            },
+            false,
+            false,
+        )
+        .unwrap();
+    suite.execute(&owner, msg).unwrap();
+
+    let proposals = client.list_proposals(&suite.querier(), None, None).unwrap();
+    assert_eq!(proposals.len(), 1);
+    let proposal_id = proposals[0].id;
+    assert_eq!(proposals[0].status, Status::Passed);
+
+    suite
+        .execute(&owner, client.execute(proposal_id).unwrap())
+        .unwrap();
+    let proposal = client.proposal(&suite.querier(), proposal_id).unwrap();
+    assert_eq!(proposal.status, Status::Executed);
+    assert_eq!(
+        client.escrow(&suite.querier(), member).unwrap(),
+        Some(EscrowStatus::pending(proposal_id))
+    );
+
+    let msg = client.deposit_escrow(coins(1_000_000, "utgd")).unwrap();
+    suite.execute(member, msg).unwrap();
+
+    let escrow = client.escrow(&suite.querier(), member).unwrap().unwrap();
+    assert_eq!(escrow.paid, Uint128::new(1_000_000));
+    assert_eq!(escrow.status, MemberStatus::Voting {});
+
+    let voters = client.list_voters(&suite.querier(), None, None).unwrap();
+    let mut voters: Vec<_> = voters.into_iter().map(|m| m.addr).collect();
+    voters.sort();
+    assert_eq!(voters, vec![member.to_owned(), owner]);
+    assert!(client.voter(&suite.querier(), member).unwrap().is_some());
+}
+
+#[test]
+fn client_distributes_and_withdraws_rewards() {
+    let mut suite = SuiteBuilder::new().with_funds("owner", 100).build();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    let msg = client.distribute_rewards(coins(100, "utgd")).unwrap();
+    suite.execute(&owner, msg).unwrap();
+    assert_eq!(
+        client
+            .withdrawable_rewards(&suite.querier(), &owner)
+            .unwrap(),
+        coin(100, "utgd")
+    );
+
+    let msg = client.withdraw_rewards(None, None).unwrap();
+    suite.execute(&owner, msg).unwrap();
+    assert_eq!(
+        client
+            .withdrawable_rewards(&suite.querier(), &owner)
+            .unwrap(),
+        coin(0, "utgd")
+    );
+    assert_eq!(
+        suite.querier().query_balance(&owner, "utgd").unwrap(),
+        coin(100, "utgd")
+    );
+}
+
+#[test]
+fn client_surfaces_contract_errors() {
+    let outsider = "outsider";
+    let mut suite = SuiteBuilder::new().with_funds(outsider, 1_000_000).build();
+    let client = suite.client();
+
+    assert_eq!(client.escrow(&suite.querier(), outsider).unwrap(), None);
+
+    let msg = client.deposit_escrow(coins(1_000_000, "utgd")).unwrap();
+    let err = suite.execute(outsider, msg).unwrap_err();
+    assert_eq!(ContractError::NotAMember {}, err.downcast().unwrap());
+}
+
+#[test]
+fn multitest_helpers_drive_a_circle() {
+    let (owner, member, newbie) = ("owner", "member", "newbie");
+    let mut app = TgradeApp::new(owner);
+    let block_info = app.block_info();
+    app.init_modules(|router, api, storage| -> AnyResult<()> {
+        for (recipient, amount) in [(owner, 2_000_000), (newbie, 1_000_000)] {
+            router.execute(
+                api,
+                storage,
+                &block_info,
+                Addr::unchecked(owner),
+                CosmosMsg::Custom(TgradeMsg::MintTokens {
+                    denom: "utgd".to_owned(),
+                    amount: Uint128::new(amount),
+                    recipient: recipient.to_owned(),
+                }),
+            )?;
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    let msg = InstantiateMsg {
+        name: "Trusted Circle".to_owned(),
+        denom: "utgd".to_owned(),
+        escrow_amount: Uint128::new(1_000_000),
+        voting_period: 1,
+        quorum: Decimal::percent(50),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![member.to_owned()],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: "utgd".to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    let circle = app.deploy_trusted_circle(owner, &msg).unwrap();
+    assert_eq!(
+        app.wrap().query_balance(owner, "utgd").unwrap(),
+        coin(0, "utgd")
+    );
+    // Voting power is snapshotted at the start of proposals
+    app.advance_blocks(1);
+
+    let add = ProposalContent::AddVotingMembers {
+        voters: vec![newbie.to_owned()],
+    };
+    let proposal_id = app
+        .propose_and_execute(&circle, &[owner, member], add)
+        .unwrap();
+    let proposal = circle.proposal(&app.wrap(), proposal_id).unwrap();
+    assert_eq!(proposal.status, Status::Executed);
+    assert_eq!(proposal.votes.yes, 2);
+
+    app.deposit_escrow(&circle, newbie).unwrap();
+    let escrow = circle.escrow(&app.wrap(), newbie).unwrap().unwrap();
+    assert_eq!(escrow.status, MemberStatus::Voting {});
+}
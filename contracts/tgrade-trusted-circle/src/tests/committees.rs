@@ -0,0 +1,292 @@
+use cosmwasm_std::{coins, Addr, Decimal};
+use tg3::{Status, Vote};
+
+use super::suite::{Suite, SuiteBuilder};
+use crate::error::ContractError;
+use crate::msg::CommitteeResponse;
+use crate::state::{ProposalContent, ProposalKind, VotingRules};
+
+const MEMBER: &str = "member";
+const OTHER: &str = "other";
+const NEWBIE: &str = "newbie";
+const COMMITTEE: &str = "membership";
+
+/// Trusted circle with the owner, `MEMBER` and `OTHER` as voters
+fn setup() -> Suite {
+    let mut suite = SuiteBuilder::new()
+        .with_funds(MEMBER, 1_000_000)
+        .with_funds(OTHER, 1_000_000)
+        .build();
+    let owner = suite.owner();
+    suite
+        .propose_add_voting(&owner, "Add voters", "", &[MEMBER, OTHER])
+        .unwrap();
+    let client = suite.client();
+    suite.execute(&owner, client.execute(1).unwrap()).unwrap();
+    for voter in [MEMBER, OTHER] {
+        let msg = client.deposit_escrow(coins(1_000_000, "utgd")).unwrap();
+        suite.execute(voter, msg).unwrap();
+    }
+    // Voting power is snapshotted at the start of proposals
+    suite.advance_seconds(5);
+    suite
+}
+
+/// Committee of `MEMBER` and `OTHER` adding non-voting members, which needs both their votes
+fn create_committee() -> ProposalContent {
+    ProposalContent::CreateCommittee {
+        name: COMMITTEE.to_owned(),
+        members: vec![MEMBER.to_owned(), OTHER.to_owned()],
+        powers: vec![ProposalKind::AddRemoveNonVotingMembers],
+        rules: VotingRules {
+            voting_period: 1,
+            quorum: Decimal::percent(50),
+            threshold: Decimal::percent(60),
+            allow_end_early: true,
+        },
+    }
+}
+
+fn add_newbie() -> ProposalContent {
+    ProposalContent::AddRemoveNonVotingMembers {
+        add: vec![NEWBIE.to_owned()],
+        remove: vec![],
+    }
+}
+
+fn last_proposal_id(suite: &Suite) -> u64 {
+    let client = suite.client();
+    let proposals = client.list_proposals(&suite.querier(), None, None).unwrap();
+    proposals.last().unwrap().id
+}
+
+/// Passes a proposal of the whole trusted circle, voted by the owner and `MEMBER`
+fn pass_proposal(suite: &mut Suite, proposal: ProposalContent) -> anyhow::Result<()> {
+    let owner = suite.owner();
+    let client = suite.client();
+    suite.execute(
+        &owner,
+        client.propose("Circle", "", proposal, false, false)?,
+    )?;
+    let proposal_id = last_proposal_id(suite);
+    suite.execute(MEMBER, client.vote(proposal_id, Vote::Yes)?)?;
+    suite.execute(&owner, client.execute(proposal_id)?)?;
+    Ok(())
+}
+
+/// `MEMBER` proposes to the committee, and `OTHER` votes for it
+fn pass_committee_proposal(suite: &mut Suite, proposal: ProposalContent) -> u64 {
+    let client = suite.client();
+    let msg = client
+        .propose_to_committee(COMMITTEE, "Committee", "", proposal)
+        .unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+    let proposal_id = last_proposal_id(suite);
+    suite
+        .execute(OTHER, client.vote(proposal_id, Vote::Yes).unwrap())
+        .unwrap();
+    proposal_id
+}
+
+#[test]
+fn committee_decides_its_proposals() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+    pass_proposal(&mut suite, create_committee()).unwrap();
+
+    let committee = client.committee(&suite.querier(), COMMITTEE).unwrap();
+    assert_eq!(
+        committee.members,
+        vec![Addr::unchecked(MEMBER), Addr::unchecked(OTHER)]
+    );
+    assert_eq!(
+        client
+            .list_committees(&suite.querier(), None, None)
+            .unwrap(),
+        vec![committee]
+    );
+
+    let msg = client
+        .propose_to_committee(COMMITTEE, "Committee", "", add_newbie())
+        .unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+    let proposal_id = last_proposal_id(&suite);
+    let proposal = client.proposal(&suite.querier(), proposal_id).unwrap();
+    assert_eq!(proposal.committee.as_deref(), Some(COMMITTEE));
+    assert_eq!(proposal.total_points, 2);
+    assert_eq!(proposal.status, Status::Open);
+
+    // Other voters of the trusted circle cannot vote
+    let err = suite
+        .execute(&owner, client.vote(proposal_id, Vote::Yes).unwrap())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized(format!("Not a member of committee {}", COMMITTEE)),
+        err.downcast().unwrap()
+    );
+
+    suite
+        .execute(OTHER, client.vote(proposal_id, Vote::Yes).unwrap())
+        .unwrap();
+    suite
+        .execute(&owner, client.execute(proposal_id).unwrap())
+        .unwrap();
+    let members = client
+        .list_non_voting_members(&suite.querier(), None, None)
+        .unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].addr, NEWBIE);
+}
+
+#[test]
+fn committee_only_decides_its_powers() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+    pass_proposal(&mut suite, create_committee()).unwrap();
+
+    let msg = client
+        .propose_to_committee(COMMITTEE, "Committee", "", ProposalContent::Unpause {})
+        .unwrap();
+    let err = suite.execute(MEMBER, msg).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized(format!(
+            "Committee {} cannot decide Unpause proposals",
+            COMMITTEE
+        )),
+        err.downcast().unwrap()
+    );
+
+    let msg = client
+        .propose_to_committee(COMMITTEE, "Committee", "", add_newbie())
+        .unwrap();
+    let err = suite.execute(&owner, msg).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized(format!("Not a member of committee {}", COMMITTEE)),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn committee_members_are_voters() {
+    let mut suite = SuiteBuilder::new().build();
+    let owner = suite.owner();
+    let client = suite.client();
+    suite
+        .propose_modify_non_voting(&owner, "Add member", "", &[NEWBIE], &[])
+        .unwrap();
+    suite.execute(&owner, client.execute(1).unwrap()).unwrap();
+
+    let committee = |members: &[&str], powers| ProposalContent::CreateCommittee {
+        name: COMMITTEE.to_owned(),
+        members: members.iter().map(|member| (*member).to_owned()).collect(),
+        powers,
+        rules: client.trusted_circle(&suite.querier()).unwrap().rules,
+    };
+    let invalid = [
+        (
+            committee(&[&owner, NEWBIE], vec![ProposalKind::Poll]),
+            format!("{} is not a voting member", NEWBIE),
+        ),
+        (
+            committee(&[&owner, &owner], vec![ProposalKind::Poll]),
+            format!("{} is listed twice", owner),
+        ),
+        (
+            committee(&[&owner], vec![ProposalKind::DissolveCommittee]),
+            "DissolveCommittee cannot be delegated".to_owned(),
+        ),
+        (committee(&[&owner], vec![]), "Missing powers".to_owned()),
+    ];
+    for (proposal, reason) in invalid {
+        let msg = client
+            .propose("Committee", "", proposal, false, false)
+            .unwrap();
+        let err = suite.execute(&owner, msg).unwrap_err();
+        assert_eq!(
+            ContractError::InvalidCommittee(reason),
+            err.downcast().unwrap()
+        );
+    }
+}
+
+#[test]
+fn dissolved_committee_cannot_execute() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+    pass_proposal(&mut suite, create_committee()).unwrap();
+    let proposal_id = pass_committee_proposal(&mut suite, add_newbie());
+
+    let dissolve = ProposalContent::DissolveCommittee {
+        name: COMMITTEE.to_owned(),
+    };
+    pass_proposal(&mut suite, dissolve.clone()).unwrap();
+    assert_eq!(
+        client
+            .list_committees(&suite.querier(), None, None)
+            .unwrap(),
+        vec![]
+    );
+
+    let err = suite
+        .execute(&owner, client.execute(proposal_id).unwrap())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CommitteeNotFound(COMMITTEE.to_owned()),
+        err.downcast().unwrap()
+    );
+    let msg = client
+        .propose_to_committee(COMMITTEE, "Committee", "", add_newbie())
+        .unwrap();
+    let err = suite.execute(MEMBER, msg).unwrap_err();
+    assert_eq!(
+        ContractError::CommitteeNotFound(COMMITTEE.to_owned()),
+        err.downcast().unwrap()
+    );
+    let err = pass_proposal(&mut suite, dissolve).unwrap_err();
+    assert_eq!(
+        ContractError::CommitteeNotFound(COMMITTEE.to_owned()),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn circle_overrides_committee() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+    pass_proposal(&mut suite, create_committee()).unwrap();
+    let proposal_id = pass_committee_proposal(&mut suite, add_newbie());
+
+    // Same committee, only deciding polls
+    let rules = client.trusted_circle(&suite.querier()).unwrap().rules;
+    let override_committee = ProposalContent::CreateCommittee {
+        name: COMMITTEE.to_owned(),
+        members: vec![MEMBER.to_owned()],
+        powers: vec![ProposalKind::Poll],
+        rules: rules.clone(),
+    };
+    pass_proposal(&mut suite, override_committee).unwrap();
+    assert_eq!(
+        client.committee(&suite.querier(), COMMITTEE).unwrap(),
+        CommitteeResponse {
+            name: COMMITTEE.to_owned(),
+            members: vec![Addr::unchecked(MEMBER)],
+            powers: vec![ProposalKind::Poll],
+            rules,
+        }
+    );
+
+    let err = suite
+        .execute(&owner, client.execute(proposal_id).unwrap())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized(format!(
+            "Committee {} cannot decide AddRemoveNonVotingMembers proposals",
+            COMMITTEE
+        )),
+        err.downcast().unwrap()
+    );
+}
@@ -0,0 +1,213 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::Addr;
+
+use super::*;
+use crate::msg::ContractPermissionsResponse;
+use crate::state::{ContractPermissions, ProposalKind};
+use crate::tests::unit_tests::TokenQuerier;
+
+type ContractDeps = OwnedDeps<MockStorage, MockApi, TokenQuerier, TgradeQuery>;
+
+/// Trusted circle with a single voter, and `TOKEN_ADDR` as only contract
+fn setup() -> (ContractDeps, Env) {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: TokenQuerier::new(&Addr::unchecked(TOKEN_ADDR), "0.1"),
+        custom_query_type: PhantomData::<TgradeQuery>,
+    };
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    (deps, later(&mock_env(), 10))
+}
+
+fn propose(
+    deps: &mut ContractDeps,
+    env: &Env,
+    proposer: &str,
+    proposal: ProposalContent,
+) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Automation".to_owned(),
+        description: "Let the bot do the chores".to_owned(),
+        proposal,
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(proposer, &[]), msg)?;
+    Ok(parse_prop_id(&res.attributes))
+}
+
+/// Proposals of the single voter pass right away
+fn propose_and_execute(deps: &mut ContractDeps, env: &Env, proposal: ProposalContent) {
+    let proposal_id = propose(deps, env, INIT_ADMIN, proposal).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::Execute { proposal_id },
+    )
+    .unwrap();
+}
+
+fn permissions(deps: &ContractDeps, addr: &str) -> Option<ContractPermissions> {
+    let msg = QueryMsg::ContractPermissions {
+        addr: addr.to_owned(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let res: ContractPermissionsResponse = from_slice(&res).unwrap();
+    res.permissions
+}
+
+fn set_permissions(permissions: ContractPermissions) -> ProposalContent {
+    ProposalContent::SetContractPermissions {
+        contract: TOKEN_ADDR.to_owned(),
+        permissions,
+    }
+}
+
+fn unauthorized() -> ContractError {
+    ContractError::Unauthorized("Contract is not permitted to execute this message".to_owned())
+}
+
+#[test]
+fn whitelisted_contracts_only_execute_granted_messages() {
+    let (mut deps, env) = setup();
+
+    propose_and_execute(
+        &mut deps,
+        &env,
+        ProposalContent::WhitelistContract(TOKEN_ADDR.to_owned()),
+    );
+    assert_eq!(
+        permissions(&deps, TOKEN_ADDR),
+        Some(ContractPermissions::default())
+    );
+    assert_eq!(permissions(&deps, NONMEMBER), None);
+
+    // No abilities until granted
+    let contract = mock_info(TOKEN_ADDR, &[]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        contract.clone(),
+        ExecuteMsg::CheckPending {},
+    )
+    .unwrap_err();
+    assert_eq!(err, unauthorized());
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        contract.clone(),
+        ExecuteMsg::DistributeRewards {},
+    )
+    .unwrap_err();
+    assert_eq!(err, unauthorized());
+    let err = propose(&mut deps, &env, TOKEN_ADDR, ProposalContent::Unpause {}).unwrap_err();
+    assert_eq!(err, unauthorized());
+    // Others are not restricted
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::CheckPending {},
+    )
+    .unwrap();
+
+    let granted = ContractPermissions {
+        distribute_rewards: false,
+        check_pending: true,
+        propose: vec![ProposalKind::AddRemoveNonVotingMembers],
+    };
+    propose_and_execute(&mut deps, &env, set_permissions(granted.clone()));
+    assert_eq!(permissions(&deps, TOKEN_ADDR), Some(granted));
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        contract.clone(),
+        ExecuteMsg::CheckPending {},
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        contract.clone(),
+        ExecuteMsg::DistributeRewards {},
+    )
+    .unwrap_err();
+    assert_eq!(err, unauthorized());
+
+    // Proposes the granted kinds only, without voting for them
+    let proposal_id = propose(
+        &mut deps,
+        &env,
+        TOKEN_ADDR,
+        ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING1.to_owned()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.votes.total(), 0);
+    assert_eq!(prop.status, Status::Open);
+    let err = propose(&mut deps, &env, TOKEN_ADDR, ProposalContent::Unpause {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Member doesn't have a voting power".to_owned())
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        contract,
+        ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, unauthorized());
+
+    // Permissions are dropped with the contract
+    propose_and_execute(
+        &mut deps,
+        &env,
+        ProposalContent::RemoveContract(TOKEN_ADDR.to_owned()),
+    );
+    assert_eq!(permissions(&deps, TOKEN_ADDR), None);
+}
+
+#[test]
+fn permissions_are_granted_to_whitelisted_contracts_only() {
+    let (mut deps, env) = setup();
+
+    let err = propose(
+        &mut deps,
+        &env,
+        INIT_ADMIN,
+        set_permissions(ContractPermissions::default()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotWhitelisted(TOKEN_ADDR.to_owned()));
+
+    let err = propose(
+        &mut deps,
+        &env,
+        INIT_ADMIN,
+        ProposalContent::SetContractPermissions {
+            contract: NONMEMBER.to_owned(),
+            permissions: ContractPermissions::default(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAContract(NONMEMBER.to_owned()));
+}
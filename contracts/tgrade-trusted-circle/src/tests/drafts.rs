@@ -0,0 +1,244 @@
+use cosmwasm_std::Addr;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::{DraftListResponse, DraftResponse};
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+const DAY: u64 = 86_400;
+
+fn instantiate_msg(draft_period: Option<u32>) -> InstantiateMsg {
+    InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period,
+    }
+}
+
+/// Adds `voters` as voting members paying their escrow
+fn add_voters(deps: &mut TgradeDeps, env: &Env, proposal_id: u64, voters: &[&str]) {
+    let add = voters.iter().map(|voter| voter.to_string()).collect();
+    proposal_add_voting_members(deps.as_mut(), env.clone(), proposal_id, add).unwrap();
+    for voter in voters {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+}
+
+/// Trusted circle with three voters, and drafts expiring after a week
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(Some(7)),
+    )
+    .unwrap();
+
+    let env = mock_env();
+    add_voters(&mut deps, &env, PROPOSAL_ID_1, &[VOTING1, VOTING2]);
+
+    (deps, later(&env, 10))
+}
+
+fn add_non_voting(member: &str) -> ProposalContent {
+    ProposalContent::AddRemoveNonVotingMembers {
+        add: vec![member.to_owned()],
+        remove: vec![],
+    }
+}
+
+fn create_draft(deps: &mut TgradeDeps, env: &Env, author: &str) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::CreateDraft {
+        title: "Add member".to_owned(),
+        description: "Hopefully without typos".to_owned(),
+        proposal: add_non_voting("bil"),
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(author, &[]), msg)?;
+    let draft_id = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "draft_id")
+        .map(|attr| attr.value.parse().unwrap())
+        .unwrap();
+    Ok(draft_id)
+}
+
+fn draft_msg(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+}
+
+fn query_draft(deps: &TgradeDeps, draft_id: u64) -> DraftResponse {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Draft { draft_id }).unwrap();
+    from_slice(&res).unwrap()
+}
+
+fn list_drafts(deps: &TgradeDeps, author: Option<&str>) -> Vec<u64> {
+    let msg = QueryMsg::ListDrafts {
+        author: author.map(str::to_owned),
+        start_after: None,
+        limit: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let res: DraftListResponse = from_slice(&res).unwrap();
+    res.drafts.into_iter().map(|draft| draft.draft_id).collect()
+}
+
+#[test]
+fn drafts_are_amended_cosponsored_and_published() {
+    let (mut deps, env) = setup();
+
+    let err = create_draft(&mut deps, &env, NONMEMBER).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Member doesn't have a voting power".to_owned())
+    );
+    let draft_id = create_draft(&mut deps, &env, VOTING1).unwrap();
+    let other_draft = create_draft(&mut deps, &env, VOTING2).unwrap();
+    assert_eq!(list_drafts(&deps, None), vec![draft_id, other_draft]);
+    assert_eq!(list_drafts(&deps, Some(VOTING2)), vec![other_draft]);
+
+    // Cosponsored once by other voting members
+    let cosponsor = ExecuteMsg::CosponsorDraft { draft_id };
+    draft_msg(&mut deps, &env, VOTING2, cosponsor.clone()).unwrap();
+    let err = draft_msg(&mut deps, &env, VOTING2, cosponsor.clone()).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyCosponsored {});
+    let err = draft_msg(&mut deps, &env, VOTING1, cosponsor.clone()).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyCosponsored {});
+    let err = draft_msg(&mut deps, &env, NONMEMBER, cosponsor.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Member doesn't have a voting power".to_owned())
+    );
+    assert_eq!(
+        query_draft(&deps, draft_id).cosponsors,
+        vec![Addr::unchecked(VOTING2)]
+    );
+
+    // Only the author amends the draft, which must be sponsored again
+    let amend = ExecuteMsg::AmendDraft {
+        draft_id,
+        title: None,
+        description: None,
+        proposal: Some(add_non_voting(NONVOTING1)),
+    };
+    let err = draft_msg(&mut deps, &env, VOTING2, amend.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Only the author can amend the draft".to_owned())
+    );
+    let invalid = ExecuteMsg::AmendDraft {
+        draft_id,
+        title: None,
+        description: None,
+        proposal: Some(ProposalContent::AddVotingMembers { voters: vec![] }),
+    };
+    let err = draft_msg(&mut deps, &env, VOTING1, invalid).unwrap_err();
+    assert_eq!(err, ContractError::NoMembers {});
+    draft_msg(&mut deps, &env, VOTING1, amend).unwrap();
+    let draft = query_draft(&deps, draft_id);
+    assert_eq!(draft.proposal, add_non_voting(NONVOTING1));
+    assert!(draft.cosponsors.is_empty());
+    draft_msg(&mut deps, &env, VOTING2, cosponsor.clone()).unwrap();
+
+    // A voter joins before the draft is published
+    let env = later(&env, DAY);
+    add_voters(&mut deps, &env, 2, &[VOTING3]);
+    draft_msg(&mut deps, &env, VOTING3, cosponsor).unwrap();
+
+    let publish = ExecuteMsg::PublishDraft { draft_id };
+    let err = draft_msg(&mut deps, &env, VOTING2, publish.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Only the author can publish the draft".to_owned())
+    );
+    let env = later(&env, 10);
+    let res = draft_msg(&mut deps, &env, VOTING1, publish.clone()).unwrap();
+    let proposal_id = parse_prop_id(&res.attributes);
+
+    // Voting starts when published
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.proposal, add_non_voting(NONVOTING1));
+    assert_eq!(prop.total_points, 4);
+    assert_eq!(prop.votes.yes, 1);
+    assert_eq!(
+        prop.cosponsors,
+        vec![Addr::unchecked(VOTING2), Addr::unchecked(VOTING3)]
+    );
+    assert_eq!(list_drafts(&deps, None), vec![other_draft]);
+    let err = draft_msg(&mut deps, &env, VOTING1, publish).unwrap_err();
+    assert_eq!(err, ContractError::DraftNotFound(draft_id));
+}
+
+#[test]
+fn drafts_expire() {
+    let (mut deps, env) = setup();
+
+    let trusted_circle = query_trusted_circle(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(trusted_circle.draft_period, 7);
+    let draft_id = create_draft(&mut deps, &env, VOTING1).unwrap();
+    assert_eq!(
+        query_draft(&deps, draft_id).expires,
+        env.block.time.plus_seconds(7 * DAY)
+    );
+
+    let env = later(&env, 7 * DAY);
+    let err = draft_msg(
+        &mut deps,
+        &env,
+        VOTING2,
+        ExecuteMsg::CosponsorDraft { draft_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DraftExpired(draft_id));
+    let err = draft_msg(
+        &mut deps,
+        &env,
+        VOTING1,
+        ExecuteMsg::PublishDraft { draft_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DraftExpired(draft_id));
+
+    let mut deps = mock_deps_tgrade();
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(Some(0)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDraftPeriod(0));
+}
@@ -0,0 +1,287 @@
+use cosmwasm_std::Event;
+use tg_bindings::{request_privileges, Privilege, PrivilegeChangeMsg, TgradeSudoMsg};
+use tg_bindings_test::mock_deps_tgrade;
+
+use super::*;
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+#[test]
+fn requests_end_blocker_privilege_when_promoted() {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        TgradeSudoMsg::PrivilegeChange(PrivilegeChangeMsg::Promoted {}),
+    )
+    .unwrap();
+    assert_eq!(res.messages, request_privileges(&[Privilege::EndBlocker]));
+
+    let err = sudo(
+        deps.as_mut(),
+        mock_env(),
+        TgradeSudoMsg::PrivilegeChange(PrivilegeChangeMsg::Demoted {}),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnknownSudoMsg {});
+}
+
+#[test]
+fn end_block_promotes_batches() {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+
+    // Nothing to do during the grace period
+    let res = sudo(deps.as_mut(), later(&env, 100), TgradeSudoMsg::EndBlock {}).unwrap();
+    assert!(res.events.is_empty());
+    assert_voting(&deps, Some(1), Some(0), Some(0), None, None);
+
+    // Batch is promoted without anyone calling `CheckPending`
+    let grace_period = VOTING_PERIOD as u64 * 86_400;
+    let res = sudo(
+        deps.as_mut(),
+        later(&env, grace_period + 1),
+        TgradeSudoMsg::EndBlock {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new("promoted")
+                .add_attribute("proposal", PROPOSAL_ID_1.to_string())
+                .add_attribute("member", VOTING1),
+            Event::new("pending_work")
+                .add_attribute("checked", "2")
+                .add_attribute("limit_reached", "false")
+        ]
+    );
+    assert_voting(&deps, Some(1), Some(1), Some(0), None, None);
+
+    // And only once
+    let res = sudo(
+        deps.as_mut(),
+        later(&env, grace_period + 10),
+        TgradeSudoMsg::EndBlock {},
+    )
+    .unwrap();
+    assert!(res.events.is_empty());
+}
+
+#[test]
+fn end_block_closes_expired_proposals() {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    // Get a second voter, so a single yes vote is not enough for passing
+    let env = mock_env();
+    proposal_add_voting_members(
+        deps.as_mut(),
+        env.clone(),
+        PROPOSAL_ID_1,
+        vec![VOTING1.into()],
+    )
+    .unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+    assert_voting(&deps, Some(1), Some(1), None, None, None);
+
+    let env = later(&env, 10);
+    let propose = |title: &str| ExecuteMsg::Propose {
+        title: title.into(),
+        description: "Pass or reject".into(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            remove: vec![],
+            add: vec![NONVOTING1.into()],
+        },
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(INIT_ADMIN, &[]),
+        propose("Rejected"),
+    )
+    .unwrap();
+    let rejected = parse_prop_id(&res.attributes);
+    let res = execute(
+        deps.as_mut(),
+        later(&env, 1),
+        mock_info(VOTING1, &[]),
+        propose("Passed"),
+    )
+    .unwrap();
+    let passed = parse_prop_id(&res.attributes);
+    execute(
+        deps.as_mut(),
+        later(&env, 2),
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::Vote {
+            proposal_id: passed,
+            vote: Vote::Yes,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        later(&env, 2),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::Vote {
+            proposal_id: rejected,
+            vote: Vote::No,
+        },
+    )
+    .unwrap();
+
+    // Still open before expiration
+    let voting_period = VOTING_PERIOD as u64 * 86_400;
+    sudo(
+        deps.as_mut(),
+        later(&env, voting_period - 1),
+        TgradeSudoMsg::EndBlock {},
+    )
+    .unwrap();
+    assert_eq!(
+        crate::state::PROPOSALS
+            .load(&deps.storage, rejected)
+            .unwrap()
+            .status,
+        Status::Open
+    );
+
+    // Rejected one is closed when it expires, passed one is left for execution
+    let res = sudo(
+        deps.as_mut(),
+        later(&env, voting_period + 1),
+        TgradeSudoMsg::EndBlock {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("proposals_closed").add_attribute("proposal", rejected.to_string())]
+    );
+    let status = |deps: &OwnedDeps<_, _, _, TgradeQuery>, id| {
+        crate::state::PROPOSALS
+            .load(&deps.storage, id)
+            .unwrap()
+            .status
+    };
+    assert_eq!(status(&deps, rejected), Status::Rejected);
+    assert_eq!(status(&deps, passed), Status::Passed);
+
+    // Closed proposals cannot be closed again
+    let err = execute(
+        deps.as_mut(),
+        later(&env, voting_period + 2),
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::Close {
+            proposal_id: rejected,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WrongCloseStatus {});
+}
+
+#[test]
+fn end_block_closes_proposals_expiring_together() {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    proposal_add_voting_members(
+        deps.as_mut(),
+        env.clone(),
+        PROPOSAL_ID_1,
+        vec![VOTING1.into()],
+    )
+    .unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+
+    // Both proposals are created in the same block, so they expire in the same second
+    let env = later(&env, 10);
+    let mut proposal_ids = vec![];
+    for title in ["First", "Second"] {
+        let msg = ExecuteMsg::Propose {
+            title: title.into(),
+            description: "Rejected".into(),
+            proposal: ProposalContent::AddRemoveNonVotingMembers {
+                remove: vec![],
+                add: vec![NONVOTING1.into()],
+            },
+            urgent: false,
+            secret: false,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+        let proposal_id = parse_prop_id(&res.attributes);
+        let msg = ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::No,
+        };
+        execute(deps.as_mut(), later(&env, 1), mock_info(VOTING1, &[]), msg).unwrap();
+        proposal_ids.push(proposal_id);
+    }
+
+    let voting_period = VOTING_PERIOD as u64 * 86_400;
+    let res = sudo(
+        deps.as_mut(),
+        later(&env, voting_period + 1),
+        TgradeSudoMsg::EndBlock {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("proposals_closed")
+            .add_attribute("proposal", proposal_ids[0].to_string())
+            .add_attribute("proposal", proposal_ids[1].to_string())]
+    );
+    for proposal_id in proposal_ids {
+        let prop = crate::state::PROPOSALS
+            .load(&deps.storage, proposal_id)
+            .unwrap();
+        assert_eq!(prop.status, Status::Rejected);
+    }
+}
@@ -0,0 +1,206 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use cosmwasm_std::SubMsg;
+use tg_bindings::TgradeMsg;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::RewardsResponse;
+use crate::state::{escrow_total, Punishment};
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+/// Trusted circle with three voters, all escrow paid in the reward denom
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+    assert_voting(&deps, Some(1), Some(1), Some(1), None, None);
+
+    set_balance(&mut deps, 3 * ESCROW_FUNDS);
+    (deps, later(&env, 10))
+}
+
+fn set_balance(deps: &mut TgradeDeps, amount: u128) {
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(amount, TRUSTED_CIRCLE_DENOM));
+}
+
+fn escrowed(deps: &TgradeDeps) -> u128 {
+    escrow_total(&deps.storage, TRUSTED_CIRCLE_DENOM)
+        .unwrap()
+        .u128()
+}
+
+fn rewards(deps: &TgradeDeps, env: &Env, msg: QueryMsg) -> u128 {
+    let res: RewardsResponse =
+        from_slice(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    res.rewards.amount.u128()
+}
+
+fn undistributed(deps: &TgradeDeps, env: &Env) -> u128 {
+    rewards(deps, env, QueryMsg::UndistributedRewards {})
+}
+
+fn withdrawable(deps: &TgradeDeps, env: &Env, owner: &str) -> u128 {
+    let msg = QueryMsg::WithdrawableRewards {
+        owner: owner.to_owned(),
+    };
+    rewards(deps, env, msg)
+}
+
+fn distribute(deps: &mut TgradeDeps, env: &Env) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::DistributeRewards {},
+    )
+}
+
+#[test]
+fn escrow_is_not_distributed() {
+    let (mut deps, env) = setup();
+    assert_eq!(escrowed(&deps), 3 * ESCROW_FUNDS);
+
+    // Only escrow in the contract, nothing to distribute
+    assert_eq!(undistributed(&deps, &env), 0);
+    let res = distribute(&mut deps, &env).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "0")));
+
+    // Rewards are sent in the same denom
+    set_balance(&mut deps, 3 * ESCROW_FUNDS + 900);
+    assert_eq!(undistributed(&deps, &env), 900);
+    let res = distribute(&mut deps, &env).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "900")));
+    for voter in [INIT_ADMIN, VOTING1, VOTING2] {
+        assert_eq!(withdrawable(&deps, &env, voter), 300);
+    }
+
+    // Withdrawing rewards doesn't touch escrow
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::WithdrawRewards {
+            owner: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_payment(res.messages, VOTING1, 300);
+    set_balance(&mut deps, 3 * ESCROW_FUNDS + 600);
+    assert_eq!(escrowed(&deps), 3 * ESCROW_FUNDS);
+    assert_eq!(undistributed(&deps, &env), 0);
+    let res = distribute(&mut deps, &env).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "0")));
+}
+
+#[test]
+fn slashed_escrow_is_released() {
+    let (mut deps, env) = setup();
+
+    // Burned escrow leaves the contract
+    let burn = Punishment::BurnEscrow {
+        member: VOTING1.into(),
+        slashing_percentage: Decimal::percent(50),
+        kick_out: false,
+    };
+    proposal_punish_members(deps.as_mut(), env.clone(), 2, &[burn]).unwrap();
+    assert_eq!(escrowed(&deps), 5 * ESCROW_FUNDS / 2);
+    set_balance(&mut deps, 5 * ESCROW_FUNDS / 2);
+    assert_eq!(undistributed(&deps, &env), 0);
+
+    // Rewarded escrow stays in the contract, but as withdrawable rewards
+    let reward = Punishment::RewardEscrow {
+        member: VOTING2.into(),
+        slashing_percentage: Decimal::percent(50),
+        kick_out: false,
+    };
+    proposal_punish_members(deps.as_mut(), env.clone(), 3, &[reward]).unwrap();
+    assert_eq!(escrowed(&deps), 2 * ESCROW_FUNDS);
+    assert_eq!(undistributed(&deps, &env), 0);
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), ESCROW_FUNDS / 2);
+
+    // And it is not distributed again
+    let res = distribute(&mut deps, &env).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "0")));
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), ESCROW_FUNDS / 2);
+}
+
+#[test]
+fn leaving_member_escrow_is_not_distributed() {
+    let (mut deps, env) = setup();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::LeaveTrustedCircle {},
+    )
+    .unwrap();
+
+    // Leaving member escrow is still held
+    assert_eq!(escrowed(&deps), 3 * ESCROW_FUNDS);
+    set_balance(&mut deps, 3 * ESCROW_FUNDS + 100);
+    let res = distribute(&mut deps, &env).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "100")));
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), 50);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 50);
+
+    // Until it is claimed
+    let env = later(&env, 2 * VOTING_PERIOD as u64 * 86_400);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::ReturnEscrow {},
+    )
+    .unwrap();
+    assert_payment(res.messages, VOTING1, ESCROW_FUNDS);
+    set_balance(&mut deps, 2 * ESCROW_FUNDS + 100);
+    assert_eq!(escrowed(&deps), 2 * ESCROW_FUNDS);
+    assert_eq!(undistributed(&deps, &env), 0);
+    let res = distribute(&mut deps, &env).unwrap();
+    assert!(res.attributes.contains(&attr("amount", "0")));
+}
+
+#[test]
+fn distribution_fails_when_escrow_not_covered() {
+    let (mut deps, env) = setup();
+
+    set_balance(&mut deps, 2 * ESCROW_FUNDS);
+    let err = distribute(&mut deps, &env).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BalanceTooLow {
+            balance: Uint128::new(2 * ESCROW_FUNDS),
+            reserved: Uint128::new(3 * ESCROW_FUNDS),
+        }
+    );
+}
+
+fn assert_payment(messages: Vec<SubMsg<TgradeMsg>>, to_addr: &str, amount: u128) {
+    assert_eq!(
+        messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: to_addr.to_owned(),
+            amount: coins(amount, TRUSTED_CIRCLE_DENOM),
+        })]
+    );
+}
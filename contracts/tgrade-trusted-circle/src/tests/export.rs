@@ -0,0 +1,349 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::{SnapshotEntry, SnapshotKey, StateSnapshot};
+
+const IMPORTER: &str = "importer";
+
+/// Trusted circle with two voters, a pending voter, a non-voting member, an executed proposal
+/// and distributed rewards
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![NONVOTING1.into()],
+        false,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let msg = ExecuteMsg::Propose {
+        title: "Add voters".to_owned(),
+        description: "".to_owned(),
+        proposal: ProposalContent::AddVotingMembers {
+            voters: vec![VOTING1.into(), VOTING2.into()],
+        },
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+    let proposal_id = parse_prop_id(&res.attributes);
+    let msg = ExecuteMsg::Execute { proposal_id };
+    execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(2 * ESCROW_FUNDS + 1000, TRUSTED_CIRCLE_DENOM),
+    );
+    let env = later(&env, 10);
+    let msg = ExecuteMsg::DistributeRewards {};
+    execute(deps.as_mut(), env.clone(), mock_info(NONMEMBER, &[]), msg).unwrap();
+
+    (deps, env)
+}
+
+fn instantiate_importer(deps: DepsMut<TgradeQuery>) -> Result<Response, ContractError> {
+    let msg = InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: 14,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: Some(IMPORTER.to_owned()),
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: "utgd".to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    instantiate(deps, mock_env(), mock_info(INIT_ADMIN, &[]), msg)
+}
+
+fn export_page(deps: &TgradeDeps, start_after: Option<SnapshotKey>) -> StateSnapshot {
+    let msg = QueryMsg::ExportState {
+        start_after,
+        limit: Some(2),
+    };
+    from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+fn export_all(deps: &TgradeDeps) -> Vec<StateSnapshot> {
+    let mut pages = vec![export_page(deps, None)];
+    while let Some(next) = pages.last().unwrap().next.clone() {
+        pages.push(export_page(deps, Some(next)));
+    }
+    pages
+}
+
+fn import(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    sender: &str,
+    snapshot: StateSnapshot,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ImportState { snapshot };
+    execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+}
+
+fn finish_import(deps: &mut TgradeDeps, env: &Env) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::FinishImport {};
+    execute(deps.as_mut(), env.clone(), mock_info(IMPORTER, &[]), msg)
+}
+
+#[test]
+fn export_and_import_state() {
+    let (deps, env) = setup();
+    let pages = export_all(&deps);
+    assert!(pages.len() > 1);
+    assert!(pages[0].header.is_some());
+    assert!(pages[1..].iter().all(|page| page.header.is_none()));
+    let entries: Vec<_> = pages.iter().flat_map(|page| &page.entries).collect();
+    assert!(matches!(entries[0], SnapshotEntry::Member { .. }));
+    assert!(matches!(
+        entries.last().unwrap(),
+        SnapshotEntry::Proposal { id: 1, .. }
+    ));
+
+    let mut imported = mock_deps_tgrade();
+    instantiate_importer(imported.as_mut()).unwrap();
+    for page in pages.clone() {
+        import(&mut imported, &env, IMPORTER, page).unwrap();
+    }
+
+    // Escrow and rewards have to be transferred first
+    let err = finish_import(&mut imported, &env).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSnapshot(_)));
+    imported.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(2 * ESCROW_FUNDS + 1000, TRUSTED_CIRCLE_DENOM),
+    );
+    finish_import(&mut imported, &env).unwrap();
+
+    assert_eq!(export_all(&imported), pages);
+    for msg in [
+        QueryMsg::ListMembers {
+            start_after: None,
+            limit: None,
+        },
+        QueryMsg::ListEscrows {
+            start_after: None,
+            limit: None,
+        },
+        QueryMsg::TotalPoints {},
+        QueryMsg::ListProposals {
+            start_after: None,
+            limit: None,
+        },
+        QueryMsg::WithdrawableRewards {
+            owner: VOTING1.to_owned(),
+        },
+        QueryMsg::UndistributedRewards {},
+        QueryMsg::RewardWeights {},
+    ] {
+        assert_eq!(
+            query(imported.as_ref(), env.clone(), msg.clone()).unwrap(),
+            query(deps.as_ref(), env.clone(), msg).unwrap()
+        );
+    }
+
+    // The pending voter can still pay in its escrow, as in the exported trusted circle
+    execute_deposit_escrow(
+        imported.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &escrow_funds()),
+    )
+    .unwrap();
+}
+
+#[test]
+fn only_import_messages_while_importing() {
+    let (deps, env) = setup();
+    let mut imported = mock_deps_tgrade();
+    instantiate_importer(imported.as_mut()).unwrap();
+
+    let msg = ExecuteMsg::Propose {
+        title: "Too early".to_owned(),
+        description: "".to_owned(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING2.into()],
+            remove: vec![],
+        },
+        urgent: false,
+        secret: false,
+    };
+    let err = execute(
+        imported.as_mut(),
+        env.clone(),
+        mock_info(IMPORTER, &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Importing {});
+
+    let mut pages = export_all(&deps).into_iter();
+    let first = pages.next().unwrap();
+    let second = pages.next().unwrap();
+    let err = import(&mut imported, &env, NONMEMBER, first.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized(_)));
+    let err = import(&mut imported, &env, IMPORTER, second.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSnapshot(_)));
+    import(&mut imported, &env, IMPORTER, first.clone()).unwrap();
+    let err = import(&mut imported, &env, IMPORTER, first).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSnapshot(_)));
+
+    // Not all members were imported
+    imported.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(2 * ESCROW_FUNDS + 1000, TRUSTED_CIRCLE_DENOM),
+    );
+    let err = finish_import(&mut imported, &env).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSnapshot(_)));
+
+    // Once finished, the state cannot be imported anymore
+    import(&mut imported, &env, IMPORTER, second.clone()).unwrap();
+    for page in pages {
+        import(&mut imported, &env, IMPORTER, page).unwrap();
+    }
+    finish_import(&mut imported, &env).unwrap();
+    let err = import(&mut imported, &env, IMPORTER, second).unwrap_err();
+    assert_eq!(err, ContractError::NotImporting {});
+}
+
+#[test]
+fn open_proposals_are_not_imported() {
+    let (mut deps, env) = setup();
+    // Two voters after the grace period, so this one stays open
+    let env = later(&env, VOTING_PERIOD as u64 * 86_400);
+    let msg = ExecuteMsg::CheckPending {};
+    execute(deps.as_mut(), env.clone(), mock_info(NONMEMBER, &[]), msg).unwrap();
+    let msg = ExecuteMsg::Propose {
+        title: "Open".to_owned(),
+        description: "".to_owned(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING2.into()],
+            remove: vec![],
+        },
+        urgent: false,
+        secret: false,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+
+    let mut imported = mock_deps_tgrade();
+    instantiate_importer(imported.as_mut()).unwrap();
+    let err = export_all(&deps)
+        .into_iter()
+        .try_for_each(|page| import(&mut imported, &env, IMPORTER, page).map(|_| ()))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSnapshot("Proposal 2 is still open for voting".to_owned())
+    );
+
+    // After it expires, it can
+    let env = later(&env, VOTING_PERIOD as u64 * 86_400);
+    let mut imported = mock_deps_tgrade();
+    instantiate_importer(imported.as_mut()).unwrap();
+    for page in export_all(&deps) {
+        import(&mut imported, &env, IMPORTER, page).unwrap();
+    }
+}
+
+#[test]
+fn batch_members_must_be_imported() {
+    let (deps, env) = setup();
+    let import_all = |pages: Vec<StateSnapshot>| {
+        let mut imported = mock_deps_tgrade();
+        instantiate_importer(imported.as_mut()).unwrap();
+        pages
+            .into_iter()
+            .try_for_each(|page| import(&mut imported, &env, IMPORTER, page).map(|_| ()))
+    };
+
+    // Without the pending voter
+    let mut pages = export_all(&deps);
+    for page in &mut pages {
+        page.entries.retain(
+            |entry| !matches!(entry, SnapshotEntry::Member { addr, .. } if addr == VOTING2),
+        );
+    }
+    let err = import_all(pages).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSnapshot(format!("Member {} of batch 1 is not imported", VOTING2))
+    );
+
+    // Pending in another batch
+    let mut pages = export_all(&deps);
+    for entry in pages.iter_mut().flat_map(|page| &mut page.entries) {
+        if let SnapshotEntry::Member { addr, escrow, .. } = entry {
+            if addr == VOTING2 {
+                escrow.status = MemberStatus::Pending { proposal_id: 2 };
+            }
+        }
+    }
+    let err = import_all(pages).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSnapshot(format!(
+            "Member {} of batch 1 is pending in another batch",
+            VOTING2
+        ))
+    );
+}
+
+#[test]
+fn passed_proposals_are_imported_rejected() {
+    let (mut deps, env) = setup();
+    // The only voter passes it right away
+    let msg = ExecuteMsg::Propose {
+        title: "Passed".to_owned(),
+        description: "".to_owned(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING2.into()],
+            remove: vec![],
+        },
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+    let proposal_id = parse_prop_id(&res.attributes);
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Passed);
+
+    let mut imported = mock_deps_tgrade();
+    instantiate_importer(imported.as_mut()).unwrap();
+    for page in export_all(&deps) {
+        import(&mut imported, &env, IMPORTER, page).unwrap();
+    }
+    imported.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(2 * ESCROW_FUNDS + 1000, TRUSTED_CIRCLE_DENOM),
+    );
+    finish_import(&mut imported, &env).unwrap();
+
+    let prop = query_proposal(imported.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Rejected);
+    let msg = ExecuteMsg::Execute { proposal_id };
+    let err = execute(imported.as_mut(), env, mock_info(INIT_ADMIN, &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::WrongExecuteStatus {});
+}
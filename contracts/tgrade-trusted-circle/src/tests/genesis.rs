@@ -2,12 +2,14 @@ use anyhow::Result as AnyResult;
 
 use cosmwasm_std::{coins, Addr, Decimal, Uint128};
 use cw_multi_test::Executor;
-use tg4::MemberListResponse;
+use tg4::{MemberListResponse, TotalPointsResponse};
 use tg_bindings_test::TgradeApp;
 
-use super::{parse_prop_id, suite::contract_trusted_circle};
+use super::parse_prop_id;
+use crate::multitest::contract_trusted_circle;
 use crate::{
-    msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    error::ContractError,
+    msg::{EscrowResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RewardWeightsResponse},
     state::ProposalContent,
     tests::TRUSTED_CIRCLE_DENOM,
 };
@@ -53,9 +55,21 @@ fn genesis_oc() {
                 threshold: Decimal::percent(50),
                 allow_end_early: true,
                 initial_members: vec![genesis_members[0].to_owned()],
+                initial_voters: vec![],
+                importer: None,
+                stake_contract: None,
+                membership_nft: None,
                 deny_list: None,
                 edit_trusted_circle_disabled: false,
                 reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+                payment_deadline: None,
+                remove_expired_pending: false,
+                reward_vesting_period: None,
+                reward_weights: None,
+                urgent_rules: None,
+                secret_rules: None,
+                guardian: None,
+                draft_period: None,
             },
             &coins(escrow_amount, TRUSTED_CIRCLE_DENOM),
             "oc-trusted-circle",
@@ -78,6 +92,8 @@ fn genesis_oc() {
                 title: "Add genesis members".to_owned(),
                 description: "Add genesis members".to_owned(),
                 proposal: ProposalContent::AddVotingMembers { voters },
+                urgent: false,
+                secret: false,
             },
             &[],
         )
@@ -125,3 +141,147 @@ fn genesis_oc() {
     assert_eq!(0, app.block_info().height);
     assert_eq!(voting_members, genesis_members);
 }
+
+#[test]
+fn genesis_initial_voters() {
+    let voters = ["member1", "member2", "member3"];
+    let member1 = Addr::unchecked(voters[0]);
+    let escrow_amount = 1_000_000;
+
+    let mut app = TgradeApp::new_genesis(voters[0]);
+    app.init_modules(|router, _, storage| {
+        router.bank.init_balance(
+            storage,
+            &member1,
+            coins(3 * escrow_amount + 1, TRUSTED_CIRCLE_DENOM),
+        )
+    })
+    .unwrap();
+
+    let contract_id = app.store_code(contract_trusted_circle());
+    let msg = InstantiateMsg {
+        name: "OC Trusted Cricle".to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(escrow_amount),
+        voting_period: 1,
+        quorum: Decimal::percent(50),
+        threshold: Decimal::percent(50),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: voters[1..].iter().copied().map(str::to_owned).collect(),
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+
+    // Escrow of every voter must be sent
+    let err = app
+        .instantiate_contract(
+            contract_id,
+            member1.clone(),
+            &msg,
+            &coins(2 * escrow_amount, TRUSTED_CIRCLE_DENOM),
+            "oc-trusted-circle",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientFunds(Uint128::new(2 * escrow_amount)),
+        err.downcast().unwrap()
+    );
+
+    // And nothing more
+    let err = app
+        .instantiate_contract(
+            contract_id,
+            member1.clone(),
+            &msg,
+            &coins(3 * escrow_amount + 1, TRUSTED_CIRCLE_DENOM),
+            "oc-trusted-circle",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ExcessFunds(Uint128::new(3 * escrow_amount + 1)),
+        err.downcast().unwrap()
+    );
+
+    let mut duplicated = msg.clone();
+    duplicated.initial_voters.push(voters[0].to_owned());
+    let err = app
+        .instantiate_contract(
+            contract_id,
+            member1.clone(),
+            &duplicated,
+            &coins(3 * escrow_amount, TRUSTED_CIRCLE_DENOM),
+            "oc-trusted-circle",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DuplicateInitialVoter(voters[0].to_owned()),
+        err.downcast().unwrap()
+    );
+
+    let contract = app
+        .instantiate_contract(
+            contract_id,
+            member1,
+            &msg,
+            &coins(3 * escrow_amount, TRUSTED_CIRCLE_DENOM),
+            "oc-trusted-circle",
+            None,
+        )
+        .unwrap();
+
+    let voting_members: MemberListResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::ListVoters {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    let voting_members: Vec<_> = voting_members
+        .members
+        .iter()
+        .map(|member| member.addr.as_str())
+        .collect();
+    assert_eq!(voting_members, voters);
+
+    let total: TotalPointsResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::TotalPoints {})
+        .unwrap();
+    assert_eq!(total.points, 3);
+    let rewards: RewardWeightsResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::RewardWeights {})
+        .unwrap();
+    assert_eq!(rewards.total_points, 3 * rewards.weights.voting);
+    for voter in voters {
+        let escrow: EscrowResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::Escrow {
+                    addr: voter.to_owned(),
+                },
+            )
+            .unwrap();
+        assert_eq!(escrow.unwrap().paid, Uint128::new(escrow_amount));
+    }
+}
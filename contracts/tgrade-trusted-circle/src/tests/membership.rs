@@ -0,0 +1,113 @@
+use cosmwasm_std::{coins, Decimal};
+
+use super::suite::{Suite, SuiteBuilder};
+use crate::state::{ProposalContent, Punishment};
+
+const MEMBER: &str = "member";
+const NON_VOTING: &str = "non-voting";
+
+/// Trusted circle with membership tokens, with the owner as voter and `NON_VOTING` as non-voting
+/// member. `MEMBER` is proposed as voter, and can pay in its escrow twice
+fn setup() -> Suite {
+    let mut suite = SuiteBuilder::new()
+        .with_membership_nft()
+        .with_funds(MEMBER, 2_000_000)
+        .build();
+    let owner = suite.owner();
+    suite
+        .propose_modify_non_voting(&owner, "Add member", "", &[NON_VOTING], &[])
+        .unwrap();
+    suite
+        .propose_add_voting(&owner, "Add voter", "", &[MEMBER])
+        .unwrap();
+    let client = suite.client();
+    for proposal_id in [1, 2] {
+        suite
+            .execute(&owner, client.execute(proposal_id).unwrap())
+            .unwrap();
+    }
+    suite
+}
+
+/// `MEMBER` pays in its escrow, and becomes a voter
+fn promote_member(suite: &mut Suite) {
+    let msg = suite
+        .client()
+        .deposit_escrow(coins(1_000_000, "utgd"))
+        .unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+}
+
+/// Passes and executes a proposal slashing half the escrow of `MEMBER`
+fn punish_member(suite: &mut Suite, kick_out: bool) {
+    let owner = suite.owner();
+    let client = suite.client();
+    let msg = client
+        .propose(
+            "Punish member",
+            "",
+            ProposalContent::PunishMembers(vec![Punishment::BurnEscrow {
+                member: MEMBER.to_owned(),
+                slashing_percentage: Decimal::percent(50),
+                kick_out,
+            }]),
+            false,
+            false,
+        )
+        .unwrap();
+    suite.execute(&owner, msg).unwrap();
+    suite.execute(&owner, client.execute(3).unwrap()).unwrap();
+}
+
+#[test]
+fn initial_voters_get_a_token() {
+    let suite = setup();
+
+    assert_eq!(suite.membership_status(&suite.owner()).unwrap(), "Voting");
+    assert_eq!(suite.membership_status(NON_VOTING), None);
+    assert_eq!(suite.membership_status(MEMBER), None);
+}
+
+#[test]
+fn promoted_voters_get_a_token() {
+    let mut suite = setup();
+
+    promote_member(&mut suite);
+    assert_eq!(suite.membership_status(MEMBER).unwrap(), "Voting");
+}
+
+#[test]
+fn demoted_voters_token_is_updated() {
+    let mut suite = setup();
+    promote_member(&mut suite);
+
+    punish_member(&mut suite, false);
+    assert_eq!(suite.membership_status(MEMBER).unwrap(), "Pending");
+
+    // Paying the slashed escrow back promotes the member again
+    let msg = suite
+        .client()
+        .deposit_escrow(coins(500_000, "utgd"))
+        .unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+    assert_eq!(suite.membership_status(MEMBER).unwrap(), "Voting");
+}
+
+#[test]
+fn kicked_out_voters_token_is_burned() {
+    let mut suite = setup();
+    promote_member(&mut suite);
+
+    punish_member(&mut suite, true);
+    assert_eq!(suite.membership_status(MEMBER), None);
+}
+
+#[test]
+fn leaving_voters_token_is_burned() {
+    let mut suite = setup();
+    promote_member(&mut suite);
+
+    let msg = suite.client().leave_trusted_circle().unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+    assert_eq!(suite.membership_status(MEMBER), None);
+}
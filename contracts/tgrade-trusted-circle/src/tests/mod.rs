@@ -1,10 +1,29 @@
 #![cfg(test)]
 
 mod bdd_tests;
+mod client;
+mod committees;
+mod contract_permissions;
 mod deny_list;
+mod drafts;
+mod end_block;
+mod escrow_rewards;
+mod export;
 mod genesis;
+mod membership;
+mod pause;
+mod pending_work;
+mod poll;
+mod reward_weights;
+mod secret;
+mod signed_votes;
+mod stake;
 mod suite;
+mod treasury;
 mod unit_tests;
+mod urgent;
+mod vesting;
+mod withdrawal;
 
 use std::cmp::PartialEq;
 use std::fmt::Debug;
@@ -86,9 +105,21 @@ fn do_instantiate(
         threshold: Decimal::percent(60),
         allow_end_early: true,
         initial_members,
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
         deny_list: None,
         edit_trusted_circle_disabled,
         reward_denom: "utgd".to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     };
     instantiate(deps, mock_env(), info, msg)
 }
@@ -116,7 +147,7 @@ fn assert_voting<S: Storage, A: Api, Q: Querier>(
     // this is only valid if we are not doing a historical query
     if height.is_none() {
         // compute expected metrics
-        let points = vec![
+        let points = [
             voting0_points,
             voting1_points,
             voting2_points,
@@ -164,7 +195,7 @@ fn assert_nonvoting<S: Storage, A: Api, Q: Querier>(
     // this is only valid if we are not doing a historical query
     if height.is_none() {
         // compute expected metrics
-        let points = vec![nonvoting1_points, nonvoting2_points, nonvoting3_points];
+        let points = [nonvoting1_points, nonvoting2_points, nonvoting3_points];
         let count = points.iter().filter(|x| x.is_some()).count();
 
         let nonvoting = list_non_voting_members(deps.as_ref(), None, None)
@@ -267,6 +298,8 @@ fn assert_can_vote(
             remove: vec![],
             add: vec!["new guy".into()],
         },
+        urgent: false,
+        secret: false,
     };
     let res = execute(deps.branch(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
     let proposal_id = parse_prop_id(&res.attributes);
@@ -0,0 +1,394 @@
+use cosmwasm_std::Addr;
+use tg_bindings::TgradeSudoMsg;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::state::UrgentRules;
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+const HOUR: u64 = 3600;
+const DAY: u64 = 24 * HOUR;
+const GUARDIAN: &str = "guardian";
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: Some(UrgentRules {
+            voting_period: 6,
+            quorum: Decimal::percent(60),
+            threshold: Decimal::percent(75),
+            max_proposals: 1,
+            limit_period: 7,
+        }),
+        secret_rules: None,
+        guardian: Some(GUARDIAN.to_owned()),
+        draft_period: None,
+    }
+}
+
+/// Trusted circle with four voters and a guardian, allowing urgent proposals
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(),
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into(), VOTING3.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2, VOTING3] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+
+    (deps, later(&env, 10))
+}
+
+fn propose(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    proposer: &str,
+    proposal: ProposalContent,
+    urgent: bool,
+) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Emergency".to_owned(),
+        description: "Keys may be compromised".to_owned(),
+        proposal,
+        urgent,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(proposer, &[]), msg)?;
+    Ok(parse_prop_id(&res.attributes))
+}
+
+/// Every voter but the proposer votes yes
+fn pass(deps: &mut TgradeDeps, env: &Env, proposer: &str, proposal_id: u64) {
+    for voter in [INIT_ADMIN, VOTING1, VOTING2, VOTING3] {
+        if voter == proposer {
+            continue;
+        }
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &[]),
+            ExecuteMsg::Vote {
+                proposal_id,
+                vote: Vote::Yes,
+            },
+        )
+        .unwrap();
+    }
+}
+
+fn execute_proposal(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::Execute { proposal_id },
+    )
+}
+
+fn pause(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    sender: &str,
+    hours: u32,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender, &[]),
+        ExecuteMsg::Pause { hours },
+    )
+}
+
+fn add_non_voting() -> ProposalContent {
+    ProposalContent::AddRemoveNonVotingMembers {
+        add: vec![NONVOTING1.into()],
+        remove: vec![],
+    }
+}
+
+#[test]
+fn guardian_pause_blocks_execution_membership_and_escrows() {
+    let (mut deps, env) = setup();
+
+    let passed = propose(&mut deps, &env, INIT_ADMIN, add_non_voting(), false).unwrap();
+    pass(&mut deps, &env, INIT_ADMIN, passed);
+    let open = propose(&mut deps, &env, VOTING1, add_non_voting(), false).unwrap();
+
+    let err = pause(&mut deps, &env, VOTING1, 24).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Only the guardian can pause the trusted circle".to_owned())
+    );
+    let err = pause(&mut deps, &env, GUARDIAN, 0).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPausePeriod(0));
+    let err = pause(&mut deps, &env, GUARDIAN, 31 * 24).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPausePeriod(31 * 24));
+    pause(&mut deps, &env, GUARDIAN, 24).unwrap();
+
+    let until = env.block.time.plus_seconds(24 * HOUR);
+    let trusted_circle = query_trusted_circle(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(trusted_circle.guardian, Some(Addr::unchecked(GUARDIAN)));
+    assert_eq!(trusted_circle.paused_until, Some(until));
+
+    // The guardian cannot extend the pause
+    let err = pause(&mut deps, &env, GUARDIAN, 48).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyPaused { until });
+
+    // Proposals can neither be created nor executed
+    let paused = ContractError::Paused { until };
+    let err = propose(&mut deps, &env, VOTING1, add_non_voting(), false).unwrap_err();
+    assert_eq!(err, paused);
+    let err = execute_proposal(&mut deps, &env, passed).unwrap_err();
+    assert_eq!(err, paused);
+
+    // Escrows can neither be deposited nor withdrawn, and members cannot leave
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+        ExecuteMsg::DepositEscrow {},
+    )
+    .unwrap_err();
+    assert_eq!(err, paused);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::ReturnEscrow {},
+    )
+    .unwrap_err();
+    assert_eq!(err, paused);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &[]),
+        ExecuteMsg::LeaveTrustedCircle {},
+    )
+    .unwrap_err();
+    assert_eq!(err, paused);
+
+    // Voting goes on
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &[]),
+        ExecuteMsg::Vote {
+            proposal_id: open,
+            vote: Vote::Yes,
+        },
+    )
+    .unwrap();
+
+    // Until the pause times out
+    let env = later(&env, 24 * HOUR);
+    let trusted_circle = query_trusted_circle(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(trusted_circle.paused_until, None);
+    execute_proposal(&mut deps, &env, passed).unwrap();
+    propose(&mut deps, &env, VOTING1, add_non_voting(), false).unwrap();
+}
+
+#[test]
+fn urgent_proposals_pause_and_unpause() {
+    let (mut deps, env) = setup();
+
+    let proposal_id = propose(
+        &mut deps,
+        &env,
+        VOTING1,
+        ProposalContent::Pause { hours: 72 },
+        true,
+    )
+    .unwrap();
+    pass(&mut deps, &env, VOTING1, proposal_id);
+    execute_proposal(&mut deps, &env, proposal_id).unwrap();
+
+    let until = env.block.time.plus_seconds(72 * HOUR);
+    let trusted_circle = query_trusted_circle(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(trusted_circle.paused_until, Some(until));
+    let err = propose(&mut deps, &env, VOTING2, add_non_voting(), false).unwrap_err();
+    assert_eq!(err, ContractError::Paused { until });
+
+    // Only a vote lifts the pause early
+    let proposal_id = propose(&mut deps, &env, VOTING2, ProposalContent::Unpause {}, true).unwrap();
+    pass(&mut deps, &env, VOTING2, proposal_id);
+    execute_proposal(&mut deps, &env, proposal_id).unwrap();
+
+    let trusted_circle = query_trusted_circle(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(trusted_circle.paused_until, None);
+    propose(&mut deps, &env, VOTING2, add_non_voting(), false).unwrap();
+}
+
+#[test]
+fn vote_extends_guardian_pause() {
+    let (mut deps, env) = setup();
+
+    pause(&mut deps, &env, GUARDIAN, 1).unwrap();
+    let proposal_id = propose(
+        &mut deps,
+        &env,
+        VOTING1,
+        ProposalContent::Pause { hours: 10 },
+        true,
+    )
+    .unwrap();
+    pass(&mut deps, &env, VOTING1, proposal_id);
+    execute_proposal(&mut deps, &env, proposal_id).unwrap();
+
+    let until = env.block.time.plus_seconds(10 * HOUR);
+    let env = later(&env, 5 * HOUR);
+    let trusted_circle = query_trusted_circle(deps.as_ref(), env.clone()).unwrap();
+    assert_eq!(trusted_circle.paused_until, Some(until));
+
+    // Pause proposals may still be created while paused, but are limited too
+    let err = propose(
+        &mut deps,
+        &env,
+        VOTING1,
+        ProposalContent::Pause { hours: 0 },
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPausePeriod(0));
+}
+
+#[test]
+fn pause_proposals_must_be_urgent() {
+    let (mut deps, env) = setup();
+
+    let err = propose(
+        &mut deps,
+        &env,
+        VOTING1,
+        ProposalContent::Pause { hours: 10 },
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PauseNotUrgent {});
+}
+
+#[test]
+fn pause_blocks_pending_members_and_spend_claims() {
+    let (mut deps, env) = setup();
+
+    // A vested treasury spend, and a member paying its escrow, promoted once the grace period
+    // of its batch ends
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &coins(1000, TRUSTED_CIRCLE_DENOM)),
+        ExecuteMsg::FundTreasury {},
+    )
+    .unwrap();
+    let spend_id = propose(
+        &mut deps,
+        &env,
+        INIT_ADMIN,
+        ProposalContent::TreasurySpend {
+            recipient: NONMEMBER.to_owned(),
+            amount: coin(1000, TRUSTED_CIRCLE_DENOM),
+            vesting: Some(10),
+        },
+        false,
+    )
+    .unwrap();
+    pass(&mut deps, &env, INIT_ADMIN, spend_id);
+    execute_proposal(&mut deps, &env, spend_id).unwrap();
+    let proposal_id = propose(
+        &mut deps,
+        &env,
+        INIT_ADMIN,
+        ProposalContent::AddVotingMembers {
+            voters: vec![NONVOTING1.into(), NONVOTING2.into()],
+        },
+        false,
+    )
+    .unwrap();
+    pass(&mut deps, &env, INIT_ADMIN, proposal_id);
+    execute_proposal(&mut deps, &env, proposal_id).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONVOTING1, &escrow_funds()),
+    )
+    .unwrap();
+
+    let env = later(&env, VOTING_PERIOD as u64 * DAY - HOUR);
+    pause(&mut deps, &env, GUARDIAN, 24).unwrap();
+    let until = env.block.time.plus_seconds(24 * HOUR);
+    let env = later(&env, 2 * HOUR);
+
+    let paused = ContractError::Paused { until };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::CheckPending {},
+    )
+    .unwrap_err();
+    assert_eq!(err, paused);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::ClaimSpend { spend_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, paused);
+    let res = sudo(deps.as_mut(), env.clone(), TgradeSudoMsg::EndBlock {}).unwrap();
+    assert_eq!(res.events, vec![]);
+    let escrow = query_escrow(deps.as_ref(), NONVOTING1.into())
+        .unwrap()
+        .unwrap();
+    assert_eq!(escrow.status, MemberStatus::PendingPaid { proposal_id });
+
+    // Until the pause times out
+    let env = later(&env, 24 * HOUR);
+    sudo(deps.as_mut(), env.clone(), TgradeSudoMsg::EndBlock {}).unwrap();
+    let escrow = query_escrow(deps.as_ref(), NONVOTING1.into())
+        .unwrap()
+        .unwrap();
+    assert_eq!(escrow.status, MemberStatus::Voting {});
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::ClaimSpend { spend_id },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
@@ -0,0 +1,231 @@
+use cosmwasm_std::{Addr, Event};
+use tg_bindings_test::mock_deps_tgrade;
+
+use super::*;
+use crate::msg::PendingWorkResponse;
+use crate::state::{batches, EscrowStatus, ESCROWS};
+use crate::tests::bdd_tests::{PROPOSAL_ID_1, PROPOSAL_ID_2};
+
+const MEMBERS: usize = PENDING_WORK_LIMIT + 50;
+
+fn member(i: usize) -> String {
+    format!("member{:03}", i)
+}
+
+fn pending_work(deps: Deps<TgradeQuery>, env: Env) -> PendingWorkResponse {
+    query_pending_work(deps, env).unwrap()
+}
+
+fn check_pending(deps: DepsMut<TgradeQuery>, env: Env) -> Vec<Event> {
+    execute(
+        deps,
+        env,
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::CheckPending {},
+    )
+    .unwrap()
+    .events
+}
+
+fn progress(checked: usize, limit_reached: bool) -> Event {
+    Event::new("pending_work")
+        .add_attribute("checked", checked.to_string())
+        .add_attribute("limit_reached", limit_reached.to_string())
+}
+
+/// Adds `MEMBERS` voting members in a single batch, and has all of them pay in their escrow
+fn setup_large_batch(mut deps: DepsMut<TgradeQuery>, env: &Env) {
+    do_instantiate(
+        deps.branch(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let add = (0..MEMBERS).map(member).collect();
+    proposal_add_voting_members(deps.branch(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for i in 0..MEMBERS {
+        let info = mock_info(&member(i), &escrow_funds());
+        execute_deposit_escrow(deps.branch(), env.clone(), info).unwrap();
+    }
+}
+
+#[test]
+fn large_batch_promotion_is_resumed() {
+    let mut deps = mock_deps_tgrade();
+    let env = mock_env();
+    setup_large_batch(deps.as_mut(), &env);
+
+    // The last payment only promotes the payer, plus as many members as the limit allows
+    assert_eq!(
+        query_total_points(deps.as_ref()).unwrap().points,
+        PENDING_WORK_LIMIT as u64 + 2
+    );
+    // The rest is picked up before the grace period ends, which is left as it was (setting the
+    // payment deadline)
+    let grace_ends_at = env
+        .block
+        .time
+        .plus_seconds(VOTING_PERIOD as u64 * 86_400)
+        .seconds();
+    let batch = batches().load(&deps.storage, PROPOSAL_ID_1).unwrap();
+    assert_eq!(batch.grace_ends_at, grace_ends_at);
+    assert_eq!(batch.promoting_since, Some(env.block.time.seconds()));
+    assert_eq!(
+        pending_work(deps.as_ref(), env.clone()),
+        PendingWorkResponse {
+            escrow_members: 0,
+            promotion_batches: 1,
+            promotion_members: (MEMBERS - PENDING_WORK_LIMIT) as u64,
+            expiry_batches: 0,
+            expiry_members: 0,
+            capped: false,
+        }
+    );
+
+    // The next check finishes the job, without waiting for the grace period
+    let events = check_pending(deps.as_mut(), env.clone());
+    assert_eq!(
+        events.last().unwrap(),
+        &progress(MEMBERS - PENDING_WORK_LIMIT, false)
+    );
+    assert_eq!(
+        query_total_points(deps.as_ref()).unwrap().points,
+        MEMBERS as u64 + 1
+    );
+    assert_eq!(
+        pending_work(deps.as_ref(), env.clone()),
+        PendingWorkResponse {
+            escrow_members: 0,
+            promotion_batches: 0,
+            promotion_members: 0,
+            expiry_batches: 0,
+            expiry_members: 0,
+            capped: false,
+        }
+    );
+
+    let batch = batches().load(&deps.storage, PROPOSAL_ID_1).unwrap();
+    assert!(batch.batch_promoted);
+    assert_eq!(batch.grace_ends_at, grace_ends_at);
+    assert_eq!(batch.promoting_since, None);
+
+    // Nothing left to do
+    let events = check_pending(deps.as_mut(), env);
+    assert!(events.is_empty());
+}
+
+#[test]
+fn escrow_increase_is_enforced_in_steps() {
+    let mut deps = mock_deps_tgrade();
+    let env = mock_env();
+    setup_large_batch(deps.as_mut(), &env);
+
+    check_pending(deps.as_mut(), env.clone());
+    let voters = MEMBERS as u64 + 1;
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, voters);
+
+    // Double the escrow
+    let adjustments = TrustedCircleAdjustments {
+        name: None,
+        escrow_amount: Some(Uint128::new(ESCROW_FUNDS * 2)),
+        voting_period: None,
+        quorum: None,
+        threshold: None,
+        allow_end_early: None,
+        edit_trusted_circle_disabled: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    proposal_edit_trusted_circle(deps.as_mut(), env.clone(), PROPOSAL_ID_2, adjustments).unwrap();
+
+    let env = later(&env, VOTING_PERIOD as u64 * 86_400);
+    assert_eq!(
+        pending_work(deps.as_ref(), env.clone()).escrow_members,
+        voters
+    );
+
+    // Only the first members are demoted on the first check
+    let events = check_pending(deps.as_mut(), env.clone());
+    assert_eq!(events.last().unwrap(), &progress(PENDING_WORK_LIMIT, true));
+    assert_eq!(
+        query_total_points(deps.as_ref()).unwrap().points,
+        voters - PENDING_WORK_LIMIT as u64
+    );
+    assert_eq!(
+        pending_work(deps.as_ref(), env.clone()).escrow_members,
+        voters - PENDING_WORK_LIMIT as u64
+    );
+    // The new escrow is already required for anyone paying in
+    let trusted_circle = query_trusted_circle(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(trusted_circle.escrow_amount, Uint128::new(ESCROW_FUNDS * 2));
+    assert_eq!(trusted_circle.escrow_pending, None);
+
+    // The second check demotes the rest, into the same batch
+    check_pending(deps.as_mut(), env.clone());
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 0);
+    assert_eq!(pending_work(deps.as_ref(), env.clone()).escrow_members, 0);
+    let batch = batches().load(&deps.storage, PROPOSAL_ID_2).unwrap();
+    assert_eq!(batch.members.len() as u64, voters);
+    assert_eq!(batch.waiting_escrow as u64, voters);
+
+    // Demoted members are promoted again once they top up their escrow
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(&member(0), &escrow_funds()),
+    )
+    .unwrap();
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 1);
+    assert_eq!(
+        query_member(deps.as_ref(), member(0), None).unwrap().points,
+        Some(1)
+    );
+    assert_eq!(
+        ESCROWS
+            .load(&deps.storage, &Addr::unchecked(member(1)))
+            .unwrap()
+            .status,
+        MemberStatus::Pending {
+            proposal_id: PROPOSAL_ID_2
+        }
+    );
+}
+
+#[test]
+fn pending_work_query_is_bounded() {
+    let mut deps = mock_deps_tgrade();
+    let env = mock_env();
+    setup_large_batch(deps.as_mut(), &env);
+    check_pending(deps.as_mut(), env.clone());
+
+    // Many more members than the query counts
+    for i in MEMBERS..PENDING_WORK_QUERY_LIMIT + 10 {
+        let addr = Addr::unchecked(member(i));
+        ESCROWS
+            .save(&mut deps.storage, &addr, &EscrowStatus::non_voting())
+            .unwrap();
+    }
+    let adjustments = TrustedCircleAdjustments {
+        name: None,
+        escrow_amount: Some(Uint128::new(ESCROW_FUNDS * 2)),
+        voting_period: None,
+        quorum: None,
+        threshold: None,
+        allow_end_early: None,
+        edit_trusted_circle_disabled: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    proposal_edit_trusted_circle(deps.as_mut(), env.clone(), PROPOSAL_ID_2, adjustments).unwrap();
+
+    let env = later(&env, VOTING_PERIOD as u64 * 86_400);
+    let work = pending_work(deps.as_ref(), env);
+    assert_eq!(work.escrow_members, PENDING_WORK_QUERY_LIMIT as u64);
+    assert!(work.capped);
+}
@@ -0,0 +1,226 @@
+use tg_bindings::TgradeSudoMsg;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+use tg_voting_contract::ContractError as VotingContractError;
+
+use super::*;
+use crate::msg::PollResults;
+use crate::state::{PollMethod, PROPOSALS};
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+const DAY: u64 = 86_400;
+
+/// Trusted circle with four voters
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into(), VOTING3.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2, VOTING3] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+
+    (deps, later(&env, 10))
+}
+
+fn propose(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    proposal: ProposalContent,
+) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Vendor".to_owned(),
+        description: "Which one should we hire?".to_owned(),
+        proposal,
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg)?;
+    Ok(parse_prop_id(&res.attributes))
+}
+
+fn poll(options: &[&str], max_choices: u32) -> ProposalContent {
+    ProposalContent::Poll {
+        options: options.iter().map(|o| o.to_string()).collect(),
+        max_choices,
+        method: PollMethod::RankedChoice,
+    }
+}
+
+fn vote_poll(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    voter: &str,
+    proposal_id: u64,
+    choices: &[u32],
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(voter, &[]),
+        ExecuteMsg::VotePoll {
+            proposal_id,
+            choices: choices.to_vec(),
+        },
+    )
+}
+
+#[test]
+fn ranked_choice_poll() {
+    let (mut deps, env) = setup();
+
+    let proposal_id = propose(&mut deps, &env, poll(&["alice", "bob", "carl"], 2)).unwrap();
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.votes.total(), 0);
+    assert_eq!(
+        prop.poll_results,
+        Some(PollResults {
+            rounds: vec![vec![0, 0, 0]],
+            winner: None,
+        })
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PollBallotRequired {});
+    let err = vote_poll(&mut deps, &env, VOTING1, proposal_id, &[0, 1, 2]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPollChoices("between 1 and 2 options must be chosen".to_owned())
+    );
+
+    vote_poll(&mut deps, &env, VOTING1, proposal_id, &[0]).unwrap();
+    vote_poll(&mut deps, &env, VOTING2, proposal_id, &[1, 0]).unwrap();
+    vote_poll(&mut deps, &env, VOTING3, proposal_id, &[2, 1]).unwrap();
+    vote_poll(&mut deps, &env, INIT_ADMIN, proposal_id, &[1]).unwrap();
+    let err = vote_poll(&mut deps, &env, INIT_ADMIN, proposal_id, &[2]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::VotingContract(VotingContractError::AlreadyVoted {})
+    );
+
+    let votes = list_votes_by_proposal(deps.as_ref(), proposal_id, None, None).unwrap();
+    let vote = votes.votes.iter().find(|v| v.voter == VOTING3).unwrap();
+    assert_eq!(vote.choices, Some(vec![2, 1]));
+
+    // Polls are only decided once expired
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Open);
+    assert_eq!(
+        prop.poll_results,
+        Some(PollResults {
+            rounds: vec![vec![1, 2, 1], vec![0, 3, 0]],
+            winner: Some(1),
+        })
+    );
+
+    let env = later(&env, VOTING_PERIOD as u64 * DAY);
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Executed);
+}
+
+#[test]
+fn polls_are_not_executed() {
+    let (mut deps, env) = setup();
+
+    let proposal_id = propose(&mut deps, &env, poll(&["yes", "no"], 1)).unwrap();
+    for voter in [VOTING1, VOTING2, VOTING3] {
+        vote_poll(&mut deps, &env, voter, proposal_id, &[0]).unwrap();
+    }
+    let execute_poll = |deps: &mut TgradeDeps, env: &Env| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(VOTING1, &[]),
+            ExecuteMsg::Execute { proposal_id },
+        )
+        .unwrap_err()
+    };
+    assert_eq!(
+        execute_poll(&mut deps, &env),
+        ContractError::WrongExecuteStatus {}
+    );
+
+    // Concluded by the end blocker once expired
+    let env = later(&env, VOTING_PERIOD as u64 * DAY);
+    assert_eq!(
+        execute_poll(&mut deps, &env),
+        ContractError::WrongExecuteStatus {}
+    );
+    sudo(deps.as_mut(), env.clone(), TgradeSudoMsg::EndBlock {}).unwrap();
+    let prop = PROPOSALS.load(&deps.storage, proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Executed);
+    assert_eq!(
+        execute_poll(&mut deps, &env),
+        ContractError::WrongExecuteStatus {}
+    );
+}
+
+#[test]
+fn poll_requires_quorum() {
+    let (mut deps, env) = setup();
+
+    let proposal_id = propose(&mut deps, &env, poll(&["yes", "no"], 1)).unwrap();
+    vote_poll(&mut deps, &env, VOTING1, proposal_id, &[0]).unwrap();
+
+    let env = later(&env, VOTING_PERIOD as u64 * DAY);
+    let prop = query_proposal(deps.as_ref(), env, proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Rejected);
+    assert_eq!(prop.poll_results.unwrap().winner, Some(0));
+}
+
+#[test]
+fn invalid_polls() {
+    let (mut deps, env) = setup();
+
+    let err = propose(&mut deps, &env, poll(&["alone"], 1)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPoll("must have between 2 and 32 options".to_owned())
+    );
+    let err = propose(&mut deps, &env, poll(&["same", "same "], 1)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPoll("options must be non-empty and unique".to_owned())
+    );
+    let err = propose(&mut deps, &env, poll(&["one", "two"], 3)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPoll(
+            "max choices must be between 1 and the number of options".to_owned()
+        )
+    );
+
+    // Regular proposals are not voted with poll ballots
+    let proposal_id = propose(
+        &mut deps,
+        &env,
+        ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING1.into()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+    let err = vote_poll(&mut deps, &env, VOTING1, proposal_id, &[0]).unwrap_err();
+    assert_eq!(err, ContractError::NotPoll {});
+}
@@ -0,0 +1,246 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::{RewardWeightsResponse, RewardsResponse};
+use crate::state::{Punishment, RewardWeights};
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+/// Trusted circle with a voter, a non-voting member, and two pending members (only `VOTING1` has
+/// paid its escrow). Rewards are weighted 4 / 2 / 1 for voting / pending paid / non-voting
+fn setup() -> (TgradeDeps, Env) {
+    setup_with_vesting(None)
+}
+
+/// Like `setup`, with rewards vesting over `vesting_period` days
+fn setup_with_vesting(vesting_period: Option<u32>) -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    let msg = InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![NONVOTING1.into()],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: vesting_period,
+        reward_weights: Some(RewardWeights {
+            non_voting: 1,
+            pending_paid: 2,
+            voting: 4,
+        }),
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        msg,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+    assert_escrow_status(
+        &deps,
+        Some(MemberStatus::Voting {}),
+        Some(MemberStatus::PendingPaid {
+            proposal_id: PROPOSAL_ID_1,
+        }),
+        Some(MemberStatus::Pending {
+            proposal_id: PROPOSAL_ID_1,
+        }),
+        None,
+    );
+
+    (deps, later(&env, 10))
+}
+
+/// Sets the contract balance to `escrowed` plus `rewards` (including the not yet withdrawn ones),
+/// and distributes it
+fn distribute(deps: &mut TgradeDeps, env: &Env, escrowed: u128, rewards: u128) {
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(escrowed + rewards, TRUSTED_CIRCLE_DENOM),
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::DistributeRewards {},
+    )
+    .unwrap();
+}
+
+fn withdrawable(deps: &TgradeDeps, env: &Env, owner: &str) -> u128 {
+    let msg = QueryMsg::WithdrawableRewards {
+        owner: owner.to_owned(),
+    };
+    let res: RewardsResponse =
+        from_slice(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    res.rewards.amount.u128()
+}
+
+fn unvested(deps: &TgradeDeps, env: &Env, owner: &str) -> u128 {
+    let msg = QueryMsg::UnvestedRewards {
+        owner: owner.to_owned(),
+    };
+    let res: RewardsResponse =
+        from_slice(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    res.rewards.amount.u128()
+}
+
+fn total_points(deps: &TgradeDeps) -> u64 {
+    let res: RewardWeightsResponse =
+        from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::RewardWeights {}).unwrap()).unwrap();
+    res.total_points
+}
+
+#[test]
+fn rewards_are_weighted_by_tier() {
+    let (mut deps, env) = setup();
+    assert_eq!(total_points(&deps), 4 + 1 + 2);
+
+    distribute(&mut deps, &env, 2 * ESCROW_FUNDS, 700);
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), 400);
+    assert_eq!(withdrawable(&deps, &env, NONVOTING1), 100);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 200);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 0);
+
+    // Non-voting members can withdraw their share
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONVOTING1, &[]),
+        ExecuteMsg::WithdrawRewards {
+            owner: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("amount", "100")));
+
+    // Once the last member pays, the whole batch is promoted
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &escrow_funds()),
+    )
+    .unwrap();
+    assert_escrow_status(
+        &deps,
+        Some(MemberStatus::Voting {}),
+        Some(MemberStatus::Voting {}),
+        Some(MemberStatus::Voting {}),
+        None,
+    );
+    assert_eq!(total_points(&deps), 4 + 1 + 4 + 4);
+
+    distribute(&mut deps, &env, 3 * ESCROW_FUNDS, 600 + 1300);
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), 800);
+    assert_eq!(withdrawable(&deps, &env, NONVOTING1), 100);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 600);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 400);
+}
+
+#[test]
+fn reward_points_follow_status_changes() {
+    let (mut deps, env) = setup();
+    distribute(&mut deps, &env, 2 * ESCROW_FUNDS, 700);
+
+    // Demoted pending paid member stops getting rewards, but keeps what it earned
+    let punishment = Punishment::BurnEscrow {
+        member: VOTING1.into(),
+        slashing_percentage: Decimal::percent(50),
+        kick_out: false,
+    };
+    proposal_punish_members(deps.as_mut(), env.clone(), 2, &[punishment]).unwrap();
+    assert_eq!(total_points(&deps), 4 + 1);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 200);
+
+    // Non-voting member leaving
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONVOTING1, &[]),
+        ExecuteMsg::LeaveTrustedCircle {},
+    )
+    .unwrap();
+    assert_eq!(total_points(&deps), 4);
+
+    // Voting member leaving
+    let env = later(&env, 10);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::LeaveTrustedCircle {},
+    )
+    .unwrap();
+    assert_eq!(total_points(&deps), 0);
+
+    // Non-voting member re-added
+    proposal_add_remove_non_voting_members(
+        deps.as_mut(),
+        env.clone(),
+        vec![NONVOTING1.into()],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(total_points(&deps), 1);
+    distribute(&mut deps, &env, 3 * ESCROW_FUNDS / 2, 700 + 50);
+    assert_eq!(withdrawable(&deps, &env, NONVOTING1), 150);
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), 400);
+}
+
+#[test]
+fn tier_change_keeps_vesting() {
+    let (mut deps, env) = setup_with_vesting(Some(10));
+    distribute(&mut deps, &env, 2 * ESCROW_FUNDS, 700);
+
+    // Half way through the vesting period
+    let env = later(&env, 5 * 86_400);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 100);
+    assert_eq!(unvested(&deps, &env, VOTING1), 100);
+
+    // The pending paid member is promoted, its points going from 2 to 4. The 2 points it had
+    // keep vesting as before, the 2 new ones only get later rewards
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &escrow_funds()),
+    )
+    .unwrap();
+    assert_eq!(total_points(&deps), 4 + 1 + 4 + 4);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 100);
+    assert_eq!(unvested(&deps, &env, VOTING1), 100);
+
+    // Everything vests at the end of the period, and later rewards vest on their own
+    distribute(&mut deps, &env, 3 * ESCROW_FUNDS, 700 + 1300);
+    let env = later(&env, 5 * 86_400);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 200 + 200);
+    assert_eq!(unvested(&deps, &env, VOTING1), 200);
+    let env = later(&env, 5 * 86_400);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 200 + 400);
+    assert_eq!(unvested(&deps, &env, VOTING1), 0);
+}
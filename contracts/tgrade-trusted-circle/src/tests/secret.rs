@@ -0,0 +1,270 @@
+use cosmwasm_std::{to_vec, Binary};
+use cw_utils::Expiration;
+use sha2::{Digest, Sha256};
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::VoteCommitment;
+use crate::state::{SecretRules, UnrevealedVotes};
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+const DAY: u64 = 86_400;
+
+fn instantiate_msg(secret_rules: Option<SecretRules>) -> InstantiateMsg {
+    InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules,
+        guardian: None,
+        draft_period: None,
+    }
+}
+
+/// Trusted circle with four voters, allowing secret proposals with a day long reveal window
+fn setup(unrevealed: UnrevealedVotes) -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    let secret_rules = SecretRules {
+        reveal_period: 24,
+        unrevealed,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(Some(secret_rules)),
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into(), VOTING3.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2, VOTING3] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+
+    (deps, later(&env, 10))
+}
+
+fn propose(deps: &mut TgradeDeps, env: &Env, secret: bool) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Add member".to_owned(),
+        description: "Without anyone knowing who voted for it".to_owned(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING1.into()],
+            remove: vec![],
+        },
+        urgent: false,
+        secret,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(VOTING1, &[]), msg)?;
+    Ok(parse_prop_id(&res.attributes))
+}
+
+fn salt(voter: &str) -> Binary {
+    Binary::from(voter.as_bytes())
+}
+
+fn hash(voter: &str, proposal_id: u64, vote: Vote) -> Binary {
+    let commitment = VoteCommitment {
+        voter: voter.to_owned(),
+        proposal_id,
+        vote,
+        salt: salt(voter),
+    };
+    Binary::from(Sha256::digest(to_vec(&commitment).unwrap()).as_slice())
+}
+
+fn commit(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    voter: &str,
+    proposal_id: u64,
+    vote: Vote,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(voter, &[]),
+        ExecuteMsg::CommitVote {
+            proposal_id,
+            hash: hash(voter, proposal_id, vote),
+        },
+    )
+}
+
+fn reveal(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    voter: &str,
+    proposal_id: u64,
+    vote: Vote,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(voter, &[]),
+        ExecuteMsg::RevealVote {
+            proposal_id,
+            vote,
+            salt: salt(voter),
+        },
+    )
+}
+
+#[test]
+fn secret_votes_are_tallied_once_revealed() {
+    let (mut deps, env) = setup(UnrevealedVotes::Abstain);
+
+    let proposal_id = propose(&mut deps, &env, true).unwrap();
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert!(prop.secret.is_some());
+    assert_eq!(prop.votes.total(), 0);
+    assert_eq!(
+        prop.expires,
+        Expiration::AtTime(
+            env.block
+                .time
+                .plus_seconds(VOTING_PERIOD as u64 * DAY + DAY)
+        )
+    );
+
+    // Votes are only committed
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SecretProposal {});
+    commit(&mut deps, &env, VOTING1, proposal_id, Vote::Yes).unwrap();
+    commit(&mut deps, &env, INIT_ADMIN, proposal_id, Vote::Yes).unwrap();
+    commit(&mut deps, &env, VOTING2, proposal_id, Vote::No).unwrap();
+    let err = commit(&mut deps, &env, VOTING2, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyVoted {});
+    let err = reveal(&mut deps, &env, VOTING1, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(err, ContractError::RevealNotStarted {});
+
+    // And revealed after the voting period
+    let env = later(&env, VOTING_PERIOD as u64 * DAY);
+    let err = commit(&mut deps, &env, VOTING3, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(err, ContractError::CommitPeriodEnded {});
+    let err = reveal(&mut deps, &env, VOTING2, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(err, ContractError::InvalidReveal {});
+    let err = reveal(&mut deps, &env, VOTING3, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(err, ContractError::NoCommitment(proposal_id));
+    reveal(&mut deps, &env, VOTING1, proposal_id, Vote::Yes).unwrap();
+    reveal(&mut deps, &env, INIT_ADMIN, proposal_id, Vote::Yes).unwrap();
+
+    // Not decided before the reveal window ends
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Open);
+    assert_eq!(prop.votes.yes, 2);
+    assert_eq!(prop.votes.abstain, 1);
+    let votes = list_votes_by_proposal(deps.as_ref(), proposal_id, None, None).unwrap();
+    assert_eq!(votes.votes.len(), 2);
+
+    let env = later(&env, DAY);
+    let prop = query_proposal(deps.as_ref(), env, proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Passed);
+}
+
+/// Two yes votes are committed, and only one is revealed
+fn unrevealed_outcome(unrevealed: UnrevealedVotes) -> Status {
+    let (mut deps, env) = setup(unrevealed);
+    let proposal_id = propose(&mut deps, &env, true).unwrap();
+    commit(&mut deps, &env, VOTING1, proposal_id, Vote::Yes).unwrap();
+    commit(&mut deps, &env, VOTING2, proposal_id, Vote::Yes).unwrap();
+
+    let env = later(&env, VOTING_PERIOD as u64 * DAY);
+    reveal(&mut deps, &env, VOTING1, proposal_id, Vote::Yes).unwrap();
+
+    let env = later(&env, DAY);
+    query_proposal(deps.as_ref(), env, proposal_id)
+        .unwrap()
+        .status
+}
+
+#[test]
+fn unrevealed_votes_count_toward_quorum_if_configured() {
+    assert_eq!(unrevealed_outcome(UnrevealedVotes::Abstain), Status::Passed);
+    assert_eq!(
+        unrevealed_outcome(UnrevealedVotes::Ignored),
+        Status::Rejected
+    );
+}
+
+#[test]
+fn secret_proposals_require_secret_rules() {
+    let (mut deps, env) = setup(UnrevealedVotes::Ignored);
+
+    // Regular proposals are not committed
+    let proposal_id = propose(&mut deps, &env, false).unwrap();
+    let err = commit(&mut deps, &env, VOTING2, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(err, ContractError::NotSecretProposal {});
+
+    let proposal_id = propose(&mut deps, &env, true).unwrap();
+    let err = commit(&mut deps, &env, NONMEMBER, proposal_id, Vote::Yes).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Member doesn't have a voting power".to_owned())
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &[]),
+        ExecuteMsg::CommitVote {
+            proposal_id,
+            hash: Binary::from(b"yes"),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCommitment {});
+
+    let mut deps = mock_deps_tgrade();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(None),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Propose {
+        title: "Add member".to_owned(),
+        description: "Secretly".to_owned(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING1.into()],
+            remove: vec![],
+        },
+        urgent: false,
+        secret: true,
+    };
+    let err = execute(deps.as_mut(), env, mock_info(INIT_ADMIN, &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::SecretNotAllowed {});
+}
@@ -0,0 +1,267 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use cosmwasm_std::{to_vec, Addr, Binary};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::{SignedVote, VoteSignDoc, VotingKeyResponse};
+use crate::state::VOTING_KEYS;
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32]).unwrap()
+}
+
+/// Trusted circle with four voters, `VOTING1` and `VOTING2` having registered their voting keys,
+/// and an open proposal, voted only by its proposer
+fn setup() -> (TgradeDeps, Env, u64) {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into(), VOTING3.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2, VOTING3] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+    for (voter, seed) in [(VOTING1, 1), (VOTING2, 2)] {
+        register_key(&mut deps, voter, &key(seed)).unwrap();
+    }
+
+    let env = later(&env, 10);
+    let msg = ExecuteMsg::Propose {
+        title: "Add member".to_owned(),
+        description: "Non-voting".to_owned(),
+        proposal: ProposalContent::AddRemoveNonVotingMembers {
+            add: vec![NONVOTING1.into()],
+            remove: vec![],
+        },
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+    let proposal_id = parse_prop_id(&res.attributes);
+
+    (deps, env, proposal_id)
+}
+
+fn register_key(
+    deps: &mut TgradeDeps,
+    member: &str,
+    key: &SigningKey,
+) -> Result<Response, ContractError> {
+    let pubkey = Binary::from(key.verifying_key().to_bytes().as_slice());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(member, &[]),
+        ExecuteMsg::RegisterVotingKey { pubkey },
+    )
+}
+
+fn sign(
+    env: &Env,
+    key: &SigningKey,
+    voter: &str,
+    proposal_id: u64,
+    vote: Vote,
+    nonce: u64,
+) -> SignedVote {
+    let doc = VoteSignDoc {
+        chain_id: env.block.chain_id.clone(),
+        contract: MOCK_CONTRACT_ADDR.to_owned(),
+        voter: voter.to_owned(),
+        proposal_id,
+        vote,
+        nonce,
+    };
+    let signature: Signature = key.sign(&to_vec(&doc).unwrap());
+    SignedVote {
+        voter: voter.to_owned(),
+        proposal_id,
+        vote,
+        nonce,
+        signature: Binary::from(signature.as_ref()),
+    }
+}
+
+fn submit(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    votes: Vec<SignedVote>,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::SubmitSignedVotes { votes },
+    )
+}
+
+fn voting_key(deps: &TgradeDeps, member: &str) -> VotingKeyResponse {
+    let msg = QueryMsg::VotingKey {
+        member: member.to_owned(),
+    };
+    from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn relayed_votes_are_tallied() {
+    let (mut deps, env, proposal_id) = setup();
+
+    let vote = sign(&env, &key(1), VOTING1, proposal_id, Vote::Yes, 0);
+    let res = submit(&mut deps, &env, vec![vote]).unwrap();
+    assert_eq!(res.events.len(), 1);
+    assert!(res.events[0]
+        .attributes
+        .contains(&attr("status", format!("{:?}", Status::Open))));
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Open);
+    assert_eq!(voting_key(&deps, VOTING1).nonce, 1);
+
+    let vote = sign(&env, &key(2), VOTING2, proposal_id, Vote::Yes, 0);
+    submit(&mut deps, &env, vec![vote]).unwrap();
+    let prop = query_proposal(deps.as_ref(), env, proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Passed);
+}
+
+#[test]
+fn votes_are_submitted_in_batches() {
+    let (mut deps, env, proposal_id) = setup();
+
+    let votes = vec![
+        sign(&env, &key(1), VOTING1, proposal_id, Vote::Yes, 0),
+        sign(&env, &key(2), VOTING2, proposal_id, Vote::No, 0),
+        sign(&env, &key(1), VOTING1, PROPOSAL_ID_1 + 5, Vote::Yes, 1),
+    ];
+    let err = submit(&mut deps, &env, votes.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    let (mut deps, _, _) = setup();
+    let res = submit(&mut deps, &env, votes[..2].to_vec()).unwrap();
+    assert_eq!(res.events.len(), 2);
+    let prop = query_proposal(deps.as_ref(), env, proposal_id).unwrap();
+    assert_eq!(prop.votes.no, 1);
+}
+
+#[test]
+fn signed_votes_cannot_be_replayed() {
+    let (mut deps, env, proposal_id) = setup();
+
+    let vote = sign(&env, &key(1), VOTING1, proposal_id, Vote::Yes, 0);
+    submit(&mut deps, &env, vec![vote.clone()]).unwrap();
+    let err = submit(&mut deps, &env, vec![vote]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidVoteNonce {
+            voter: VOTING1.to_owned(),
+            nonce: 0,
+            expected: 1,
+        }
+    );
+
+    // Even on another contract or chain
+    let mut env = env;
+    env.block.chain_id = "other-chain".to_owned();
+    let vote = sign(&env, &key(2), VOTING2, proposal_id, Vote::Yes, 0);
+    let err = submit(&mut deps, &mock_env(), vec![vote]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteSignature(VOTING2.to_owned()));
+}
+
+#[test]
+fn signature_must_match_voter_key() {
+    let (mut deps, env, proposal_id) = setup();
+
+    // Signed by someone else
+    let vote = sign(&env, &key(2), VOTING1, proposal_id, Vote::Yes, 0);
+    let err = submit(&mut deps, &env, vec![vote]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteSignature(VOTING1.to_owned()));
+
+    // Tampered with
+    let mut vote = sign(&env, &key(1), VOTING1, proposal_id, Vote::Yes, 0);
+    vote.vote = Vote::No;
+    let err = submit(&mut deps, &env, vec![vote]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteSignature(VOTING1.to_owned()));
+
+    // Without a registered key
+    let vote = sign(&env, &key(3), VOTING3, proposal_id, Vote::Yes, 0);
+    let err = submit(&mut deps, &env, vec![vote]).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingKey(VOTING3.to_owned()));
+
+    let err = submit(&mut deps, &env, vec![]).unwrap_err();
+    assert_eq!(err, ContractError::NoSignedVotes {});
+}
+
+#[test]
+fn only_members_register_valid_keys() {
+    let (mut deps, _, _) = setup();
+
+    let err = register_key(&mut deps, NONMEMBER, &key(4)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Only members can register a voting key".to_owned())
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VOTING3, &[]),
+        ExecuteMsg::RegisterVotingKey {
+            pubkey: Binary::from([5u8; 33]),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidVotingKey {});
+
+    // Keys can be replaced
+    register_key(&mut deps, VOTING1, &key(4)).unwrap();
+    let pubkey = key(4).verifying_key().to_bytes();
+    assert_eq!(
+        voting_key(&deps, VOTING1).pubkey,
+        Some(Binary::from(pubkey.as_slice()))
+    );
+    assert_eq!(voting_key(&deps, VOTING3).pubkey, None);
+}
+
+#[test]
+fn voting_keys_are_not_shared() {
+    let (mut deps, _, _) = setup();
+
+    let err = register_key(&mut deps, VOTING3, &key(1)).unwrap_err();
+    assert_eq!(err, ContractError::VotingKeyTaken {});
+    register_key(&mut deps, VOTING1, &key(1)).unwrap();
+
+    // Replaced keys can be registered by others
+    register_key(&mut deps, VOTING1, &key(4)).unwrap();
+    register_key(&mut deps, VOTING3, &key(1)).unwrap();
+    let err = register_key(&mut deps, VOTING2, &key(4)).unwrap_err();
+    assert_eq!(err, ContractError::VotingKeyTaken {});
+}
+
+#[test]
+fn signed_votes_are_bound_to_voter() {
+    let (mut deps, env, proposal_id) = setup();
+
+    // A key shared before keys had to be unique
+    let pubkey = Binary::from(key(1).verifying_key().to_bytes().as_slice());
+    VOTING_KEYS
+        .save(&mut deps.storage, &Addr::unchecked(VOTING3), &pubkey)
+        .unwrap();
+
+    let mut vote = sign(&env, &key(1), VOTING1, proposal_id, Vote::Yes, 0);
+    vote.voter = VOTING3.to_owned();
+    let err = submit(&mut deps, &env, vec![vote]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteSignature(VOTING3.to_owned()));
+}
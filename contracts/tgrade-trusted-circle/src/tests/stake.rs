@@ -0,0 +1,301 @@
+use cosmwasm_std::{coins, Decimal, Uint128};
+
+use super::suite::{Suite, SuiteBuilder};
+use crate::error::ContractError;
+use crate::msg::EscrowClaim;
+use crate::state::{MemberStatus, ProposalContent, Punishment};
+
+const MEMBER: &str = "member";
+const UNBONDING_PERIOD: u64 = 1_000;
+
+/// Trusted circle bonding its escrow, with the owner and `MEMBER` as voters. `MEMBER` paid in
+/// 500_000 more than the required escrow, and the owner has 500_000 left
+fn setup() -> Suite {
+    let mut suite = SuiteBuilder::new()
+        .with_stake(UNBONDING_PERIOD)
+        .with_funds(MEMBER, 1_500_000)
+        .with_funds("owner", 500_000)
+        .build();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    let msg = client
+        .propose(
+            "Add member",
+            "",
+            ProposalContent::AddVotingMembers {
+                voters: vec![MEMBER.to_owned()],
+            },
+            false,
+            false,
+        )
+        .unwrap();
+    suite.execute(&owner, msg).unwrap();
+    suite.execute(&owner, client.execute(1).unwrap()).unwrap();
+    let msg = client.deposit_escrow(coins(1_500_000, "utgd")).unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+    suite
+}
+
+/// Passes and executes a proposal punishing `MEMBER`
+fn punish_member(suite: &mut Suite, punishment: Punishment) {
+    let owner = suite.owner();
+    let client = suite.client();
+    let msg = client
+        .propose(
+            "Punish member",
+            "",
+            ProposalContent::PunishMembers(vec![punishment]),
+            false,
+            false,
+        )
+        .unwrap();
+    suite.execute(&owner, msg).unwrap();
+    let proposal_id = client.list_proposals(&suite.querier(), None, None).unwrap()[1].id;
+    suite
+        .execute(&owner, client.execute(proposal_id).unwrap())
+        .unwrap();
+}
+
+/// The owner pays in and returns 500_000 of escrow, which stays unbonding
+fn unbond_owner_escrow(suite: &mut Suite) {
+    let owner = suite.owner();
+    let client = suite.client();
+    let msg = client.deposit_escrow(coins(500_000, "utgd")).unwrap();
+    suite.execute(&owner, msg).unwrap();
+    suite
+        .execute(&owner, client.return_escrow().unwrap())
+        .unwrap();
+}
+
+#[test]
+fn escrow_is_bonded() {
+    let suite = setup();
+    let client = suite.client();
+
+    let trusted_circle = client.trusted_circle(&suite.querier()).unwrap();
+    assert!(trusted_circle.stake_contract.is_some());
+    assert_eq!(suite.staked(), 2_500_000);
+    assert_eq!(suite.balance(client.addr().as_str()), 0);
+}
+
+#[test]
+fn returned_escrow_is_claimable_once_unbonded() {
+    let mut suite = setup();
+    let client = suite.client();
+
+    suite
+        .execute(MEMBER, client.return_escrow().unwrap())
+        .unwrap();
+    assert_eq!(suite.staked(), 2_000_000);
+    let claims = client.escrow_claims(&suite.querier(), MEMBER).unwrap();
+    assert_eq!(claims.claims.len(), 1);
+    assert_eq!(claims.claims[0].amount, Uint128::new(500_000));
+
+    // Still unbonding
+    let err = suite
+        .execute(MEMBER, client.claim_escrow().unwrap())
+        .unwrap_err();
+    assert_eq!(ContractError::NoEscrowClaims {}, err.downcast().unwrap());
+
+    suite.advance_seconds(UNBONDING_PERIOD);
+    suite
+        .execute(MEMBER, client.claim_escrow().unwrap())
+        .unwrap();
+    assert_eq!(suite.balance(MEMBER), 500_000);
+    assert_eq!(suite.balance(client.addr().as_str()), 0);
+    let claims = client.escrow_claims(&suite.querier(), MEMBER).unwrap();
+    assert_eq!(claims.claims, vec![]);
+}
+
+#[test]
+fn unbonded_escrow_is_not_distributed() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    suite
+        .execute(MEMBER, client.return_escrow().unwrap())
+        .unwrap();
+    unbond_owner_escrow(&mut suite);
+    suite.advance_seconds(UNBONDING_PERIOD);
+
+    // Claiming its own escrow, the member gets the owner one back from the stake contract too
+    suite
+        .execute(MEMBER, client.claim_escrow().unwrap())
+        .unwrap();
+    assert_eq!(suite.balance(MEMBER), 500_000);
+    assert_eq!(suite.balance(client.addr().as_str()), 500_000);
+
+    let msg = client.distribute_rewards(coins(100, "utgd")).unwrap();
+    suite.execute(MEMBER, msg).unwrap();
+    let rewards = client
+        .withdrawable_rewards(&suite.querier(), &owner)
+        .unwrap()
+        .amount
+        + client
+            .withdrawable_rewards(&suite.querier(), MEMBER)
+            .unwrap()
+            .amount;
+    assert_eq!(rewards, Uint128::new(100));
+
+    suite
+        .execute(&owner, client.claim_escrow().unwrap())
+        .unwrap();
+    assert_eq!(suite.balance(&owner), 500_000);
+    assert_eq!(suite.balance(client.addr().as_str()), 100);
+}
+
+#[test]
+fn burning_escrow_unbonds_it() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    unbond_owner_escrow(&mut suite);
+    let claims = client.escrow_claims(&suite.querier(), &owner).unwrap();
+    let release_at = claims.claims[0].release_at;
+
+    punish_member(
+        &mut suite,
+        Punishment::BurnEscrow {
+            member: MEMBER.to_owned(),
+            slashing_percentage: Decimal::percent(50),
+            kick_out: false,
+        },
+    );
+    let escrow = client.escrow(&suite.querier(), MEMBER).unwrap().unwrap();
+    assert_eq!(escrow.paid, Uint128::new(750_000));
+    assert_eq!(suite.staked(), 1_750_000);
+
+    // The escrow unbonding for the owner is left untouched
+    let claims = client.escrow_claims(&suite.querier(), &owner).unwrap();
+    assert_eq!(
+        claims.claims,
+        vec![EscrowClaim {
+            amount: Uint128::new(500_000),
+            release_at,
+        }]
+    );
+    suite.advance_seconds(UNBONDING_PERIOD);
+    suite
+        .execute(&owner, client.claim_escrow().unwrap())
+        .unwrap();
+    assert_eq!(suite.balance(&owner), 500_000);
+    // The burnt escrow came back with the claim, and is burnt by the next distribution
+    assert_eq!(suite.balance(client.addr().as_str()), 750_000);
+
+    let msg = client.distribute_rewards(vec![]).unwrap();
+    suite.execute(&owner, msg).unwrap();
+    assert_eq!(suite.balance(client.addr().as_str()), 0);
+    let rewards = client
+        .withdrawable_rewards(&suite.querier(), &owner)
+        .unwrap();
+    assert_eq!(rewards.amount, Uint128::zero());
+}
+
+#[test]
+fn burning_escrow_keeps_claims_of_leavers() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    // Both the member and the owner have escrow unbonding
+    suite
+        .execute(MEMBER, client.return_escrow().unwrap())
+        .unwrap();
+    unbond_owner_escrow(&mut suite);
+    assert_eq!(suite.staked(), 2_000_000);
+
+    punish_member(
+        &mut suite,
+        Punishment::BurnEscrow {
+            member: MEMBER.to_owned(),
+            slashing_percentage: Decimal::percent(50),
+            kick_out: false,
+        },
+    );
+    assert_eq!(suite.staked(), 1_500_000);
+    for leaver in [MEMBER, owner.as_str()] {
+        let claims = client.escrow_claims(&suite.querier(), leaver).unwrap();
+        assert_eq!(claims.claims.len(), 1);
+        assert_eq!(claims.claims[0].amount, Uint128::new(500_000));
+    }
+
+    suite.advance_seconds(UNBONDING_PERIOD);
+    for leaver in [MEMBER, owner.as_str()] {
+        suite
+            .execute(leaver, client.claim_escrow().unwrap())
+            .unwrap();
+        assert_eq!(suite.balance(leaver), 500_000);
+    }
+    assert_eq!(suite.balance(client.addr().as_str()), 500_000);
+
+    let msg = client.distribute_rewards(vec![]).unwrap();
+    suite.execute(&owner, msg).unwrap();
+    assert_eq!(suite.balance(client.addr().as_str()), 0);
+}
+
+#[test]
+fn rewarded_escrow_is_distributed_once_unbonded() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    punish_member(
+        &mut suite,
+        Punishment::RewardEscrow {
+            member: MEMBER.to_owned(),
+            slashing_percentage: Decimal::percent(50),
+            kick_out: false,
+        },
+    );
+    assert_eq!(suite.staked(), 1_750_000);
+
+    suite.advance_seconds(UNBONDING_PERIOD);
+    // The first call claims the unbonded escrow, the second one distributes it
+    for _ in 0..2 {
+        let msg = client.distribute_rewards(vec![]).unwrap();
+        suite.execute(&owner, msg).unwrap();
+    }
+    // The punished member was demoted, so the owner is the only voter left
+    let rewards = client
+        .withdrawable_rewards(&suite.querier(), &owner)
+        .unwrap();
+    assert_eq!(rewards.amount, Uint128::new(750_000));
+}
+
+#[test]
+fn rewarded_escrow_is_not_shared_with_punished_member() {
+    let mut suite = setup();
+    let owner = suite.owner();
+    let client = suite.client();
+
+    // The member keeps enough escrow to stay a voter
+    punish_member(
+        &mut suite,
+        Punishment::RewardEscrow {
+            member: MEMBER.to_owned(),
+            slashing_percentage: Decimal::percent(20),
+            kick_out: false,
+        },
+    );
+    let escrow = client.escrow(&suite.querier(), MEMBER).unwrap().unwrap();
+    assert_eq!(escrow.paid, Uint128::new(1_200_000));
+    assert_eq!(escrow.status, MemberStatus::Voting {});
+    assert_eq!(suite.staked(), 2_200_000);
+
+    suite.advance_seconds(UNBONDING_PERIOD);
+    for _ in 0..2 {
+        let msg = client.distribute_rewards(vec![]).unwrap();
+        suite.execute(&owner, msg).unwrap();
+    }
+    let rewards = client
+        .withdrawable_rewards(&suite.querier(), &owner)
+        .unwrap();
+    assert_eq!(rewards.amount, Uint128::new(300_000));
+    let rewards = client
+        .withdrawable_rewards(&suite.querier(), MEMBER)
+        .unwrap();
+    assert_eq!(rewards.amount, Uint128::zero());
+}
@@ -1,26 +1,22 @@
 use anyhow::Result as AnyResult;
 
-use cosmwasm_std::{coins, Addr, CosmosMsg, Decimal, Uint128};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, QuerierWrapper, Uint128};
+use cw721::NftInfoResponse;
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, CosmosRouter, Executor};
 use derivative::Derivative;
 use tg4::Member;
 use tg_bindings::{TgradeMsg, TgradeQuery};
 use tg_bindings_test::TgradeApp;
+use tgrade_membership_nft::msg::{Metadata, QueryMsg as NftQueryMsg};
 
 use crate::{
-    msg::{ExecuteMsg, InstantiateMsg},
+    helpers::TgTrustedCircleContract,
+    msg::{ExecuteMsg, InstantiateMsg, MembershipNftInit},
+    multitest::TrustedCircleExt,
     state::ProposalContent,
     tests::TRUSTED_CIRCLE_DENOM,
 };
 
-pub fn contract_trusted_circle() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
-    Box::new(ContractWrapper::new(
-        crate::contract::execute,
-        crate::contract::instantiate,
-        crate::contract::query,
-    ))
-}
-
 pub fn contract_engagement() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
     Box::new(
         ContractWrapper::new(
@@ -32,6 +28,25 @@ pub fn contract_engagement() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
     )
 }
 
+pub fn contract_stake() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    Box::new(
+        ContractWrapper::new(
+            tg4_stake::contract::execute,
+            tg4_stake::contract::instantiate,
+            tg4_stake::contract::query,
+        )
+        .with_sudo(tg4_stake::contract::sudo),
+    )
+}
+
+pub fn contract_membership_nft() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    Box::new(ContractWrapper::new_with_empty(
+        tgrade_membership_nft::contract::execute,
+        tgrade_membership_nft::contract::instantiate,
+        tgrade_membership_nft::contract::query,
+    ))
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Suite {
@@ -40,6 +55,7 @@ pub struct Suite {
     deny_list: Addr,
     contract: Addr,
     owner: Addr,
+    stake: Option<Addr>,
 }
 
 impl Suite {
@@ -47,6 +63,63 @@ impl Suite {
         self.owner.to_string()
     }
 
+    pub fn client(&self) -> TgTrustedCircleContract {
+        TgTrustedCircleContract::new(self.contract.clone())
+    }
+
+    pub fn querier(&self) -> QuerierWrapper<'_, TgradeQuery> {
+        self.app.wrap()
+    }
+
+    pub fn execute(&mut self, sender: &str, msg: CosmosMsg<TgradeMsg>) -> AnyResult<AppResponse> {
+        self.app.execute_circle(sender, msg)
+    }
+
+    pub fn advance_seconds(&mut self, seconds: u64) {
+        self.app.advance_seconds(seconds)
+    }
+
+    pub fn balance(&self, addr: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, TRUSTED_CIRCLE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    /// Escrow bonded by the trusted circle in the stake contract
+    pub fn staked(&self) -> u128 {
+        let stake = self.stake.as_ref().unwrap();
+        let res: tg4_stake::msg::StakedResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                stake,
+                &tg4_stake::msg::QueryMsg::Staked {
+                    address: self.contract.to_string(),
+                },
+            )
+            .unwrap();
+        res.liquid.amount.u128()
+    }
+
+    /// Status in the metadata of the membership token of `addr`, if it holds one
+    pub fn membership_status(&self, addr: &str) -> Option<String> {
+        let nft = self
+            .client()
+            .trusted_circle(&self.querier())
+            .unwrap()
+            .membership_nft
+            .unwrap();
+        let msg = NftQueryMsg::NftInfo {
+            token_id: addr.to_owned(),
+        };
+        let res: Option<NftInfoResponse<Metadata>> =
+            self.app.wrap().query_wasm_smart(nft, &msg).ok();
+        res.map(|info| info.extension.status)
+    }
+
     pub fn propose_modify_non_voting(
         &mut self,
         executor: &str,
@@ -65,6 +138,8 @@ impl Suite {
                 title: title.to_owned(),
                 description: description.to_owned(),
                 proposal: ProposalContent::AddRemoveNonVotingMembers { add, remove },
+                urgent: false,
+                secret: false,
             },
             &[],
         )
@@ -86,6 +161,8 @@ impl Suite {
                 title: title.to_owned(),
                 description: description.to_owned(),
                 proposal: ProposalContent::AddVotingMembers { voters },
+                urgent: false,
+                secret: false,
             },
             &[],
         )
@@ -97,6 +174,9 @@ impl Suite {
 pub struct SuiteBuilder {
     deny_list: Vec<Member>,
     members: Vec<String>,
+    funds: Vec<(String, u128)>,
+    unbonding_period: Option<u64>,
+    membership_nft: bool,
 }
 
 impl SuiteBuilder {
@@ -109,6 +189,23 @@ impl SuiteBuilder {
         self
     }
 
+    pub fn with_funds(mut self, addr: &str, amount: u128) -> Self {
+        self.funds.push((addr.to_owned(), amount));
+        self
+    }
+
+    /// Bonds the escrow in a tg4-stake contract with the given unbonding period (in seconds)
+    pub fn with_stake(mut self, unbonding_period: u64) -> Self {
+        self.unbonding_period = Some(unbonding_period);
+        self
+    }
+
+    /// Mints membership tokens for voting members
+    pub fn with_membership_nft(mut self) -> Self {
+        self.membership_nft = true;
+        self
+    }
+
     pub fn build(self) -> Suite {
         let owner = Addr::unchecked("owner");
         let mut app = TgradeApp::new(owner.as_str());
@@ -126,6 +223,19 @@ impl SuiteBuilder {
                     recipient: owner.to_string(),
                 }),
             )?;
+            for (addr, amount) in &self.funds {
+                router.execute(
+                    api,
+                    storage,
+                    &block_info,
+                    owner.clone(),
+                    CosmosMsg::Custom(TgradeMsg::MintTokens {
+                        denom: "utgd".to_string(),
+                        amount: Uint128::new(*amount),
+                        recipient: addr.clone(),
+                    }),
+                )?;
+            }
             Ok(())
         })
         .unwrap();
@@ -149,11 +259,37 @@ impl SuiteBuilder {
             )
             .unwrap();
 
-        let contract_id = app.store_code(contract_trusted_circle());
-        let contract = app
-            .instantiate_contract(
-                contract_id,
+        let stake = self.unbonding_period.map(|unbonding_period| {
+            let stake_id = app.store_code(contract_stake());
+            app.instantiate_contract(
+                stake_id,
                 owner.clone(),
+                &tg4_stake::msg::InstantiateMsg {
+                    denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+                    tokens_per_point: Uint128::new(1_000),
+                    min_bond: Uint128::new(1_000),
+                    unbonding_period,
+                    admin: Some(owner.to_string()),
+                    preauths_hooks: 0,
+                    preauths_slashing: 0,
+                    auto_return_limit: 0,
+                },
+                &[],
+                "stake",
+                Some(owner.to_string()),
+            )
+            .unwrap()
+        });
+
+        let membership_nft = self.membership_nft.then(|| MembershipNftInit {
+            code_id: app.store_code(contract_membership_nft()),
+            name: "Trusted Circle membership".to_owned(),
+            symbol: "TC".to_owned(),
+        });
+
+        let contract = app
+            .deploy_trusted_circle(
+                owner.as_str(),
                 &InstantiateMsg {
                     name: "Trusted Circle".to_owned(),
                     denom: TRUSTED_CIRCLE_DENOM.to_owned(),
@@ -163,21 +299,32 @@ impl SuiteBuilder {
                     threshold: Decimal::percent(50),
                     allow_end_early: true,
                     initial_members: self.members,
+                    initial_voters: vec![],
+                    importer: None,
+                    stake_contract: stake.as_ref().map(Addr::to_string),
+                    membership_nft,
                     deny_list: Some(deny_list.to_string()),
                     edit_trusted_circle_disabled: false,
                     reward_denom: "utgd".to_owned(),
+                    payment_deadline: None,
+                    remove_expired_pending: false,
+                    reward_vesting_period: None,
+                    reward_weights: None,
+                    urgent_rules: None,
+                    secret_rules: None,
+                    guardian: None,
+                    draft_period: None,
                 },
-                &coins(1_000_000, "utgd"),
-                "trusted-circle",
-                Some(owner.to_string()),
             )
-            .unwrap();
+            .unwrap()
+            .addr();
 
         Suite {
             app,
             deny_list,
             contract,
             owner,
+            stake,
         }
     }
 }
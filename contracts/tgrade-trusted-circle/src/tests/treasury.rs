@@ -0,0 +1,210 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use cosmwasm_std::SubMsg;
+use tg_bindings::TgradeMsg;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::{RewardsResponse, SpendListResponse, TreasuryResponse};
+
+const GRANT_DENOM: &str = "grant";
+const DAY: u64 = 86_400;
+
+/// Trusted circle with a single voter, and 1000 grant tokens in the treasury
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    let env = later(&mock_env(), 10);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &coins(1000, GRANT_DENOM)),
+        ExecuteMsg::FundTreasury {},
+    )
+    .unwrap();
+    (deps, env)
+}
+
+/// Proposes and executes a treasury spend, returning the spend id
+fn spend(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    amount: u128,
+    vesting: Option<u32>,
+) -> Result<(u64, Response), ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Grant".to_owned(),
+        description: "Fund the grantee".to_owned(),
+        proposal: ProposalContent::TreasurySpend {
+            recipient: NONMEMBER.to_owned(),
+            amount: coin(amount, GRANT_DENOM),
+            vesting,
+        },
+        urgent: false,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg)?;
+    let proposal_id = parse_prop_id(&res.attributes);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::Execute { proposal_id },
+    )?;
+    Ok((proposal_id, res))
+}
+
+fn claim(deps: &mut TgradeDeps, env: &Env, sender: &str, spend_id: u64) -> Response {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender, &[]),
+        ExecuteMsg::ClaimSpend { spend_id },
+    )
+    .unwrap()
+}
+
+fn treasury(deps: &TgradeDeps) -> TreasuryResponse {
+    from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Treasury {}).unwrap()).unwrap()
+}
+
+fn list_spends(deps: &TgradeDeps, env: &Env, recipient: Option<&str>) -> SpendListResponse {
+    let msg = QueryMsg::ListSpends {
+        recipient: recipient.map(str::to_owned),
+        start_after: None,
+        limit: None,
+    };
+    from_slice(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
+}
+
+fn payment(amount: u128) -> Vec<SubMsg<TgradeMsg>> {
+    vec![SubMsg::new(BankMsg::Send {
+        to_address: NONMEMBER.to_owned(),
+        amount: coins(amount, GRANT_DENOM),
+    })]
+}
+
+#[test]
+fn vested_spend_is_claimed_over_time() {
+    let (mut deps, env) = setup();
+
+    let (spend_id, res) = spend(&mut deps, &env, 600, Some(10)).unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        treasury(&deps),
+        TreasuryResponse {
+            available: coins(400, GRANT_DENOM),
+            committed: coins(600, GRANT_DENOM),
+        }
+    );
+
+    // Only the recipient can claim
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(INIT_ADMIN, &[]),
+        ExecuteMsg::ClaimSpend { spend_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SpendNotFound(spend_id));
+
+    let env = later(&env, 5 * DAY);
+    let spends = list_spends(&deps, &env, Some(NONMEMBER)).spends;
+    assert_eq!(spends.len(), 1);
+    assert_eq!(spends[0].spend_id, spend_id);
+    assert_eq!(spends[0].claimable, Uint128::new(300));
+    let res = claim(&mut deps, &env, NONMEMBER, spend_id);
+    assert_eq!(res.messages, payment(300));
+    let res = claim(&mut deps, &env, NONMEMBER, spend_id);
+    assert_eq!(res.messages, vec![]);
+
+    let env = later(&env, 10 * DAY);
+    let res = claim(&mut deps, &env, NONMEMBER, spend_id);
+    assert_eq!(res.messages, payment(300));
+    assert_eq!(
+        treasury(&deps),
+        TreasuryResponse {
+            available: coins(400, GRANT_DENOM),
+            committed: vec![],
+        }
+    );
+    assert_eq!(list_spends(&deps, &env, Some(INIT_ADMIN)).spends, vec![]);
+}
+
+#[test]
+fn spend_without_vesting_is_sent_right_away() {
+    let (mut deps, env) = setup();
+
+    let (spend_id, res) = spend(&mut deps, &env, 1000, None).unwrap();
+    assert_eq!(res.messages, payment(1000));
+    assert_eq!(treasury(&deps).available, vec![]);
+    let spends = list_spends(&deps, &env, None).spends;
+    assert_eq!(spends[0].spend_id, spend_id);
+    assert_eq!(spends[0].claimed, Uint128::new(1000));
+    assert_eq!(spends[0].claimable, Uint128::zero());
+
+    // Treasury is empty now
+    let err = spend(&mut deps, &env, 1, None).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TreasuryTooLow {
+            available: coin(0, GRANT_DENOM),
+            requested: coin(1, GRANT_DENOM),
+        }
+    );
+    let err = spend(&mut deps, &env, 0, None).unwrap_err();
+    assert_eq!(err, ContractError::ZeroTreasurySpend {});
+}
+
+#[test]
+fn treasury_is_not_distributed() {
+    let (mut deps, env) = setup();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &coins(500, TRUSTED_CIRCLE_DENOM)),
+        ExecuteMsg::FundTreasury {},
+    )
+    .unwrap();
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(ESCROW_FUNDS + 500 + 100, TRUSTED_CIRCLE_DENOM),
+    );
+
+    let res: RewardsResponse = from_slice(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UndistributedRewards {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rewards, coin(100, TRUSTED_CIRCLE_DENOM));
+
+    // Balance must cover both escrow and treasury
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(ESCROW_FUNDS + 400, TRUSTED_CIRCLE_DENOM),
+    );
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::DistributeRewards {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BalanceTooLow {
+            balance: Uint128::new(ESCROW_FUNDS + 400),
+            reserved: Uint128::new(ESCROW_FUNDS + 500),
+        }
+    );
+}
@@ -2,26 +2,28 @@
 use super::*;
 use std::marker::PhantomData;
 
-use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::testing::{MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    to_binary, Addr, Binary, ContractInfoResponse, ContractResult, Decimal, Deps, Empty,
-    QuerierResult, QueryRequest, StdError, SubMsg, SystemError, SystemResult, WasmQuery,
+    to_binary, Addr, Binary, ContractInfoResponse, ContractResult, Decimal, Deps, Empty, Order,
+    QuerierResult, QueryRequest, StdError, StdResult, SubMsg, SystemError, SystemResult, WasmQuery,
 };
-use cw_storage_plus::Item;
-use tg4::MemberInfo;
+use cw_storage_plus::{Item, Map};
+use tg4::{Member, MemberInfo};
 
-use crate::msg::{InstantiateMsg, RulesResponse};
-use crate::state::{EscrowStatus, Punishment};
+use crate::msg::{
+    InstantiateMsg, MembersAtHeightResponse, ProposalTallyResponse, RewardsResponse, RulesResponse,
+};
+use crate::state::{add_to_batch, EscrowAction, EscrowStatus, Punishment, Votes, ESCROWS};
 use crate::tests::bdd_tests::{
     propose_add_voting_members_and_execute, PROPOSAL_ID_1, PROPOSAL_ID_2,
 };
 use tg_bindings::TgradeQuery;
-use tg_bindings_test::mock_deps_tgrade;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
 
 // Used for the whitelisting test
 pub const TOKEN_CONTRACT: Item<String> = Item::new("contract_info");
 
-struct TokenQuerier {
+pub(super) struct TokenQuerier {
     contract: String,
     storage: MockStorage,
 }
@@ -152,8 +154,17 @@ fn instantiation_enough_funds() {
         },
         deny_list: None,
         edit_trusted_circle_disabled: false,
+        payment_deadline: None,
+        remove_expired_pending: false,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        paused_until: None,
+        draft_period: 30,
+        stake_contract: None,
+        membership_nft: None,
     };
-    let trusted_circle = query_trusted_circle(deps.as_ref()).unwrap();
+    let trusted_circle = query_trusted_circle(deps.as_ref(), mock_env()).unwrap();
     assert_eq!(trusted_circle, expected);
 }
 
@@ -190,6 +201,10 @@ fn test_proposal_validation() {
                 threshold: None,
                 allow_end_early: None,
                 edit_trusted_circle_disabled: None,
+                urgent_rules: None,
+                secret_rules: None,
+                guardian: None,
+                draft_period: None,
             }),
             ContractError::InvalidPendingEscrow(Uint128::zero()),
         ),
@@ -217,6 +232,8 @@ fn add_voting_members_validation() {
         title: "Add zero voting members".to_string(),
         description: "Add voting members validation".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -296,6 +313,73 @@ fn test_add_voting_members_overlapping_batches() {
     );
 }
 
+#[test]
+fn promoted_members_only_get_later_rewards() {
+    let mut deps = mock_deps_tgrade();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+    let env = mock_env();
+    let set_balance = |deps: &mut TgradeDeps, amount: u128| {
+        let funds = coins(amount, TRUSTED_CIRCLE_DENOM);
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, funds);
+    };
+    let distribute = |deps: &mut TgradeDeps| {
+        let msg = ExecuteMsg::DistributeRewards {};
+        execute(deps.as_mut(), env.clone(), mock_info(NONMEMBER, &[]), msg).unwrap();
+    };
+    let withdrawable = |deps: &TgradeDeps, owner: &str| {
+        let msg = QueryMsg::WithdrawableRewards {
+            owner: owner.to_owned(),
+        };
+        let raw = query(deps.as_ref(), env.clone(), msg).unwrap();
+        from_slice::<RewardsResponse>(&raw)
+            .unwrap()
+            .rewards
+            .amount
+            .u128()
+    };
+    set_balance(&mut deps, ESCROW_FUNDS + 900);
+    distribute(&mut deps);
+    assert_eq!(withdrawable(&deps, INIT_ADMIN), 900);
+
+    // A batch promoted early, as everybody paid
+    let add = vec![VOTING1.into(), VOTING2.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2] {
+        let info = mock_info(voter, &escrow_funds());
+        execute_deposit_escrow(deps.as_mut(), env.clone(), info).unwrap();
+    }
+    // And a batch promoted when its grace period ends, with a member paying afterwards
+    let add = vec![VOTING3.into(), NONVOTING1.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_2, add).unwrap();
+    let info = mock_info(VOTING3, &escrow_funds());
+    execute_deposit_escrow(deps.as_mut(), env.clone(), info).unwrap();
+    let env = later(&env, 14 * 86_400 + 1);
+    let info = mock_info(NONMEMBER, &[]);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::CheckPending {},
+    )
+    .unwrap();
+    let info = mock_info(NONVOTING1, &escrow_funds());
+    execute_deposit_escrow(deps.as_mut(), env, info).unwrap();
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 5);
+
+    // Promoted members get their points once, and no earlier rewards
+    let promoted = [VOTING1, VOTING2, VOTING3, NONVOTING1];
+    for voter in promoted {
+        assert_eq!(withdrawable(&deps, voter), 0);
+    }
+    set_balance(&mut deps, 5 * ESCROW_FUNDS + 900 + 1500);
+    distribute(&mut deps);
+    assert_eq!(withdrawable(&deps, INIT_ADMIN), 1200);
+    for voter in promoted {
+        assert_eq!(withdrawable(&deps, voter), 300);
+    }
+}
+
 #[test]
 fn test_escrows() {
     let mut deps = mock_deps_tgrade();
@@ -344,6 +428,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: voting_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING1.into(),
@@ -351,6 +436,7 @@ fn test_escrows() {
                     paid: Uint128::new(0),
                     status: pending_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING2.into(),
@@ -358,6 +444,7 @@ fn test_escrows() {
                     paid: Uint128::new(0),
                     status: pending_status,
                 },
+                payment_deadline: None,
             },
         ],
     );
@@ -387,6 +474,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: voting_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING1.into(),
@@ -394,6 +482,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: paid_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING2.into(),
@@ -401,6 +490,7 @@ fn test_escrows() {
                     paid: Uint128::new(0),
                     status: pending_status,
                 },
+                payment_deadline: None,
             },
         ],
     );
@@ -436,6 +526,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: voting_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING1.into(),
@@ -443,6 +534,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: paid_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING2.into(),
@@ -450,6 +542,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS - 1),
                     status: pending_status,
                 },
+                payment_deadline: None,
             },
         ],
     );
@@ -487,6 +580,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: voting_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING1.into(),
@@ -494,6 +588,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: voting_status,
                 },
+                payment_deadline: None,
             },
             Escrow {
                 addr: VOTING2.into(),
@@ -501,6 +596,7 @@ fn test_escrows() {
                     paid: Uint128::new(ESCROW_FUNDS),
                     status: voting_status,
                 },
+                payment_deadline: None,
             },
         ],
     );
@@ -621,6 +717,218 @@ fn test_escrows() {
     )
 }
 
+fn instantiate_with_payment_deadline(deps: DepsMut<TgradeQuery>, remove_expired_pending: bool) {
+    let msg = InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: "utgd".to_owned(),
+        payment_deadline: Some(7),
+        remove_expired_pending,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    instantiate(
+        deps,
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        msg,
+    )
+    .unwrap();
+}
+
+#[test]
+fn unpaid_pending_members_expire() {
+    for remove_expired_pending in [false, true] {
+        let mut deps = mock_deps_tgrade();
+        instantiate_with_payment_deadline(deps.as_mut(), remove_expired_pending);
+
+        let env = mock_env();
+        let add = vec![VOTING1.into(), VOTING2.into(), VOTING3.into()];
+        proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+
+        // First pays in full, second only a part, third nothing
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(VOTING1, &escrow_funds()),
+        )
+        .unwrap();
+        let partial = coins(ESCROW_FUNDS / 2, TRUSTED_CIRCLE_DENOM);
+        execute_deposit_escrow(deps.as_mut(), env.clone(), mock_info(VOTING2, &partial)).unwrap();
+
+        // Unpaid pending members are marked with their deadline
+        let grace_period = VOTING_PERIOD as u64 * 86_400;
+        let deadline = env.block.time.seconds() + grace_period + 7 * 86_400;
+        let escrows = list_escrows(deps.as_ref(), None, None).unwrap().escrows;
+        let deadlines: Vec<_> = escrows
+            .iter()
+            .map(|e| (e.addr.as_str(), e.payment_deadline))
+            .collect();
+        assert_eq!(
+            deadlines,
+            vec![
+                (VOTING2, Some(deadline)),
+                (INIT_ADMIN, None),
+                (VOTING3, Some(deadline)),
+                (VOTING1, None),
+            ]
+        );
+
+        // After the grace period, the paid member is promoted, the others are still pending
+        let grace_ended = later(&env, grace_period + 1);
+        let res =
+            execute_check_pending(deps.as_mut(), grace_ended, mock_info(NONMEMBER, &[])).unwrap();
+        assert!(res.messages.is_empty());
+        assert_voting(&deps, Some(1), Some(1), Some(0), Some(0), None);
+
+        // Once the deadline is over, no more payments are accepted
+        let expired = later(&env, grace_period + 7 * 86_400);
+        let err = execute_deposit_escrow(
+            deps.as_mut(),
+            expired.clone(),
+            mock_info(VOTING3, &escrow_funds()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::PaymentDeadlinePassed(deadline));
+
+        // And unpaid members are expired, and refunded
+        let res = execute_check_pending(deps.as_mut(), expired.clone(), mock_info(NONMEMBER, &[]))
+            .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: VOTING2.into(),
+                amount: partial,
+            })]
+        );
+        let evt = res
+            .events
+            .iter()
+            .find(|e| e.ty == "pending_expired")
+            .unwrap();
+        assert_eq!(
+            evt.attributes,
+            vec![
+                attr("proposal", PROPOSAL_ID_1.to_string()),
+                attr("member", VOTING2),
+                attr("member", VOTING3),
+                attr("removed", remove_expired_pending.to_string()),
+            ]
+        );
+
+        if remove_expired_pending {
+            assert_voting(&deps, Some(1), Some(1), None, None, None);
+            assert_escrow_status(
+                &deps,
+                Some(MemberStatus::Voting {}),
+                Some(MemberStatus::Voting {}),
+                None,
+                None,
+            );
+        } else {
+            assert_voting(&deps, Some(1), Some(1), Some(0), Some(0), None);
+            assert_escrow_status(
+                &deps,
+                Some(MemberStatus::Voting {}),
+                Some(MemberStatus::Voting {}),
+                Some(MemberStatus::NonVoting {}),
+                Some(MemberStatus::NonVoting {}),
+            );
+            assert_escrow_paid(
+                &deps,
+                Some(ESCROW_FUNDS),
+                Some(ESCROW_FUNDS),
+                Some(0),
+                Some(0),
+            );
+        }
+
+        // Batches are only expired once
+        let res = execute_check_pending(
+            deps.as_mut(),
+            later(&expired, 100),
+            mock_info(NONMEMBER, &[]),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert!(res.events.is_empty());
+    }
+}
+
+#[test]
+fn members_added_to_expired_batch_expire() {
+    let mut deps = mock_deps_tgrade();
+    instantiate_with_payment_deadline(deps.as_mut(), false);
+
+    let env = mock_env();
+    let add = vec![VOTING1.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+
+    // The batch is promoted, and its unpaid member expired once the deadline is over
+    let grace_period = VOTING_PERIOD as u64 * 86_400;
+    let expired = later(&env, grace_period + 7 * 86_400);
+    let info = mock_info(NONMEMBER, &[]);
+    execute_check_pending(deps.as_mut(), expired.clone(), info.clone()).unwrap();
+    assert_escrow_status(
+        &deps,
+        Some(MemberStatus::Voting {}),
+        Some(MemberStatus::NonVoting {}),
+        None,
+        None,
+    );
+
+    // A member demoted into the batch afterwards, as escrow increases enforced over several
+    // calls do
+    let demoted = Addr::unchecked(VOTING2);
+    let escrow = EscrowStatus {
+        paid: Uint128::zero(),
+        status: MemberStatus::Pending {
+            proposal_id: PROPOSAL_ID_1,
+        },
+    };
+    ESCROWS.save(&mut deps.storage, &demoted, &escrow).unwrap();
+    add_to_batch(&mut deps.storage, &expired, PROPOSAL_ID_1, 0, &[demoted]).unwrap();
+
+    // Is expired as well
+    let res = execute_check_pending(deps.as_mut(), later(&expired, 1), info).unwrap();
+    let evt = res
+        .events
+        .iter()
+        .find(|e| e.ty == "pending_expired")
+        .unwrap();
+    assert_eq!(
+        evt.attributes,
+        vec![
+            attr("proposal", PROPOSAL_ID_1.to_string()),
+            attr("member", VOTING2),
+            attr("removed", "false"),
+        ]
+    );
+    assert_escrow_status(
+        &deps,
+        Some(MemberStatus::Voting {}),
+        Some(MemberStatus::NonVoting {}),
+        Some(MemberStatus::NonVoting {}),
+        None,
+    );
+}
+
 #[test]
 fn test_initial_nonvoting_members() {
     let mut deps = mock_deps_tgrade();
@@ -646,6 +954,8 @@ fn update_non_voting_members_validation() {
         title: "Add / remove zero non-voting members".to_string(),
         description: "Update non-voting members validation".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -672,6 +982,8 @@ fn test_update_nonvoting_members() {
         title: "Add participants".to_string(),
         description: "These are my friends, KYC done".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -712,6 +1024,8 @@ fn test_update_nonvoting_members() {
         title: "Update participants".to_string(),
         description: "Typo in one of those addresses...".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     env.block.height += 5;
     let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
@@ -741,7 +1055,8 @@ fn test_update_nonvoting_members() {
             voter: INIT_ADMIN.to_string(),
             vote: Vote::Yes,
             proposal_id,
-            points: 1
+            points: 1,
+            choices: None,
         }]
     );
 
@@ -754,13 +1069,15 @@ fn test_update_nonvoting_members() {
                 voter: INIT_ADMIN.to_string(),
                 vote: Vote::Yes,
                 proposal_id: proposal_id - 1,
-                points: 1
+                points: 1,
+                choices: None,
             },
             VoteInfo {
                 voter: INIT_ADMIN.to_string(),
                 vote: Vote::Yes,
                 proposal_id,
-                points: 1
+                points: 1,
+                choices: None,
             }
         ]
     );
@@ -791,6 +1108,8 @@ fn test_whitelist_contract() {
         title: "Whitelist token address".to_string(),
         description: "This is my trusted token".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -829,6 +1148,8 @@ fn test_whitelist_contract() {
         title: "Remove token address".to_string(),
         description: "This was a trusted token".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -868,7 +1189,9 @@ fn propose_new_voting_rules() {
     let info = mock_info(INIT_ADMIN, &escrow_funds());
     do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
 
-    let rules = query_trusted_circle(deps.as_ref()).unwrap().rules;
+    let rules = query_trusted_circle(deps.as_ref(), mock_env())
+        .unwrap()
+        .rules;
     assert_eq!(
         rules,
         VotingRules {
@@ -888,11 +1211,17 @@ fn propose_new_voting_rules() {
         threshold: Some(Decimal::percent(51)),
         allow_end_early: Some(true),
         edit_trusted_circle_disabled: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     });
     let msg = ExecuteMsg::Propose {
         title: "Streamline voting process".to_string(),
         description: "Make some adjustments".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -929,7 +1258,7 @@ fn propose_new_voting_rules() {
     assert_eq!(res.events.len(), 0);
 
     // check the rules have been updated
-    let trusted_circle = query_trusted_circle(deps.as_ref()).unwrap();
+    let trusted_circle = query_trusted_circle(deps.as_ref(), mock_env()).unwrap();
     assert_eq!(
         trusted_circle.rules,
         VotingRules {
@@ -948,7 +1277,9 @@ fn rules_can_be_frozen_on_instantiation() {
     let info = mock_info(INIT_ADMIN, &escrow_funds());
     do_instantiate(deps.as_mut(), info, vec![], true).unwrap();
 
-    let rules = query_trusted_circle(deps.as_ref()).unwrap().rules;
+    let rules = query_trusted_circle(deps.as_ref(), mock_env())
+        .unwrap()
+        .rules;
     assert_eq!(
         rules,
         VotingRules {
@@ -968,11 +1299,17 @@ fn rules_can_be_frozen_on_instantiation() {
         threshold: Some(Decimal::percent(51)),
         allow_end_early: Some(true),
         edit_trusted_circle_disabled: Some(true),
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     });
     let msg = ExecuteMsg::Propose {
         title: "Streamline voting process".to_string(),
         description: "Make some adjustments".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -986,7 +1323,9 @@ fn rules_can_be_frozen_with_adjustment() {
     let info = mock_info(INIT_ADMIN, &escrow_funds());
     do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
 
-    let rules = query_trusted_circle(deps.as_ref()).unwrap().rules;
+    let rules = query_trusted_circle(deps.as_ref(), mock_env())
+        .unwrap()
+        .rules;
     assert_eq!(
         rules,
         VotingRules {
@@ -1006,11 +1345,17 @@ fn rules_can_be_frozen_with_adjustment() {
         threshold: Some(Decimal::percent(51)),
         allow_end_early: Some(true),
         edit_trusted_circle_disabled: Some(true),
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     });
     let msg = ExecuteMsg::Propose {
         title: "Streamline voting process".to_string(),
         description: "Make some adjustments".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1040,11 +1385,17 @@ fn rules_can_be_frozen_with_adjustment() {
         threshold: Some(Decimal::percent(41)),
         allow_end_early: None,
         edit_trusted_circle_disabled: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     });
     let msg = ExecuteMsg::Propose {
         title: "Streamline voting process".to_string(),
         description: "Make some adjustments".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1061,6 +1412,8 @@ fn rules_can_be_frozen_with_adjustment() {
         title: "Add participants".to_string(),
         description: "These are my friends, KYC done".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1079,7 +1432,9 @@ fn propose_new_voting_rules_validation() {
     let info = mock_info(INIT_ADMIN, &escrow_funds());
     do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
 
-    let rules = query_trusted_circle(deps.as_ref()).unwrap().rules;
+    let rules = query_trusted_circle(deps.as_ref(), mock_env())
+        .unwrap()
+        .rules;
     assert_eq!(
         rules,
         VotingRules {
@@ -1099,11 +1454,17 @@ fn propose_new_voting_rules_validation() {
         threshold: None,
         allow_end_early: None,
         edit_trusted_circle_disabled: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     });
     let msg = ExecuteMsg::Propose {
         title: "Streamline voting process".to_string(),
         description: "Make some adjustments".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1133,6 +1494,364 @@ fn raw_queries_work() {
     assert_eq!(None, member3_raw);
 }
 
+#[test]
+fn members_and_total_points_at_height() {
+    let mut deps = mock_deps_tgrade();
+    let env = mock_env();
+    let start = env.block.height;
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![NONVOTING1.into()], false).unwrap();
+
+    // VOTING1 becomes a voter two blocks later
+    proposal_add_voting_members(deps.as_mut(), env.clone(), 1, vec![VOTING1.into()]).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        later(&env, 10),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 2);
+
+    // Later on, the admin and the non-voting member leave
+    let leave = ExecuteMsg::LeaveTrustedCircle {};
+    execute(
+        deps.as_mut(),
+        later(&env, 100),
+        mock_info(INIT_ADMIN, &[]),
+        leave.clone(),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        later(&env, 100),
+        mock_info(NONVOTING1, &[]),
+        leave,
+    )
+    .unwrap();
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 1);
+    assert_eq!(
+        list_members(deps.as_ref(), None, None)
+            .unwrap()
+            .members
+            .len(),
+        2
+    );
+
+    // Totals at the beginning of every height
+    let total_at = |height| {
+        query_total_points_at_height(deps.as_ref(), height)
+            .unwrap()
+            .points
+    };
+    assert_eq!(total_at(start - 1), 0);
+    assert_eq!(total_at(start + 1), 1);
+    assert_eq!(total_at(start + 3), 2);
+    assert_eq!(total_at(start + 21), 1);
+
+    // Members before the leaves, including the one removed since then
+    let member = |addr: &str, points| Member {
+        addr: addr.into(),
+        points,
+        start_height: None,
+    };
+    let members = list_members_at_height(deps.as_ref(), start + 3, None, None, false).unwrap();
+    assert_eq!(
+        members.members,
+        vec![
+            member(NONVOTING1, 0),
+            member(INIT_ADMIN, 1),
+            member(VOTING1, 1)
+        ]
+    );
+    let voters = list_members_at_height(deps.as_ref(), start + 3, None, None, true).unwrap();
+    assert_eq!(
+        voters.members,
+        vec![member(INIT_ADMIN, 1), member(VOTING1, 1)]
+    );
+    // Before VOTING1 paid in
+    let voters = list_members_at_height(deps.as_ref(), start + 1, None, None, true).unwrap();
+    assert_eq!(voters.members, vec![member(INIT_ADMIN, 1)]);
+
+    // Pagination
+    let members = list_members_at_height(
+        deps.as_ref(),
+        start + 3,
+        Some(NONVOTING1.into()),
+        Some(1),
+        false,
+    )
+    .unwrap();
+    assert_eq!(members.members, vec![member(INIT_ADMIN, 1)]);
+    // VOTING1 changed twice (added, then paid in), and is still listed once
+    let page = |start_after: &str| {
+        list_members_at_height(
+            deps.as_ref(),
+            start + 3,
+            Some(start_after.into()),
+            Some(1),
+            false,
+        )
+        .unwrap()
+        .members
+    };
+    assert_eq!(page(INIT_ADMIN), vec![member(VOTING1, 1)]);
+    assert_eq!(page(VOTING1), vec![]);
+}
+
+#[test]
+fn voters_at_height_checks_bounded_members() {
+    let mut deps = mock_deps_tgrade();
+    let env = mock_env();
+    // Non-voting members between the admin and VOTING1
+    let non_voting = (0..12).map(|i| format!("mem{:02}", i)).collect();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, non_voting, false).unwrap();
+    let add = vec![VOTING1.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+
+    let page = |start_after: Option<&str>| {
+        let start_after = start_after.map(String::from);
+        let height = env.block.height + 1;
+        list_members_at_height(deps.as_ref(), height, start_after, Some(1), true).unwrap()
+    };
+    let voter = |addr: &str| Member {
+        addr: addr.into(),
+        points: 1,
+        start_height: None,
+    };
+    assert_eq!(
+        page(None),
+        MembersAtHeightResponse {
+            members: vec![voter(INIT_ADMIN)],
+            next_start_after: Some(INIT_ADMIN.into()),
+        }
+    );
+    // At most 10 members are checked for a single voter
+    assert_eq!(
+        page(Some(INIT_ADMIN)),
+        MembersAtHeightResponse {
+            members: vec![],
+            next_start_after: Some("mem09".into()),
+        }
+    );
+    assert_eq!(
+        page(Some("mem09")),
+        MembersAtHeightResponse {
+            members: vec![voter(VOTING1)],
+            next_start_after: Some(VOTING1.into()),
+        }
+    );
+    assert_eq!(
+        page(Some(VOTING1)),
+        MembersAtHeightResponse {
+            members: vec![],
+            next_start_after: None,
+        }
+    );
+}
+
+#[test]
+fn migration_seeds_total_snapshot() {
+    let mut deps = mock_deps_tgrade();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+
+    // State of a contract from before the total was snapshotted
+    let changelog = crate::state::TOTAL.changelog();
+    let heights: Vec<u64> = changelog
+        .keys(&deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()
+        .unwrap();
+    assert!(!heights.is_empty());
+    for height in heights {
+        changelog.remove(&mut deps.storage, height);
+    }
+    tg_utils::TOTAL.save(&mut deps.storage, &3).unwrap();
+    cw2::set_contract_version(
+        &mut deps.storage,
+        "crates.io:tgrade-trusted_circle",
+        "0.14.0",
+    )
+    .unwrap();
+
+    let env = later(&mock_env(), 100);
+    migrate(deps.as_mut(), env.clone(), Empty {}).unwrap();
+
+    // Heights up to the migration get the total at the migration
+    assert_eq!(
+        query_total_points_at_height(deps.as_ref(), 1)
+            .unwrap()
+            .points,
+        3
+    );
+    assert_eq!(
+        query_total_points_at_height(deps.as_ref(), env.block.height)
+            .unwrap()
+            .points,
+        3
+    );
+    assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 3);
+}
+
+#[test]
+fn migration_computes_escrow_totals() {
+    let mut deps = mock_deps_tgrade();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+    let add = vec![VOTING1.into()];
+    proposal_add_voting_members(deps.as_mut(), mock_env(), PROPOSAL_ID_1, add).unwrap();
+    let info = mock_info(VOTING1, &coins(ESCROW_FUNDS / 2, TRUSTED_CIRCLE_DENOM));
+    execute_deposit_escrow(deps.as_mut(), mock_env(), info).unwrap();
+
+    // State of a contract from before escrow totals were tracked
+    crate::state::ESCROW_TOTALS.remove(&mut deps.storage, TRUSTED_CIRCLE_DENOM);
+    cw2::set_contract_version(
+        &mut deps.storage,
+        "crates.io:tgrade-trusted_circle",
+        "0.14.0",
+    )
+    .unwrap();
+
+    migrate(deps.as_mut(), mock_env(), Empty {}).unwrap();
+    assert_eq!(
+        crate::state::escrow_total(&deps.storage, TRUSTED_CIRCLE_DENOM).unwrap(),
+        Uint128::new(ESCROW_FUNDS + ESCROW_FUNDS / 2)
+    );
+}
+
+#[test]
+fn escrow_history_records_every_change() {
+    let mut deps = mock_deps_tgrade();
+    let env = mock_env();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+
+    // VOTING1 is added, and pays in two steps (becoming a voter)
+    proposal_add_voting_members(deps.as_mut(), env.clone(), 1, vec![VOTING1.into()]).unwrap();
+    let half = coins(ESCROW_FUNDS / 2, TRUSTED_CIRCLE_DENOM);
+    execute_deposit_escrow(deps.as_mut(), later(&env, 10), mock_info(VOTING1, &half)).unwrap();
+    execute_deposit_escrow(deps.as_mut(), later(&env, 20), mock_info(VOTING1, &half)).unwrap();
+
+    // Pays some extra in, and takes it back
+    execute_deposit_escrow(deps.as_mut(), later(&env, 30), mock_info(VOTING1, &half)).unwrap();
+    execute_return_escrow(deps.as_mut(), later(&env, 40), mock_info(VOTING1, &[])).unwrap();
+
+    // Gets slashed (and so demoted)
+    let punishment = Punishment::BurnEscrow {
+        member: VOTING1.into(),
+        slashing_percentage: Decimal::percent(50),
+        kick_out: false,
+    };
+    proposal_punish_members(deps.as_mut(), later(&env, 50), 2, &[punishment]).unwrap();
+
+    // Leaves, and claims the rest of the escrow
+    execute_leave_trusted_circle(deps.as_mut(), later(&env, 60), mock_info(VOTING1, &[])).unwrap();
+    let claim_env = later(&env, VOTING_PERIOD as u64 * 86_400 * 2 + 60);
+    execute_return_escrow(deps.as_mut(), claim_env, mock_info(VOTING1, &[])).unwrap();
+
+    let history = query_escrow_history(deps.as_ref(), VOTING1.into(), None, None)
+        .unwrap()
+        .entries;
+    let changes: Vec<_> = history
+        .iter()
+        .map(|e| (e.action.clone(), e.paid.u128(), e.status))
+        .collect();
+    let half = ESCROW_FUNDS / 2;
+    let pending = MemberStatus::Pending { proposal_id: 1 };
+    let pending_paid = MemberStatus::PendingPaid { proposal_id: 1 };
+    let claim_at = later(&env, 60).block.time.seconds() + VOTING_PERIOD as u64 * 86_400 * 2;
+    assert_eq!(
+        changes,
+        vec![
+            (EscrowAction::AddVoting { proposal_id: 1 }, 0, Some(pending)),
+            (
+                EscrowAction::Deposit {
+                    amount: Uint128::new(half)
+                },
+                half,
+                Some(pending)
+            ),
+            (
+                EscrowAction::Deposit {
+                    amount: Uint128::new(half)
+                },
+                ESCROW_FUNDS,
+                Some(pending_paid)
+            ),
+            (
+                EscrowAction::Promote {},
+                ESCROW_FUNDS,
+                Some(MemberStatus::Voting {})
+            ),
+            (
+                EscrowAction::Deposit {
+                    amount: Uint128::new(half)
+                },
+                ESCROW_FUNDS + half,
+                Some(MemberStatus::Voting {})
+            ),
+            (
+                EscrowAction::Return {
+                    amount: Uint128::new(half)
+                },
+                ESCROW_FUNDS,
+                Some(MemberStatus::Voting {})
+            ),
+            (
+                EscrowAction::Slash {
+                    proposal_id: 2,
+                    amount: Uint128::new(half)
+                },
+                half,
+                Some(MemberStatus::Voting {})
+            ),
+            (
+                EscrowAction::Demote { proposal_id: 2 },
+                half,
+                Some(MemberStatus::Pending { proposal_id: 2 })
+            ),
+            (
+                EscrowAction::Leave {},
+                half,
+                Some(MemberStatus::Leaving { claim_at })
+            ),
+            (
+                EscrowAction::Return {
+                    amount: Uint128::new(half)
+                },
+                0,
+                None
+            ),
+        ]
+    );
+    assert_eq!(history[1].height, env.block.height + 2);
+    assert_eq!(history[1].time, later(&env, 10).block.time);
+
+    // Paginated, oldest first
+    let page = query_escrow_history(deps.as_ref(), VOTING1.into(), Some(history[2].id), Some(2))
+        .unwrap()
+        .entries;
+    assert_eq!(page, history[3..5].to_vec());
+
+    // Other members have their own history
+    let admin = query_escrow_history(deps.as_ref(), INIT_ADMIN.into(), None, None)
+        .unwrap()
+        .entries;
+    assert_eq!(admin.len(), 1);
+    assert_eq!(
+        admin[0].action,
+        EscrowAction::Deposit {
+            amount: Uint128::new(ESCROW_FUNDS)
+        }
+    );
+}
+
 const VOTING4: &str = "bouncer";
 
 fn create_proposal(deps: DepsMut<TgradeQuery>, delay: u64) -> u64 {
@@ -1144,6 +1863,8 @@ fn create_proposal(deps: DepsMut<TgradeQuery>, delay: u64) -> u64 {
             remove: vec![],
             add: vec!["new guy".into()],
         },
+        urgent: false,
+        secret: false,
     };
     let env = later(&mock_env(), delay);
     let res = execute(deps, env, mock_info(INIT_ADMIN, &[]), msg).unwrap();
@@ -1163,6 +1884,87 @@ fn yes_vote(proposal_id: u64) -> ExecuteMsg {
     }
 }
 
+#[test]
+fn proposal_tally_breakdown() {
+    let mut deps = mock_deps_tgrade();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+
+    // 5 voters, 40% quorum, 60% threshold
+    let voters = [VOTING1, VOTING2, VOTING3, VOTING4];
+    let add = voters.iter().map(|v| v.to_string()).collect();
+    proposal_add_voting_members(deps.as_mut(), mock_env(), PROPOSAL_ID_1, add).unwrap();
+    for voter in voters {
+        execute_deposit_escrow(deps.as_mut(), mock_env(), mock_info(voter, &escrow_funds()))
+            .unwrap();
+    }
+
+    let vote = |deps: DepsMut<TgradeQuery>, voter: &str, proposal_id, vote| {
+        let msg = ExecuteMsg::Vote { proposal_id, vote };
+        execute(deps, later(&mock_env(), 20), mock_info(voter, &[]), msg).unwrap();
+    };
+    let tally = |deps: Deps<TgradeQuery>, proposal_id, delay| {
+        query_proposal_tally(deps, later(&mock_env(), delay), proposal_id).unwrap()
+    };
+
+    // Only the proposer voted, quorum is not reached yet
+    let rejected = create_proposal(deps.as_mut(), 10);
+    let res = tally(deps.as_ref(), rejected, 20);
+    assert_eq!(
+        res,
+        ProposalTallyResponse {
+            id: rejected,
+            status: Status::Open,
+            total_points: 5,
+            votes: Votes::yes(1),
+            quorum_needed: 2,
+            quorum_reached: 1,
+            yes_needed_at_expiry: 1,
+            yes_needed_early: Some(3),
+            uncast_points: 4,
+            early_pass_possible: true,
+            projected_status: Status::Rejected,
+        }
+    );
+
+    // One no vote reaches the quorum, but not the threshold
+    vote(deps.as_mut(), VOTING1, rejected, Vote::No);
+    let res = tally(deps.as_ref(), rejected, 20);
+    assert_eq!(res.quorum_reached, 2);
+    assert_eq!(res.yes_needed_at_expiry, 2);
+    assert!(res.early_pass_possible);
+    assert_eq!(res.projected_status, Status::Rejected);
+
+    // Two more no votes, and there are not enough uncast points left to pass early
+    vote(deps.as_mut(), VOTING2, rejected, Vote::No);
+    vote(deps.as_mut(), VOTING3, rejected, Vote::No);
+    let res = tally(deps.as_ref(), rejected, 20);
+    assert_eq!(res.uncast_points, 1);
+    assert_eq!(res.yes_needed_at_expiry, 3);
+    assert!(!res.early_pass_possible);
+    assert_eq!(res.projected_status, Status::Rejected);
+
+    // A second yes vote is enough for passing at expiry
+    let passed = create_proposal(deps.as_mut(), 10);
+    vote(deps.as_mut(), VOTING1, passed, Vote::Yes);
+    let res = tally(deps.as_ref(), passed, 20);
+    assert_eq!(res.status, Status::Open);
+    assert_eq!(res.projected_status, Status::Passed);
+    // And a third one passes it right away
+    vote(deps.as_mut(), VOTING2, passed, Vote::Yes);
+    let res = tally(deps.as_ref(), passed, 20);
+    assert_eq!(res.status, Status::Passed);
+    assert!(!res.early_pass_possible);
+    assert_eq!(res.projected_status, Status::Passed);
+
+    // Once expired, the first one is rejected
+    let expired = VOTING_PERIOD as u64 * 86_400 + 10;
+    let res = tally(deps.as_ref(), rejected, expired);
+    assert_eq!(res.status, Status::Rejected);
+    assert!(!res.early_pass_possible);
+    assert_eq!(res.projected_status, Status::Rejected);
+}
+
 // Setup:
 // * Create 5 voters
 // * Require 60% threshold, 50% quorum to pass
@@ -1187,9 +1989,21 @@ fn leaving_voter_cannot_vote_anymore() {
         threshold: Decimal::percent(60),
         allow_end_early: true,
         initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
         deny_list: None,
         edit_trusted_circle_disabled: false,
         reward_denom: "utgd".to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
     };
     instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1375,6 +2189,8 @@ fn propose_punish_members_distribution() {
             "Punish VOTING1 with a 50% slashing. Distribute slashed funds among VOTING2 and NONMEMBER"
                 .to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1500,6 +2316,8 @@ fn propose_punish_members_distribution_zero_slash() {
             "Punish VOTING1 with a 0% slashing. Distribute slashed funds among VOTING2 and NONMEMBER"
                 .to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1566,6 +2384,8 @@ fn propose_punish_members_burn() {
         title: "Punish VOTING1".to_string(),
         description: "Punish VOTING1 with a 25% slashing. Burn slashed funds".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1675,6 +2495,8 @@ fn propose_punish_members_burn_zero_slashing() {
         title: "Punish VOTING1".to_string(),
         description: "Punish VOTING1 with a 25% slashing. Burn slashed funds".to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -1694,6 +2516,147 @@ fn propose_punish_members_burn_zero_slashing() {
     assert!(res.messages.is_empty());
 }
 
+#[test]
+fn propose_punish_members_reward() {
+    let mut deps = mock_deps_tgrade();
+    let start = mock_env();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+
+    // Add new members, and all of them pay in, so they are promoted right away
+    let batch1 = vec![VOTING1.into(), VOTING2.into()];
+    let delay1 = 10;
+    propose_add_voting_members_and_execute(
+        deps.as_mut(),
+        later(&start, delay1),
+        INIT_ADMIN,
+        batch1,
+    )
+    .unwrap();
+    for voter in [VOTING1, VOTING2] {
+        let info = mock_info(voter, &escrow_funds());
+        execute_deposit_escrow(deps.as_mut(), later(&start, delay1 + 1), info).unwrap();
+    }
+    assert_voting(&deps, Some(1), Some(1), Some(1), None, None);
+
+    // Make a punish proposal
+    let prop = ProposalContent::PunishMembers(vec![Punishment::RewardEscrow {
+        member: VOTING1.into(),
+        slashing_percentage: Decimal::percent(25),
+        kick_out: false,
+    }]);
+    let msg = ExecuteMsg::Propose {
+        title: "Punish VOTING1".to_string(),
+        description: "Punish VOTING1 with a 25% slashing. Reward slashed funds".to_string(),
+        proposal: prop,
+        urgent: false,
+        secret: false,
+    };
+    let env = later(&start, delay1 + 20);
+    let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+    let proposal_id = parse_prop_id(&res.attributes);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING2, &[]),
+        yes_vote(proposal_id),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONVOTING1, &[]),
+        ExecuteMsg::Execute { proposal_id },
+    )
+    .unwrap();
+    assert_eq!(
+        &res.events[0].attributes[3],
+        &attr("slashed_escrow", "reward")
+    );
+    let slashed = ESCROW_FUNDS / 4;
+    assert!(res.attributes.contains(&attr(
+        "rewarded",
+        coin(slashed, TRUSTED_CIRCLE_DENOM).to_string()
+    )));
+    // Funds are kept in the contract
+    assert!(res.messages.is_empty());
+
+    // VOTING1 is demoted, and slashed funds are shared among the remaining voters only
+    assert_voting(&deps, Some(1), Some(0), Some(1), None, None);
+    let withdrawable = |deps: &OwnedDeps<_, _, _, TgradeQuery>, owner: &str| {
+        let msg = QueryMsg::WithdrawableRewards {
+            owner: owner.to_owned(),
+        };
+        let res: RewardsResponse =
+            from_slice(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        res.rewards.amount.u128()
+    };
+    assert_eq!(withdrawable(&deps, INIT_ADMIN), slashed / 2);
+    assert_eq!(withdrawable(&deps, VOTING1), 0);
+    assert_eq!(withdrawable(&deps, VOTING2), slashed / 2);
+    let msg = QueryMsg::DistributedRewards {};
+    let res: RewardsResponse = from_slice(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.rewards, coin(slashed, TRUSTED_CIRCLE_DENOM));
+}
+
+#[test]
+fn punish_members_reward_requires_reward_denom() {
+    let mut deps = mock_deps_tgrade();
+    let msg = InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: "ureward".to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Propose {
+        title: "Punish INIT_ADMIN".to_string(),
+        description: "Reward slashed funds".to_string(),
+        proposal: ProposalContent::PunishMembers(vec![Punishment::RewardEscrow {
+            member: INIT_ADMIN.into(),
+            slashing_percentage: Decimal::percent(10),
+            kick_out: false,
+        }]),
+        urgent: false,
+        secret: false,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(INIT_ADMIN, &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::EscrowNotRewardable {
+            denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+            reward_denom: "ureward".to_owned(),
+        }
+    );
+}
+
 #[test]
 fn punish_members_validation() {
     let mut deps = mock_deps_tgrade();
@@ -1801,6 +2764,8 @@ fn punish_members_validation() {
             title: "Invalid proposal".to_string(),
             description: "Proposal with invalid / inconsistent information".to_string(),
             proposal: prop.clone(),
+            urgent: false,
+            secret: false,
         };
         let mut env = mock_env();
         env.block.height += 10;
@@ -1862,6 +2827,8 @@ fn propose_punish_members_kick_out() {
             "Punish VOTING1 with a 75% slashing and expulsion. Send slashed funds to VOTING2"
                 .to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let res = execute(
         deps.as_mut(),
@@ -2008,6 +2975,8 @@ fn propose_punish_multiple_members() {
         Punish VOTING1 with a 50% slashing. Burn slashed funds."
             .to_string(),
         proposal: prop,
+        urgent: false,
+        secret: false,
     };
     let mut env = mock_env();
     env.block.height += 10;
@@ -2146,9 +3115,74 @@ fn instantiate_with_default_denom() {
             threshold: Decimal::percent(60),
             allow_end_early: false,
             initial_members: vec![],
+            initial_voters: vec![],
+            importer: None,
+            stake_contract: None,
+            membership_nft: None,
             deny_list: None,
             edit_trusted_circle_disabled: false,
             reward_denom: "osmo".to_owned(),
+            payment_deadline: None,
+            remove_expired_pending: false,
+            reward_vesting_period: None,
+            reward_weights: None,
+            urgent_rules: None,
+            secret_rules: None,
+            guardian: None,
+            draft_period: None,
         }
     );
 }
+
+#[test]
+fn migration_indexes_proposals_expiring_together() {
+    let mut deps = mock_deps_tgrade();
+    let info = mock_info(INIT_ADMIN, &escrow_funds());
+    do_instantiate(deps.as_mut(), info, vec![], false).unwrap();
+    // A second voter, so the proposals stay open
+    let env = mock_env();
+    let add = vec![VOTING1.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    execute_deposit_escrow(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &escrow_funds()),
+    )
+    .unwrap();
+    let env = later(&env, 10);
+    for title in ["First", "Second"] {
+        let msg = ExecuteMsg::Propose {
+            title: title.into(),
+            description: "Expiring together".into(),
+            proposal: ProposalContent::AddRemoveNonVotingMembers {
+                remove: vec![],
+                add: vec![NONVOTING1.into()],
+            },
+            urgent: false,
+            secret: false,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+    }
+
+    // State of a contract from before proposals were indexed by id, where the second proposal
+    // replaced the first one
+    let index = crate::state::PROPOSAL_BY_EXPIRY;
+    let keys: Vec<_> = index
+        .keys(&deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()
+        .unwrap();
+    let expiry = keys[0].0;
+    for key in keys {
+        index.remove(&mut deps.storage, key);
+    }
+    let old_index: Map<u64, u64> = Map::new("proposals_by_expiry");
+    old_index.save(&mut deps.storage, expiry, &2).unwrap();
+
+    migrate(deps.as_mut(), env, Empty {}).unwrap();
+    let keys: Vec<_> = index
+        .keys(&deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()
+        .unwrap();
+    assert_eq!(keys, vec![(expiry, 1), (expiry, 2)]);
+    assert!(old_index.is_empty(&deps.storage));
+}
@@ -0,0 +1,207 @@
+use cw_utils::Expiration;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::state::{Punishment, UrgentRules};
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+const HOUR: u64 = 3600;
+
+fn urgent_rules() -> UrgentRules {
+    UrgentRules {
+        voting_period: 6,
+        quorum: Decimal::percent(60),
+        threshold: Decimal::percent(75),
+        max_proposals: 1,
+        limit_period: 7,
+    }
+}
+
+fn instantiate_msg(urgent_rules: Option<UrgentRules>) -> InstantiateMsg {
+    InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: None,
+        reward_weights: None,
+        urgent_rules,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    }
+}
+
+/// Trusted circle with four voters, allowing urgent proposals
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(Some(urgent_rules())),
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into(), VOTING3.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2, VOTING3] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+    assert_voting(&deps, Some(1), Some(1), Some(1), Some(1), None);
+
+    (deps, later(&env, 10))
+}
+
+fn punish(member: &str) -> ProposalContent {
+    ProposalContent::PunishMembers(vec![Punishment::BurnEscrow {
+        member: member.to_owned(),
+        slashing_percentage: Decimal::percent(50),
+        kick_out: false,
+    }])
+}
+
+fn propose(
+    deps: &mut TgradeDeps,
+    env: &Env,
+    proposer: &str,
+    proposal: ProposalContent,
+) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Compromised key".to_owned(),
+        description: "Slash before it's too late".to_owned(),
+        proposal,
+        urgent: true,
+        secret: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(proposer, &[]), msg)?;
+    Ok(parse_prop_id(&res.attributes))
+}
+
+fn vote_yes(deps: &mut TgradeDeps, env: &Env, voter: &str, proposal_id: u64) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(voter, &[]),
+        ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn urgent_proposal_uses_urgent_rules() {
+    let (mut deps, env) = setup();
+
+    let proposal_id = propose(&mut deps, &env, VOTING1, punish(VOTING3)).unwrap();
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert!(prop.urgent);
+    assert_eq!(
+        prop.expires,
+        Expiration::AtTime(env.block.time.plus_seconds(6 * HOUR))
+    );
+    assert_eq!(prop.rules, urgent_rules().voting_rules());
+    assert_eq!(prop.voting_period_secs, 6 * HOUR);
+
+    // Not decided with half of the votes
+    let env = later(&env, HOUR);
+    vote_yes(&mut deps, &env, INIT_ADMIN, proposal_id);
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Open);
+
+    vote_yes(&mut deps, &env, VOTING2, proposal_id);
+    let prop = query_proposal(deps.as_ref(), env.clone(), proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Passed);
+}
+
+#[test]
+fn undecided_urgent_proposal_expires_in_hours() {
+    let (mut deps, env) = setup();
+
+    let proposal_id = propose(&mut deps, &env, VOTING1, punish(VOTING3)).unwrap();
+    vote_yes(&mut deps, &env, VOTING2, proposal_id);
+
+    let env = later(&env, 6 * HOUR);
+    let prop = query_proposal(deps.as_ref(), env, proposal_id).unwrap();
+    assert_eq!(prop.status, Status::Rejected);
+}
+
+#[test]
+fn only_some_proposals_can_be_urgent() {
+    let (mut deps, env) = setup();
+
+    let add = ProposalContent::AddVotingMembers {
+        voters: vec![NONMEMBER.into()],
+    };
+    let err = propose(&mut deps, &env, VOTING1, add).unwrap_err();
+    assert_eq!(err, ContractError::UrgentNotAllowed {});
+
+    // Circles without urgent rules have no urgent proposals at all
+    let mut deps = mock_deps_tgrade();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(None),
+    )
+    .unwrap();
+    let err = propose(&mut deps, &env, INIT_ADMIN, punish(INIT_ADMIN)).unwrap_err();
+    assert_eq!(err, ContractError::UrgentNotAllowed {});
+}
+
+#[test]
+fn urgent_proposals_are_limited_per_member() {
+    let (mut deps, env) = setup();
+
+    propose(&mut deps, &env, VOTING1, punish(VOTING3)).unwrap();
+    let err = propose(&mut deps, &env, VOTING1, punish(VOTING2)).unwrap_err();
+    assert_eq!(err, ContractError::UrgentLimitReached(1));
+
+    // Other members are not limited by it
+    propose(&mut deps, &env, VOTING2, punish(VOTING3)).unwrap();
+
+    // And it is allowed again after the limit period
+    let env = later(&env, 7 * 86_400);
+    propose(&mut deps, &env, VOTING1, punish(VOTING2)).unwrap();
+}
+
+#[test]
+fn urgent_rules_must_be_stricter() {
+    let mut deps = mock_deps_tgrade();
+    let urgent_rules = UrgentRules {
+        threshold: Decimal::percent(51),
+        ..urgent_rules()
+    };
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        instantiate_msg(Some(urgent_rules)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidUrgentRules("threshold must be at least the regular one".to_owned())
+    );
+}
@@ -0,0 +1,189 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::RewardsResponse;
+use crate::state::Punishment;
+use crate::tests::bdd_tests::PROPOSAL_ID_1;
+
+const VESTING_PERIOD: u32 = 10; // [days]
+const VESTING_SECS: u64 = VESTING_PERIOD as u64 * 86_400;
+
+/// Trusted circle with three voters and 900 rewards distributed, vesting over `VESTING_PERIOD`
+fn setup() -> (TgradeDeps, Env) {
+    let mut deps = mock_deps_tgrade();
+    let msg = InstantiateMsg {
+        name: TRUSTED_CIRCLE_NAME.to_owned(),
+        denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        escrow_amount: Uint128::new(ESCROW_FUNDS),
+        voting_period: VOTING_PERIOD,
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(60),
+        allow_end_early: true,
+        initial_members: vec![],
+        initial_voters: vec![],
+        importer: None,
+        stake_contract: None,
+        membership_nft: None,
+        deny_list: None,
+        edit_trusted_circle_disabled: false,
+        reward_denom: TRUSTED_CIRCLE_DENOM.to_owned(),
+        payment_deadline: None,
+        remove_expired_pending: false,
+        reward_vesting_period: Some(VESTING_PERIOD),
+        reward_weights: None,
+        urgent_rules: None,
+        secret_rules: None,
+        guardian: None,
+        draft_period: None,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        msg,
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let add = vec![VOTING1.into(), VOTING2.into()];
+    proposal_add_voting_members(deps.as_mut(), env.clone(), PROPOSAL_ID_1, add).unwrap();
+    for voter in [VOTING1, VOTING2] {
+        execute_deposit_escrow(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(voter, &escrow_funds()),
+        )
+        .unwrap();
+    }
+
+    let env = later(&env, 10);
+    set_balance(&mut deps, 3 * ESCROW_FUNDS + 900);
+    distribute(&mut deps, &env);
+    (deps, env)
+}
+
+fn set_balance(deps: &mut TgradeDeps, amount: u128) {
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(amount, TRUSTED_CIRCLE_DENOM));
+}
+
+fn distribute(deps: &mut TgradeDeps, env: &Env) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::DistributeRewards {},
+    )
+    .unwrap();
+}
+
+fn rewards(deps: &TgradeDeps, env: &Env, msg: QueryMsg) -> u128 {
+    let res: RewardsResponse =
+        from_slice(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    res.rewards.amount.u128()
+}
+
+fn withdrawable(deps: &TgradeDeps, env: &Env, owner: &str) -> u128 {
+    let msg = QueryMsg::WithdrawableRewards {
+        owner: owner.to_owned(),
+    };
+    rewards(deps, env, msg)
+}
+
+fn unvested(deps: &TgradeDeps, env: &Env, owner: &str) -> u128 {
+    let msg = QueryMsg::UnvestedRewards {
+        owner: owner.to_owned(),
+    };
+    rewards(deps, env, msg)
+}
+
+#[test]
+fn rewards_vest_linearly() {
+    let (mut deps, env) = setup();
+
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 0);
+    assert_eq!(unvested(&deps, &env, VOTING1), 300);
+
+    // Half way through, half of the rewards can be withdrawn
+    let env = later(&env, VESTING_SECS / 2);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 150);
+    assert_eq!(unvested(&deps, &env, VOTING1), 150);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::WithdrawRewards {
+            owner: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("amount", "150")));
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 0);
+
+    // And the rest when the vesting period is over
+    let env = later(&env, VESTING_SECS / 2);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 150);
+    assert_eq!(unvested(&deps, &env, VOTING1), 0);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 300);
+}
+
+#[test]
+fn leaving_member_forfeits_unvested_rewards() {
+    let (mut deps, env) = setup();
+
+    let env = later(&env, VESTING_SECS / 2);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING1, &[]),
+        ExecuteMsg::LeaveTrustedCircle {},
+    )
+    .unwrap();
+    assert_eq!(unvested(&deps, &env, VOTING1), 0);
+
+    // Unvested part goes back to the pool, and is distributed among the remaining voters
+    let res: RewardsResponse = from_slice(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UndistributedRewards {},
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rewards, coin(150, TRUSTED_CIRCLE_DENOM));
+    distribute(&mut deps, &env);
+
+    let env = later(&env, VESTING_SECS);
+    assert_eq!(withdrawable(&deps, &env, INIT_ADMIN), 375);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 375);
+}
+
+#[test]
+fn punished_member_forfeits_unvested_rewards() {
+    let (mut deps, env) = setup();
+
+    // Not even demoted, but unvested rewards are lost
+    let env = later(&env, VESTING_SECS / 2);
+    let punishment = Punishment::BurnEscrow {
+        member: VOTING2.into(),
+        slashing_percentage: Decimal::zero(),
+        kick_out: false,
+    };
+    let res = proposal_punish_members(deps.as_mut(), env.clone(), 2, &[punishment]).unwrap();
+    assert!(res.attributes.contains(&attr(
+        "forfeited",
+        coin(150, TRUSTED_CIRCLE_DENOM).to_string()
+    )));
+    assert_voting(&deps, Some(1), Some(1), Some(1), None, None);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 150);
+    assert_eq!(unvested(&deps, &env, VOTING2), 0);
+
+    // Rewards distributed later are vesting for the punished member as usual
+    distribute(&mut deps, &env);
+    let env = later(&env, VESTING_SECS);
+    assert_eq!(withdrawable(&deps, &env, VOTING2), 200);
+    assert_eq!(withdrawable(&deps, &env, VOTING1), 350);
+}
@@ -0,0 +1,148 @@
+use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+use cosmwasm_std::{Addr, SubMsg};
+use tg_bindings::TgradeMsg;
+use tg_bindings_test::{mock_deps_tgrade, TgradeDeps};
+
+use super::*;
+use crate::msg::DelegatedResponse;
+
+const COLD: &str = INIT_ADMIN;
+const HOT: &str = "hot_wallet";
+const BOT: &str = "operations_bot";
+
+/// Trusted circle with a single voter, who has 100 rewards to withdraw
+fn setup() -> TgradeDeps {
+    let mut deps = mock_deps_tgrade();
+    do_instantiate(
+        deps.as_mut(),
+        mock_info(INIT_ADMIN, &escrow_funds()),
+        vec![],
+        false,
+    )
+    .unwrap();
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        coins(ESCROW_FUNDS + 100, TRUSTED_CIRCLE_DENOM),
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONMEMBER, &[]),
+        ExecuteMsg::DistributeRewards {},
+    )
+    .unwrap();
+    deps
+}
+
+fn withdraw(
+    deps: &mut TgradeDeps,
+    sender: &str,
+    owner: Option<&str>,
+    receiver: Option<&str>,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::WithdrawRewards {
+            owner: owner.map(str::to_owned),
+            receiver: receiver.map(str::to_owned),
+        },
+    )
+}
+
+fn delegate(deps: &mut TgradeDeps, sender: &str, delegate: &str) {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::DelegateWithdrawal {
+            delegate: delegate.to_owned(),
+        },
+    )
+    .unwrap();
+}
+
+fn delegated(deps: &TgradeDeps, owner: &str) -> Addr {
+    let msg = QueryMsg::Delegated {
+        owner: owner.to_owned(),
+    };
+    let res: DelegatedResponse =
+        from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    res.delegated
+}
+
+fn payment(to_addr: &str, amount: u128) -> Vec<SubMsg<TgradeMsg>> {
+    vec![SubMsg::new(BankMsg::Send {
+        to_address: to_addr.to_owned(),
+        amount: coins(amount, TRUSTED_CIRCLE_DENOM),
+    })]
+}
+
+#[test]
+fn withdraw_to_receiver() {
+    let mut deps = setup();
+
+    let res = withdraw(&mut deps, COLD, None, Some(HOT)).unwrap();
+    assert_eq!(res.messages, payment(HOT, 100));
+    assert!(res.attributes.contains(&attr("owner", COLD)));
+    assert!(res.attributes.contains(&attr("receiver", HOT)));
+}
+
+#[test]
+fn delegated_withdrawal() {
+    let mut deps = setup();
+    assert_eq!(delegated(&deps, COLD), Addr::unchecked(COLD));
+
+    // Not delegated yet
+    let err = withdraw(&mut deps, BOT, Some(COLD), None).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized("Sender is neither owner or delegated".to_owned())
+    );
+
+    delegate(&mut deps, COLD, BOT);
+    assert_eq!(delegated(&deps, COLD), Addr::unchecked(BOT));
+
+    // Funds go to the owner by default
+    let res = withdraw(&mut deps, BOT, Some(COLD), None).unwrap();
+    assert_eq!(res.messages, payment(COLD, 100));
+    assert!(res.attributes.contains(&attr("sender", BOT)));
+
+    // Only a single delegate at a time
+    delegate(&mut deps, COLD, HOT);
+    assert_eq!(delegated(&deps, COLD), Addr::unchecked(HOT));
+    withdraw(&mut deps, BOT, Some(COLD), None).unwrap_err();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(COLD, &[]),
+        ExecuteMsg::UndelegateWithdrawal {},
+    )
+    .unwrap();
+    assert_eq!(delegated(&deps, COLD), Addr::unchecked(COLD));
+    withdraw(&mut deps, HOT, Some(COLD), Some(HOT)).unwrap_err();
+}
+
+#[test]
+fn delegate_cannot_withdraw_from_non_voter() {
+    let mut deps = setup();
+    proposal_add_voting_members(
+        deps.as_mut(),
+        mock_env(),
+        crate::tests::bdd_tests::PROPOSAL_ID_1,
+        vec![VOTING1.into()],
+    )
+    .unwrap();
+    delegate(&mut deps, VOTING1, BOT);
+
+    let err = withdraw(&mut deps, BOT, Some(VOTING1), None).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidStatus(MemberStatus::Pending {
+            proposal_id: crate::tests::bdd_tests::PROPOSAL_ID_1
+        })
+    );
+}